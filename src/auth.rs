@@ -1,27 +1,22 @@
-use std::fs;
-use std::path::PathBuf;
-
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use rand::rngs::OsRng;
 
 /// Keypair file format: JSON array of 64 bytes (same as Solana CLI's id.json).
 /// First 32 bytes = secret key, last 32 bytes = public key.
 const KEYPAIR_FILE: &str = "keypair.json";
-const APP_DIR: &str = "anima";
 
 /// Auth message prefix. The signed payload is: "ANIMA_AUTH_v1:{client_id}"
 /// This proves the client controls the Ed25519 private key that generated
 /// the public key from which client_id was derived.
 const AUTH_MESSAGE_PREFIX: &str = "ANIMA_AUTH_v1";
 
-/// Returns the path to ~/.anima/keypair.json (or keypair-{suffix}.json if specified).
-fn keypair_path(suffix: Option<&str>) -> PathBuf {
-    let home = dirs::home_dir().expect("Could not find home directory");
-    let filename = match suffix {
+/// Storage key for the keypair: keypair.json (or keypair-{suffix}.json if specified).
+/// Lives in ~/.anima/ natively, localStorage in the browser (see `storage`).
+fn keypair_key(suffix: Option<&str>) -> String {
+    match suffix {
         Some(s) => format!("keypair-{}.json", s),
         None => KEYPAIR_FILE.to_string(),
-    };
-    home.join(format!(".{}", APP_DIR)).join(filename)
+    }
 }
 
 /// Parse --keypair <suffix> from CLI args.
@@ -35,10 +30,9 @@ pub fn keypair_suffix_from_args() -> Option<String> {
 /// Load an existing keypair from disk, or generate and save a new one.
 /// Returns (signing_key, public_key_bytes).
 pub fn load_or_create_keypair(suffix: Option<&str>) -> (SigningKey, [u8; 32]) {
-    let path = keypair_path(suffix);
+    let key = keypair_key(suffix);
 
-    if path.exists() {
-        let data = fs::read_to_string(&path).expect("Failed to read keypair file");
+    if let Some(data) = crate::storage::read(&key) {
         let bytes: Vec<u8> = serde_json::from_str(&data).expect("Failed to parse keypair JSON");
         assert_eq!(bytes.len(), 64, "Keypair file must be 64 bytes");
 
@@ -48,7 +42,7 @@ pub fn load_or_create_keypair(suffix: Option<&str>) -> (SigningKey, [u8; 32]) {
 
         bevy::log::info!(
             "Loaded keypair from {} — pubkey: {}",
-            path.display(),
+            crate::storage::location(&key),
             pubkey_address(&pubkey)
         );
 
@@ -64,14 +58,11 @@ pub fn load_or_create_keypair(suffix: Option<&str>) -> (SigningKey, [u8; 32]) {
 
         let json = serde_json::to_string(&keypair_bytes).unwrap();
 
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).expect("Failed to create .anima directory");
-        }
-        fs::write(&path, &json).expect("Failed to write keypair file");
+        crate::storage::write(&key, &json).expect("Failed to write keypair file");
 
         bevy::log::info!(
             "Generated new keypair at {} — pubkey: {}",
-            path.display(),
+            crate::storage::location(&key),
             pubkey_address(&pubkey)
         );
 
@@ -209,6 +200,19 @@ impl ClientIdentity {
         }
     }
 
+    /// A fresh identity that is never saved — for throwaway clients like the
+    /// load tester, which would otherwise all share one keypair file.
+    pub fn ephemeral() -> Self {
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let pubkey = signing_key.verifying_key().to_bytes();
+        Self {
+            signing_key,
+            pubkey,
+            client_id: pubkey_to_client_id(&pubkey),
+            address: pubkey_address(&pubkey),
+        }
+    }
+
     /// Sign the auth challenge for this identity.
     /// Returns (pubkey_bytes, signature_bytes) to send to the server.
     pub fn sign_auth(&self) -> ([u8; 32], Vec<u8>) {
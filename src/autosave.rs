@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::{CtfScore, MatchState, Resources};
+
+/// Default match autosave file, next to the lifetime stats in ~/.anima.
+const AUTOSAVE_FILE: &str = "match_autosave.json";
+const APP_DIR: &str = "anima";
+
+/// Restored player entries nobody has claimed this long after the restore
+/// are dropped, so they don't linger in every later autosave.
+pub const PENDING_RESTORE_SECS: f32 = 300.0;
+
+/// Carried items, materials and streak of one player, keyed by client id in the snapshot.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PlayerSnapshot {
+    pub equipped: Option<String>,
+    pub inventory: Vec<String>,
+    pub kill_streak: u32,
+    /// Missing in snapshots written before materials were tracked.
+    #[serde(default)]
+    pub resources: Resources,
+}
+
+/// Where a world item (Equippable) lies.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ItemSnapshot {
+    pub name: String,
+    pub position: Vec3,
+}
+
+/// Everything the autosave restores after a server restart. Lifetime stats
+/// are persisted separately by `StatsStore`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MatchSnapshot {
+    pub match_state: Option<MatchState>,
+    pub ctf_score: Option<CtfScore>,
+    pub players: HashMap<u64, PlayerSnapshot>,
+    pub items: Vec<ItemSnapshot>,
+}
+
+/// Server-only: periodic match autosave. Set with `--autosave <secs>`
+/// (and optionally `--autosave-file <path>`).
+#[derive(Resource, Clone, Debug)]
+pub struct MatchAutosave {
+    pub path: PathBuf,
+    pub interval_secs: f32,
+    /// Player entries from the loaded snapshot not yet handed back — each is
+    /// restored once, when that client id reconnects and passes wallet auth.
+    pub pending_players: HashMap<u64, PlayerSnapshot>,
+    /// Elapsed secs after which unclaimed `pending_players` are dropped.
+    pub pending_until: f32,
+}
+
+impl MatchAutosave {
+    /// Load the snapshot at `self.path`, if there is a readable one.
+    pub fn load(&self) -> Option<MatchSnapshot> {
+        let data = fs::read_to_string(&self.path).ok()?;
+        match serde_json::from_str(&data) {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                warn!("[AUTOSAVE] Failed to parse {}: {} — starting fresh", self.path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Write the snapshot. Goes through a temporary file so a crash mid-write
+    /// never leaves a truncated autosave behind.
+    pub fn save(&self, snapshot: &MatchSnapshot) {
+        if let Some(parent) = self.path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                warn!("[AUTOSAVE] Failed to create {}: {}", parent.display(), e);
+                return;
+            }
+        }
+        let json = match serde_json::to_string_pretty(snapshot) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("[AUTOSAVE] Failed to serialize match state: {}", e);
+                return;
+            }
+        };
+        let tmp = self.path.with_extension("json.tmp");
+        if let Err(e) = fs::write(&tmp, json).and_then(|()| fs::rename(&tmp, &self.path)) {
+            warn!("[AUTOSAVE] Failed to write {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Parse --autosave <secs> and --autosave-file <path> from CLI args.
+/// None when autosave is off, or when there is no --autosave-file and no
+/// home directory to put the default file in.
+pub fn parse_autosave() -> Option<MatchAutosave> {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+    };
+    let interval_secs = value("--autosave")?.parse::<f32>().ok().filter(|v| *v > 0.0)?;
+    let path = match value("--autosave-file") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let Some(home) = dirs::home_dir() else {
+                warn!("[AUTOSAVE] Could not find home directory — pass --autosave-file; autosave is off");
+                return None;
+            };
+            home.join(format!(".{}", APP_DIR)).join(AUTOSAVE_FILE)
+        }
+    };
+    info!("[AUTOSAVE] Saving match state to {} every {}s", path.display(), interval_secs);
+    Some(MatchAutosave {
+        path,
+        interval_secs,
+        pending_players: HashMap::new(),
+        pending_until: 0.0,
+    })
+}
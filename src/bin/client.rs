@@ -16,12 +16,13 @@ use lightyear::prelude::*;
 use multiplayer::player::*;
 use multiplayer::protocol::*;
 use multiplayer::world::{
-    spawn_lights, spawn_world_model, update_view_model, WorldModelCamera, DEFAULT_RENDER_LAYER,
+    spawn_lights, spawn_world_model, update_view_model, animate_view_model_breathing, start_weapon_inspect, animate_weapon_inspect, WorldModelCamera, DEFAULT_RENDER_LAYER,
     interaction_ui_system, init_replicated_doors, init_replicated_equippables,
-    init_replicated_interactables, sync_door_state, sync_equippable_position, sync_equippable_visibility,
-    sync_remote_equipped, spawn_tracer, cleanup_tracers, remote_shot_tracers,
-    start_jab_animation, animate_jab, LeftHand,
+    init_replicated_interactables, init_replicated_trade_stations, sync_door_state, sync_equippable_position, sync_equippable_visibility,
+    sync_remote_equipped, spawn_tracer, cleanup_tracers, remote_shot_tracers, animate_shot_flashes,
+    start_jab_animation, animate_jab, update_laser_sights, update_weapon_spawn_glow, init_replicated_flags, sync_flag_transforms, LeftHand,
 };
+use multiplayer::trade::TRADE_OFFERS;
 use multiplayer::{SharedPlugin, FIXED_TIMESTEP_HZ, PROTOCOL_ID, SERVER_PORT};
 
 // ========================================
@@ -46,6 +47,55 @@ struct AssetLoadTracker {
 #[derive(Component)]
 struct MenuCamera;
 
+/// Marker for the view-model (arms/held item) camera on the local player.
+#[derive(Component)]
+struct ViewModelCamera;
+
+/// Marker for the camera that draws teammate silhouettes over the world.
+/// A child of the world camera, so it shares its view; it starts with a
+/// fresh depth buffer, which is what lets silhouettes show through walls.
+#[derive(Component)]
+struct TeammateOutlineCamera;
+
+/// Marker for a remote player's team-colored silhouette (see
+/// `TeammateOutlineCamera`), shown only for our own team.
+#[derive(Component)]
+struct TeammateSilhouette;
+
+/// Marker for the camera that draws the reduced-resolution 3D image to the
+/// window when the render scale is below 1.0.
+#[derive(Component)]
+struct UpscaleCamera;
+
+/// Offscreen target the 3D cameras render into while the render scale is
+/// below 1.0. `size` is the internal resolution it was created for.
+#[derive(Resource, Default)]
+struct ScaledRenderTarget {
+    image: Option<Handle<Image>>,
+    size: (u32, u32),
+}
+
+/// Client-only LOD state on a remote player: the full body meshes to restore
+/// (standing and crouched) and the billboard shown instead past
+/// `player_lod_distance`.
+#[derive(Component)]
+struct RemotePlayerLod {
+    body_mesh: Handle<Mesh>,
+    crouch_mesh: Handle<Mesh>,
+    billboard: Entity,
+    far: bool,
+    crouching: bool,
+}
+
+/// Marker for a remote player's far-distance billboard.
+#[derive(Component)]
+struct PlayerBillboard;
+
+/// Marker for a remote player's head sphere, shown while they lean so the
+/// part peeking out past the body is visible (it is also what gets hit).
+#[derive(Component)]
+struct LeanHead;
+
 /// Marker: egui fonts have been configured.
 #[derive(Resource)]
 struct EguiFontsReady;
@@ -94,8 +144,57 @@ fn main() {
         tick_duration: Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
     });
     app.add_plugins(SharedPlugin);
+    // Collider wireframes for debugging collision mismatches — hidden until F4
+    app.add_plugins(avian3d::prelude::PhysicsDebugPlugin::default());
+    app.insert_gizmo_config(
+        avian3d::prelude::PhysicsGizmos::default(),
+        GizmoConfig { enabled: false, ..default() },
+    );
     app.init_state::<AppState>();
-    app.insert_resource(CursorState::default());
+    app.init_resource::<CameraRecoil>();
+    // Network IO layer (--transport, default udp)
+    app.insert_resource(multiplayer::transport::transport_from_args());
+    // Mouse sensitivity in cm/360 + DPI (--cm360 / --dpi)
+    let saved_settings = multiplayer::settings::PlayerSettings::load();
+    let player_settings = multiplayer::settings::parse_player_settings(&saved_settings);
+    // Kill slow-mo on the presentation clock only (--hit-stop <secs>)
+    app.insert_resource(HitStop::new(player_settings.hit_stop_secs));
+    // FOV and cursor lock come from the saved settings (~/.anima/settings.json)
+    app.insert_resource(CursorState { locked: player_settings.lock_cursor });
+    app.insert_resource(BaseFov(player_settings.fov_degrees.to_radians()));
+    app.insert_resource(multiplayer::settings::SavedPlayerSettings { saved: saved_settings, started: player_settings.clone() });
+    app.insert_resource(player_settings);
+    app.add_systems(Update, (persist_player_settings, sync_camera_sensitivity));
+    app.register_type::<multiplayer::settings::KeyBindings>();
+    app.insert_resource(multiplayer::settings::parse_key_bindings());
+    app.insert_resource(multiplayer::settings::parse_gamepad_settings());
+    app.init_resource::<Time<Presentation>>();
+    app.add_systems(PreUpdate, advance_presentation_time);
+    app.add_systems(Update, trigger_hit_stop.run_if(in_state(AppState::InGame)));
+    // MSAA / FXAA / TAA and render-resolution scale (--msaa / --aa / --render-scale)
+    app.insert_resource(multiplayer::settings::parse_graphics_settings());
+    // Tab scoreboard sort + columns (--scoreboard-sort / --scoreboard-columns)
+    app.insert_resource(multiplayer::settings::parse_scoreboard_settings());
+    // Heading strip at the top of the HUD (--no-compass / --compass-arc)
+    app.insert_resource(multiplayer::settings::parse_compass_settings());
+    app.insert_resource(multiplayer::settings::parse_crosshair_settings());
+    app.add_systems(Startup, load_crosshair_image);
+    app.init_resource::<ScaledRenderTarget>();
+    // Runaway-spawn watchdog (--entity-warning <count>)
+    app.insert_resource(multiplayer::diagnostics::parse_entity_warning());
+    app.add_systems(Update, multiplayer::diagnostics::check_entity_count);
+    // CSV network metrics (--net-diagnostics <file>)
+    if let Some(export) = multiplayer::diagnostics::parse_net_diagnostics() {
+        multiplayer::diagnostics::add_net_diagnostics_export(&mut app, export);
+    }
+    // Observer/referee mode (--observer-token)
+    let observer_grid = multiplayer::observer::parse_observer_grid();
+    app.insert_resource(ObserverSession {
+        token: multiplayer::observer::parse_observer_token(),
+        grid: observer_grid.is_some(),
+        grid_size: observer_grid.unwrap_or(2),
+        ..default()
+    });
     // One Camera2d in Startup — persists until InGame
     app.add_systems(Startup, setup);
 
@@ -119,32 +218,44 @@ fn main() {
         Update,
         (
             sync_camera_pitch,
+            apply_camera_recoil.after(sync_camera_pitch),
             grab_mouse,
             change_fov,
+            toggle_collider_debug,
+            apply_stance_camera.after(apply_camera_recoil),
             update_view_model,
+            animate_view_model_breathing,
+            start_weapon_inspect,
+            animate_weapon_inspect.after(animate_view_model_breathing),
             interaction_ui_system,
             sync_door_state,
             init_replicated_doors,
             init_replicated_equippables,
             init_replicated_interactables,
+            update_weapon_spawn_glow,
+            init_replicated_flags,
+            apply_graphics_settings,
+            update_remote_player_lod,
         )
             .run_if(in_state(AppState::InGame)),
     );
     app.add_systems(
         Update,
-        (sync_equippable_visibility, sync_equippable_position, sync_remote_equipped)
+        (sync_equippable_visibility, sync_equippable_position, sync_remote_equipped, sync_flag_transforms, sync_remote_lean_heads, update_teammate_silhouettes, multiplayer::utility::sync_thrown_utilities, multiplayer::utility::fade_smoke_clouds)
             .run_if(in_state(AppState::InGame))
             .run_if(not(lightyear::prelude::is_in_rollback)),
     );
     // Leafwing populates `ActionState<PlayerActions>` in `InputManagerSystem::Update`.
-    // We mutate it (rotate Move by yaw, zero Look when cursor unlocked) in
+    // We mutate it (rotate Move by yaw, zero Look when cursor unlocked, scale Look
+    // from mouse counts to radians) in
     // `InputManagerSystem::ManualControl`, which is guaranteed to run after Update.
     // Then `InputSystems::BufferClientInputs` snapshots the ActionState into the
     // input buffer and replicates it to the server — so the server sees the final
     // world-space Move axis directly.
     app.add_systems(
         FixedPreUpdate,
-        (pre_rotate_move_input, gate_look_on_cursor)
+        (gate_input_on_chat, gate_input_while_dead, pre_rotate_move_input, gate_look_on_cursor, apply_look_sensitivity, apply_gamepad_look)
+            .chain()
             .in_set(InputManagerSystem::ManualControl)
             .before(lightyear::prelude::client::input::InputSystems::BufferClientInputs)
             .run_if(not(lightyear::prelude::is_in_rollback))
@@ -153,9 +264,20 @@ fn main() {
 
     app.add_systems(
         Update,
-        (cleanup_tracers, remote_shot_tracers, animate_jab, crosshair_hud, health_hud, inventory_hud, death_screen, kill_feed_ui, build_version_hud, log_health_changes)
+        (cleanup_tracers, animate_shot_flashes, remote_shot_tracers, update_laser_sights, animate_jab, crosshair_hud, health_hud, inventory_hud, death_screen, round_banner, hazard_overlay, kill_feed_ui, profile_panel, build_version_hud, log_health_changes, init_replicated_trade_stations, trade_station_menu, apply_key_bindings, multiplayer::utility::init_replicated_utilities)
             .run_if(in_state(AppState::InGame)),
     );
+    app.add_systems(
+        Update,
+        (revive_hud, pause_banner, observer_hud, ctf_hud, graphics_panel, escape_menu.after(grab_mouse), look_curve_panel, inventory_panel, training_hud, recoil_trainer_hud, nameplate_hud, scoreboard, compass_hud, entity_warning_hud, flash_overlay, utility_hud).run_if(in_state(AppState::InGame)),
+    );
+
+    // Reconnect with backoff when the connection drops
+    app.init_resource::<ConnectionState>();
+    app.add_systems(
+        Update,
+        (watch_connection, connection_banner).run_if(in_state(AppState::InGame)),
+    );
 
     // Wallet auth: send signed proof to server after connection established
     app.add_systems(
@@ -163,10 +285,85 @@ fn main() {
         send_wallet_auth.run_if(in_state(AppState::InGame)),
     );
 
+    // Preferences (auto-equip, ...): sent on connect and when settings change
+    app.add_systems(
+        Update,
+        send_player_preferences.run_if(in_state(AppState::InGame)),
+    );
+    // Movement, ADS and lean rules come from the server, whatever our own CLI flags say
+    app.add_systems(
+        Update,
+        (adopt_server_movement_config, adopt_server_ads_config, adopt_server_lean_config),
+    );
+
+    // Server MOTD + rules, shown on join
+    app.init_resource::<MotdPanel>();
+    app.add_systems(
+        Update,
+        (receive_motd, motd_panel)
+            .chain()
+            .run_if(in_state(AppState::InGame)),
+    );
+
+    // Chat: Enter opens the box, Enter sends, Escape cancels
+    app.init_resource::<ChatLog>();
+    app.init_resource::<ChatFocus>();
+    // Quick-chat wheel: hold T, point with the mouse, release (--quick-chat)
+    app.insert_resource(multiplayer::settings::parse_quick_chat_settings());
+    app.init_resource::<QuickChatWheel>();
+    app.add_systems(
+        Update,
+        (receive_chat, chat_ui.before(motd_panel), quick_chat_wheel).run_if(in_state(AppState::InGame)),
+    );
+
+    // Team swap: "/team" in chat asks the server, which answers either way
+    app.init_resource::<TeamSwapNotice>();
+    app.add_systems(Update, team_swap_notice.run_if(in_state(AppState::InGame)));
+    app.init_resource::<AfkNotice>();
+    app.add_systems(Update, afk_notice.run_if(in_state(AppState::InGame)));
+
+    // Observer mode: token login, spectator camera, referee hotkeys
+    app.add_systems(
+        Update,
+        (send_observer_login, receive_observer_status, observer_camera, observer_grid, observer_admin_keys)
+            .chain()
+            .run_if(in_state(AppState::InGame)),
+    );
+
+    // Highlight clips: F8 (or a multi-kill with --auto-clip) saves the last
+    // few seconds; --play-clip <file> plays one back as ghosts
+    app.insert_resource(multiplayer::clips::ClipRecorder::new(multiplayer::clips::parse_clip_settings()));
+    if let Some(clip) = multiplayer::clips::parse_play_clip() {
+        app.insert_resource(ClipPlayback { clip, start: None });
+        app.add_systems(Update, play_clip.run_if(in_state(AppState::InGame)));
+    }
+    app.add_systems(
+        Update,
+        (record_clip, clip_hud).chain().run_if(in_state(AppState::InGame)),
+    );
+
+    // Footsteps and bullet impacts, spatialized around the active camera
+    app.add_systems(Startup, load_sound_effects);
+    app.add_systems(
+        Update,
+        (play_footsteps, play_remote_impacts).run_if(in_state(AppState::InGame)),
+    );
+
+    // Dead players spectate: follow living players or fly free until respawn
+    app.init_resource::<SpectatorState>();
+    app.add_systems(
+        Update,
+        (spectate_while_dead, spectator_hud).chain().run_if(in_state(AppState::InGame)),
+    );
+
     app.add_observer(on_predicted_spawn);
     app.add_observer(on_interpolated_spawn);
     app.add_observer(spawn_tracer);
+    app.add_observer(kick_camera_recoil);
+    app.init_resource::<RecoilTrainer>();
+    app.add_observer(record_recoil_trainer_shot);
     app.add_observer(start_jab_animation);
+    app.add_observer(play_local_impact);
     app.run();
 }
 
@@ -266,6 +463,37 @@ fn setup_egui_fonts(mut contexts: EguiContexts, mut commands: Commands) {
     info!("Custom fonts loaded into egui");
 }
 
+/// Seconds without further changes before edited settings are written out, so
+/// holding the FOV keys doesn't rewrite the file every frame.
+const SETTINGS_SAVE_DELAY: f32 = 1.0;
+
+/// Mirrors the in-game FOV into `PlayerSettings` and saves the settings to
+/// disk once they've stopped changing. Only in-game edits are saved; CLI
+/// overrides last for this run.
+fn persist_player_settings(
+    base_fov: Res<BaseFov>,
+    mut settings: ResMut<multiplayer::settings::PlayerSettings>,
+    mut baseline: ResMut<multiplayer::settings::SavedPlayerSettings>,
+    time: Res<Time<Real>>,
+    mut save_at: Local<Option<f32>>,
+) {
+    let fov_degrees = base_fov.0.to_degrees().round();
+    if settings.fov_degrees != fov_degrees && base_fov.is_changed() {
+        settings.fov_degrees = fov_degrees;
+    }
+    let now = time.elapsed_secs();
+    if settings.is_changed() && !settings.is_added() {
+        *save_at = Some(now + SETTINGS_SAVE_DELAY);
+    }
+    if save_at.is_some_and(|at| now >= at) {
+        *save_at = None;
+        let persisted = settings.persisted(&baseline.saved, &baseline.started);
+        persisted.save();
+        baseline.saved = persisted;
+        baseline.started = settings.clone();
+    }
+}
+
 // ========================================
 // Shared UI helpers
 // ========================================
@@ -511,6 +739,7 @@ fn menu_ui(
     anima_cover: Option<Res<AnimaCover>>,
     line_gradient: Option<Res<LineGradient>>,
     mut menu_sel: ResMut<MenuSelection>,
+    mut exit: MessageWriter<AppExit>,
     mut frame_count: Local<u32>,
 ) {
     *frame_count += 1;
@@ -700,7 +929,10 @@ fn menu_ui(
                             next_state.set(AppState::InGame);
                         }
                         // 1 => Settings (not yet implemented)
-                        2 => std::process::exit(0),
+                        // AppExit rather than process::exit, which the browser build can't do
+                        2 => {
+                            exit.write(AppExit::Success);
+                        }
                         _ => {}
                     }
                 }
@@ -731,7 +963,23 @@ fn despawn_menu(
     }
 }
 
-fn connect_to_server(mut commands: Commands, identity: Res<multiplayer::auth::ClientIdentity>) {
+fn connect_to_server(
+    mut commands: Commands,
+    identity: Res<multiplayer::auth::ClientIdentity>,
+    transport: Res<multiplayer::transport::ActiveTransport>,
+) {
+    let client_entity = spawn_client(&mut commands, &identity, &transport);
+
+    // Store the client entity so we can send wallet auth after connection
+    commands.insert_resource(PendingWalletAuth(client_entity));
+}
+
+/// Spawns a lightyear client entity for the game server and starts connecting.
+fn spawn_client(
+    commands: &mut Commands,
+    identity: &multiplayer::auth::ClientIdentity,
+    transport: &multiplayer::transport::ActiveTransport,
+) -> Entity {
     // Default to production server; override with ANIMA_SERVER_ADDR for local dev
     let server_ip: Ipv4Addr = std::env::var("ANIMA_SERVER_ADDR")
         .ok()
@@ -755,28 +1003,154 @@ fn connect_to_server(mut commands: Commands, identity: Res<multiplayer::auth::Cl
         ..default()
     };
 
-    let client_entity = commands
-        .spawn((
-            Client::default(),
-            Link::default(),
-            NetcodeClient::new(auth, netcode_config).expect("Failed to create netcode client"),
-            UdpIo::default(),
-            LocalAddr(client_addr),
-            PeerAddr(server_addr),
-            ReplicationReceiver::default(),
-            PredictionManager::default(),
-            ReplicationSender::new(
-                Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
-                SendUpdatesMode::SinceLastAck,
-                false,
-            ),
-        ))
-        .id();
+    let mut client = commands.spawn((
+        Client::default(),
+        Link::default(),
+        NetcodeClient::new(auth, netcode_config).expect("Failed to create netcode client"),
+        ReplicationReceiver::default(),
+        PredictionManager::default(),
+        ReplicationSender::new(
+            Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
+            SendUpdatesMode::SinceLastAck,
+            false,
+        ),
+    ));
+    transport.0.insert_client_io(&mut client, client_addr, server_addr);
+    let client_entity = client.id();
 
     commands.trigger(Connect { entity: client_entity });
+    client_entity
+}
 
-    // Store the client entity so we can send wallet auth after connection
-    commands.insert_resource(PendingWalletAuth(client_entity));
+// ========================================
+// Reconnection
+// ========================================
+
+/// Reconnect attempts after a dropped connection before giving up.
+const RECONNECT_ATTEMPTS: u32 = 5;
+/// Seconds an attempt gets to connect; doubles each attempt, up to RECONNECT_MAX_SECS.
+const RECONNECT_BASE_SECS: f32 = 3.0;
+const RECONNECT_MAX_SECS: f32 = 24.0;
+
+/// Client-only: where our connection to the server stands.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+enum ConnectionState {
+    /// First connect after entering the game.
+    #[default]
+    Connecting,
+    Connected,
+    /// The connection dropped; attempt `attempt` (1-based) is in flight and
+    /// is abandoned for the next one at `next_try` (elapsed secs).
+    Reconnecting { attempt: u32, next_try: f32 },
+    /// Every attempt failed. R starts over.
+    Failed,
+}
+
+/// Seconds attempt `attempt` (1-based) waits for a connection.
+fn reconnect_window(attempt: u32) -> f32 {
+    (RECONNECT_BASE_SECS * 2f32.powi(attempt.saturating_sub(1) as i32)).min(RECONNECT_MAX_SECS)
+}
+
+/// Notices a dropped connection and reconnects with backoff. Each attempt
+/// replaces the client entity, so lightyear's entity mappings and message
+/// state from the old session go with it; everything replicated from the
+/// old session is despawned (the server sends it again on reconnect).
+fn watch_connection(
+    mut state: ResMut<ConnectionState>,
+    client_query: Query<(Entity, Has<Connected>), With<Client>>,
+    replicated_query: Query<Entity, Or<(With<Replicated>, With<Predicted>, With<Interpolated>)>>,
+    identity: Res<multiplayer::auth::ClientIdentity>,
+    transport: Res<multiplayer::transport::ActiveTransport>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    let connected = client_query.iter().any(|(_, connected)| connected);
+    let reconnect = |commands: &mut Commands, attempt: u32| {
+        for (client, _) in client_query.iter() {
+            commands.entity(client).despawn();
+        }
+        let client = spawn_client(commands, &identity, &transport);
+        commands.insert_resource(PendingWalletAuth(client));
+        info!("[RECONNECT] Attempt {}/{}", attempt, RECONNECT_ATTEMPTS);
+        ConnectionState::Reconnecting { attempt, next_try: now + reconnect_window(attempt) }
+    };
+
+    match *state {
+        ConnectionState::Connecting => {
+            if connected {
+                *state = ConnectionState::Connected;
+            }
+        }
+        ConnectionState::Connected => {
+            if connected {
+                return;
+            }
+            warn!("[RECONNECT] Lost connection to the server");
+            for entity in replicated_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            *state = reconnect(&mut commands, 1);
+        }
+        ConnectionState::Reconnecting { attempt, next_try } => {
+            if connected {
+                info!("[RECONNECT] Reconnected after {} attempt(s)", attempt);
+                *state = ConnectionState::Connected;
+            } else if now >= next_try {
+                *state = if attempt < RECONNECT_ATTEMPTS {
+                    reconnect(&mut commands, attempt + 1)
+                } else {
+                    warn!("[RECONNECT] Giving up after {} attempts", attempt);
+                    ConnectionState::Failed
+                };
+            }
+        }
+        ConnectionState::Failed => {
+            if keys.just_pressed(KeyCode::KeyR) {
+                *state = reconnect(&mut commands, 1);
+            }
+        }
+    }
+}
+
+/// Banner while the connection is down.
+fn connection_banner(
+    mut contexts: EguiContexts,
+    state: Res<ConnectionState>,
+    time: Res<Time>,
+) {
+    let (title, detail) = match *state {
+        ConnectionState::Reconnecting { attempt, next_try } => (
+            "CONNECTION LOST",
+            format!(
+                "Reconnecting (attempt {}/{}, {:.0}s)",
+                attempt,
+                RECONNECT_ATTEMPTS,
+                (next_try - time.elapsed_secs()).max(0.0)
+            ),
+        ),
+        ConnectionState::Failed => ("DISCONNECTED", "Could not reach the server. Press R to try again".to_string()),
+        _ => return,
+    };
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("connection_banner")));
+    painter.text(
+        egui::pos2(screen.center().x, screen.height() * 0.35),
+        egui::Align2::CENTER_CENTER,
+        title,
+        cinzel_black(48.0),
+        cream(1.0),
+    );
+    painter.text(
+        egui::pos2(screen.center().x, screen.height() * 0.35 + 40.0),
+        egui::Align2::CENTER_CENTER,
+        detail,
+        chakra(16.0),
+        cream(0.6),
+    );
 }
 
 /// Resource tracking that we need to send wallet auth on the client entity.
@@ -813,247 +1187,2819 @@ fn send_wallet_auth(
     commands.remove_resource::<PendingWalletAuth>();
 }
 
-// ========================================
-// Player spawn
-// ========================================
-
-/// Log health changes for debugging.
-fn log_health_changes(
-    query: Query<(Entity, &PlayerHealth, Has<Controlled>), Changed<PlayerHealth>>,
+/// Client-side system: sends gameplay preferences on connect and whenever
+/// PlayerSettings change, so the server can apply them to our player.
+fn send_player_preferences(
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    mut sender_query: Query<(&mut MessageSender<PlayerPreferencesMessage>, Ref<Connected>)>,
 ) {
-    for (entity, health, is_local) in query.iter() {
-        let tag = if is_local { "LOCAL" } else { "REMOTE" };
-        info!("[HEALTH] {} {:?} health={}", tag, entity, health.0);
+    for (mut sender, connected) in sender_query.iter_mut() {
+        if !connected.is_added() && !settings.is_changed() {
+            continue;
+        }
+        sender.send::<PreferencesChannel>(PlayerPreferencesMessage {
+            auto_equip: settings.auto_equip,
+            auto_crouch_ads: settings.auto_crouch_ads,
+        });
+        info!(
+            "[PREFS] Sent preferences (auto_equip={}, auto_crouch_ads={})",
+            settings.auto_equip, settings.auto_crouch_ads
+        );
     }
 }
 
-/// HUD: health bar at the bottom-center of the screen.
-fn health_hud(
-    mut contexts: EguiContexts,
-    player_query: Query<&PlayerHealth, With<Controlled>>,
+/// Client-side system: copies the server's replicated movement rules into
+/// our MovementConfig, so client prediction moves the way the server does.
+fn adopt_server_movement_config(
+    query: Query<&multiplayer::protocol::ServerMovementConfig, Changed<multiplayer::protocol::ServerMovementConfig>>,
+    mut config: ResMut<multiplayer::player::MovementConfig>,
 ) {
-    let Ok(health) = player_query.single() else { return; };
-    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    for server in query.iter() {
+        if *config != server.0 {
+            info!("[MOVEMENT] Using the server's movement config: {:?}", server.0);
+            *config = server.0.clone();
+        }
+    }
+}
 
-    let screen = ctx.screen_rect();
-    let bar_w = 200.0;
-    let bar_h = 16.0;
-    let bar_x = (screen.width() - bar_w) / 2.0;
-    let bar_y = screen.height() - 50.0;
+/// Client-side system: copies the server's replicated ADS rules into our
+/// AdsConfig. We never read --ads-switch ourselves.
+fn adopt_server_ads_config(
+    query: Query<&multiplayer::protocol::ServerAdsConfig, Changed<multiplayer::protocol::ServerAdsConfig>>,
+    mut config: ResMut<multiplayer::player::AdsConfig>,
+) {
+    for server in query.iter() {
+        if *config != server.0 {
+            info!("[ADS] Using the server's ADS config: {:?}", server.0);
+            *config = server.0;
+        }
+    }
+}
 
-    egui::Area::new(egui::Id::new("health_hud"))
-        .fixed_pos(egui::pos2(bar_x, bar_y))
-        .order(egui::Order::Foreground)
-        .interactable(false)
-        .show(ctx, |ui| {
-            let hp = health.0.max(0) as f32;
-            let pct = (hp / 100.0).clamp(0.0, 1.0);
+/// Client-side system: copies the server's replicated lean limits into our
+/// LeanConfig, so the camera leans as far as the server's head hitbox does.
+fn adopt_server_lean_config(
+    query: Query<&multiplayer::protocol::ServerLeanConfig, Changed<multiplayer::protocol::ServerLeanConfig>>,
+    mut config: ResMut<LeanConfig>,
+) {
+    for server in query.iter() {
+        if *config != server.0 {
+            info!("[LEAN] Using the server's lean config: {:?}", server.0);
+            *config = server.0;
+        }
+    }
+}
 
-            // Bar color: green → yellow → red as health drops
-            let color = if pct > 0.5 {
-                egui::Color32::from_rgb(50, 200, 80)
-            } else if pct > 0.25 {
-                egui::Color32::from_rgb(220, 180, 30)
-            } else {
-                egui::Color32::from_rgb(220, 40, 40)
-            };
+// ========================================
+// Observer / Referee
+// ========================================
 
-            let (rect, _) = ui.allocate_exact_size(
-                egui::vec2(bar_w, bar_h),
-                egui::Sense::hover(),
-            );
+/// Free-fly spectator camera speed (m/s).
+const OBSERVER_FREE_SPEED: f32 = 12.0;
+/// Shift multiplier for the free camera.
+const OBSERVER_FAST_MULT: f32 = 3.0;
 
-            // Background
-            ui.painter().rect_filled(rect, 4.0, egui::Color32::from_rgba_unmultiplied(0, 0, 0, 160));
-            // Health fill
-            let fill_rect = egui::Rect::from_min_size(
-                rect.min,
-                egui::vec2(bar_w * pct, bar_h),
-            );
-            ui.painter().rect_filled(fill_rect, 4.0, color);
-            // Border
-            ui.painter().rect_stroke(rect, 4.0, egui::Stroke::new(1.0, egui::Color32::from_white_alpha(80)), egui::StrokeKind::Outside);
-            // Text
-            ui.painter().text(
-                rect.center(),
-                egui::Align2::CENTER_CENTER,
-                format!("{}", health.0.max(0)),
-                chakra(12.0),
-                egui::Color32::WHITE,
-            );
-        });
+/// Client-side observer state. `token` comes from --observer-token; `active`
+/// is set once the server accepts it. `target` is the followed player's id,
+/// None for the free-fly camera.
+#[derive(Resource, Default)]
+struct ObserverSession {
+    token: Option<String>,
+    active: bool,
+    target: Option<u64>,
+    yaw: f32,
+    pitch: f32,
+    /// H hides the followed player's stats panel.
+    hide_stats: bool,
+    /// G splits the screen into a grid_size × grid_size grid of player
+    /// views (--observer-grid <n> starts in it).
+    grid: bool,
+    grid_size: u32,
+    /// Player shown in each grid cell, row by row.
+    grid_targets: Vec<u64>,
 }
 
-/// Crosshair — small cross at screen center when a gun is equipped.
-fn crosshair_hud(
-    mut contexts: EguiContexts,
-    player_query: Query<&PlayerEquipped, With<Controlled>>,
+/// Marker for the spectator camera spawned when observer access is granted.
+#[derive(Component)]
+struct ObserverCamera;
+
+/// Sends the observer token once the connection is established.
+fn send_observer_login(
+    session: Res<ObserverSession>,
+    mut sender_query: Query<(&mut MessageSender<ObserverLoginMessage>, Ref<Connected>)>,
 ) {
-    let Ok(equipped) = player_query.single() else { return; };
-    let Some(ref name) = equipped.0 else { return; };
-    // Only show crosshair for guns
-    if !(name.contains("AK") || name.contains("ak") || name.contains("gun")) {
-        return;
+    let Some(ref token) = session.token else { return; };
+    for (mut sender, connected) in sender_query.iter_mut() {
+        if !connected.is_added() {
+            continue;
+        }
+        sender.send::<ObserverChannel>(ObserverLoginMessage { token: token.clone() });
+        info!("[OBSERVER] Sent observer login");
     }
-    let Ok(ctx) = contexts.ctx_mut() else { return; };
-    let screen = ctx.screen_rect();
-    let center = egui::pos2(screen.width() / 2.0, screen.height() / 2.0);
-    let color = egui::Color32::from_rgba_unmultiplied(255, 255, 255, 180);
-    let stroke = egui::Stroke::new(1.5, color);
-    let size = 8.0;
-    let gap = 3.0;
-
-    let painter = ctx.layer_painter(egui::LayerId::new(
-        egui::Order::Foreground,
-        egui::Id::new("crosshair"),
-    ));
-
-    // Horizontal lines
-    painter.line_segment([egui::pos2(center.x - size, center.y), egui::pos2(center.x - gap, center.y)], stroke);
-    painter.line_segment([egui::pos2(center.x + gap, center.y), egui::pos2(center.x + size, center.y)], stroke);
-    // Vertical lines
-    painter.line_segment([egui::pos2(center.x, center.y - size), egui::pos2(center.x, center.y - gap)], stroke);
-    painter.line_segment([egui::pos2(center.x, center.y + gap), egui::pos2(center.x, center.y + size)], stroke);
-    // Center dot
-    painter.circle_filled(center, 1.0, color);
 }
 
-/// Inventory HUD — bottom-left, shows equipped item and carried inventory.
-fn inventory_hud(
-    mut contexts: EguiContexts,
-    player_query: Query<(&PlayerEquipped, &PlayerInventory), With<Controlled>>,
-    mut frame_count: Local<u32>,
+/// Switches to spectator mode when the server accepts our observer token.
+fn receive_observer_status(
+    mut receiver_query: Query<&mut MessageReceiver<ObserverStatusMessage>>,
+    mut session: ResMut<ObserverSession>,
+    base_fov: Res<BaseFov>,
+    mut commands: Commands,
 ) {
-    *frame_count += 1;
-    if *frame_count <= 2 { return; }
-    let Ok((equipped, inventory)) = player_query.single() else { return; };
-    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    for mut receiver in receiver_query.iter_mut() {
+        for status in receiver.receive() {
+            if !status.granted {
+                error!("[OBSERVER] Server rejected the observer token — playing normally");
+                continue;
+            }
+            if session.active {
+                continue;
+            }
+            info!("[OBSERVER] Observer access granted");
+            session.active = true;
+            session.pitch = -0.4;
+            commands.spawn((
+                ObserverCamera,
+                Camera3d::default(),
+                Projection::from(PerspectiveProjection {
+                    fov: base_fov.0,
+                    ..default()
+                }),
+                Transform::from_xyz(0.0, 12.0, 20.0)
+                    .with_rotation(Quat::from_euler(EulerRot::YXZ, 0.0, -0.4, 0.0)),
+            ));
+        }
+    }
+}
 
-    // Only show if the player has something equipped or in inventory
-    if equipped.0.is_none() && inventory.items.is_empty() {
-        return;
+/// Spectator camera: Tab cycles through players (first-person from their eye),
+/// F returns to the free camera (mouse look, WASD, Space/Ctrl up/down, Shift fast).
+/// Keys are ignored while typing in chat.
+fn observer_camera(
+    mut session: ResMut<ObserverSession>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), With<ObserverCamera>>,
+    players: Query<(&PlayerId, &Transform, &PlayerYaw, &PlayerPitch, Option<&PlayerStance>), Without<ObserverCamera>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    chat_focus: Res<ChatFocus>,
+    mouse_motion: Res<bevy::input::mouse::AccumulatedMouseMotion>,
+    cursor_state: Res<CursorState>,
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    bindings: Res<multiplayer::settings::KeyBindings>,
+    base_fov: Res<BaseFov>,
+    lean_config: Res<LeanConfig>,
+    time: Res<Time>,
+) {
+    if !session.active { return; }
+    let Ok((mut cam, mut projection)) = camera_query.single_mut() else { return; };
+    let no_keys = ButtonInput::default();
+    let keys = if chat_focus.typing { &no_keys } else { keys.as_ref() };
+    if let Projection::Perspective(ref mut perspective) = projection.as_mut() {
+        perspective.fov = base_fov.0;
     }
 
+    if keys.just_pressed(KeyCode::Tab) {
+        let mut ids: Vec<u64> = players.iter().map(|(id, ..)| id.0).collect();
+        ids.sort_unstable();
+        // Cycle through players, then back to the free camera
+        session.target = match session.target {
+            None => ids.first().copied(),
+            Some(current) => ids.into_iter().find(|id| *id > current),
+        };
+    }
+    if keys.just_pressed(KeyCode::KeyF) {
+        session.target = None;
+    }
+    if keys.just_pressed(KeyCode::KeyH) {
+        session.hide_stats = !session.hide_stats;
+    }
+    if keys.just_pressed(KeyCode::KeyG) {
+        session.grid = !session.grid;
+    }
+
+    if let Some(target) = session.target {
+        if let Some((_, transform, yaw, pitch, stance)) = players.iter().find(|(id, ..)| id.0 == target) {
+            cam.translation = transform.translation
+                + stance.map(|s| s.eye_offset(yaw.0, &lean_config)).unwrap_or(Vec3::Y * EYE_HEIGHT);
+            cam.rotation = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0);
+            // Leaving follow mode starts the free camera from the same view
+            session.yaw = yaw.0;
+            session.pitch = pitch.0;
+            return;
+        }
+        // Followed player left
+        session.target = None;
+    }
+
+    let FreeLook { yaw, pitch } = fly_free_camera(
+        &mut cam,
+        FreeLook { yaw: session.yaw, pitch: session.pitch },
+        keys,
+        cursor_state.locked.then_some(mouse_motion.delta),
+        &settings,
+        &bindings,
+        time.delta_secs(),
+    );
+    session.yaw = yaw;
+    session.pitch = pitch;
+}
+
+/// Yaw/pitch of a free-fly camera.
+#[derive(Clone, Copy, Debug, Default)]
+struct FreeLook {
+    yaw: f32,
+    pitch: f32,
+}
+
+/// Free-fly camera shared by observers and dead players: mouse look (None
+/// while the cursor is free), movement keys to fly, jump/crouch for up/down,
+/// sprint to go fast. Returns the updated look angles.
+fn fly_free_camera(
+    cam: &mut Transform,
+    mut look: FreeLook,
+    keys: &ButtonInput<KeyCode>,
+    mouse_delta: Option<Vec2>,
+    settings: &multiplayer::settings::PlayerSettings,
+    bindings: &multiplayer::settings::KeyBindings,
+    dt: f32,
+) -> FreeLook {
+    if let Some(mouse_delta) = mouse_delta {
+        look.yaw -= mouse_delta.x * settings.yaw_per_count();
+        look.pitch = (look.pitch - mouse_delta.y * settings.pitch_per_count())
+            .clamp(-std::f32::consts::FRAC_PI_2 + 0.01, std::f32::consts::FRAC_PI_2 - 0.01);
+    }
+    cam.rotation = Quat::from_euler(EulerRot::YXZ, look.yaw, look.pitch, 0.0);
+
+    let mut wish = Vec3::ZERO;
+    if keys.pressed(bindings.forward) { wish += *cam.forward(); }
+    if keys.pressed(bindings.back) { wish -= *cam.forward(); }
+    if keys.pressed(bindings.right) { wish += *cam.right(); }
+    if keys.pressed(bindings.left) { wish -= *cam.right(); }
+    if keys.pressed(bindings.jump) { wish += Vec3::Y; }
+    if keys.pressed(bindings.crouch) { wish -= Vec3::Y; }
+    let speed = if keys.pressed(bindings.sprint) {
+        OBSERVER_FREE_SPEED * OBSERVER_FAST_MULT
+    } else {
+        OBSERVER_FREE_SPEED
+    };
+    cam.translation += wish.normalize_or_zero() * speed * dt;
+    look
+}
+
+/// One cell of the observer grid; `slot` counts row by row from the top left.
+#[derive(Component)]
+struct ObserverGridCamera {
+    slot: usize,
+}
+
+/// Observer grid: while `session.grid` is on, the screen is split into a
+/// grid of first-person views — the followed player first, then everyone
+/// else by id. The main spectator camera is switched off meanwhile.
+fn observer_grid(
+    mut session: ResMut<ObserverSession>,
+    mut main_camera: Query<&mut Camera, (With<ObserverCamera>, Without<ObserverGridCamera>)>,
+    mut grid_cameras: Query<(Entity, &ObserverGridCamera, &mut Camera, &mut Transform)>,
+    players: Query<(&PlayerId, &Transform, &PlayerYaw, &PlayerPitch, Option<&PlayerStance>), Without<ObserverGridCamera>>,
+    window_query: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    base_fov: Res<BaseFov>,
+    lean_config: Res<LeanConfig>,
+    mut commands: Commands,
+) {
+    let enabled = session.active && session.grid;
+    if let Ok(mut camera) = main_camera.single_mut() {
+        camera.is_active = !enabled;
+    }
+    if !enabled {
+        for (entity, ..) in grid_cameras.iter() {
+            commands.entity(entity).despawn();
+        }
+        session.grid_targets.clear();
+        return;
+    }
+
+    let cells = (session.grid_size * session.grid_size) as usize;
+    let mut ids: Vec<u64> = players.iter().map(|(id, ..)| id.0).collect();
+    ids.sort_unstable();
+    if let Some(target) = session.target {
+        if let Some(pos) = ids.iter().position(|id| *id == target) {
+            ids.remove(pos);
+            ids.insert(0, target);
+        }
+    }
+    ids.truncate(cells);
+    session.grid_targets = ids;
+
+    if grid_cameras.iter().count() != cells {
+        for (entity, ..) in grid_cameras.iter() {
+            commands.entity(entity).despawn();
+        }
+        for slot in 0..cells {
+            commands.spawn((
+                ObserverGridCamera { slot },
+                Camera3d::default(),
+                Camera {
+                    // Below every other camera: the cells must never draw
+                    // over the egui HUD camera (order 10), however many there are
+                    order: -1 - slot as isize,
+                    is_active: false,
+                    ..default()
+                },
+                Projection::from(PerspectiveProjection {
+                    fov: base_fov.0,
+                    ..default()
+                }),
+            ));
+        }
+        return;
+    }
+
+    let Ok(window) = window_query.single() else { return; };
+    let size = UVec2::new(window.physical_width(), window.physical_height()) / session.grid_size;
+    for (_, cell, mut camera, mut transform) in grid_cameras.iter_mut() {
+        let target = session.grid_targets.get(cell.slot).copied();
+        let Some((_, player, yaw, pitch, stance)) = target.and_then(|t| players.iter().find(|(id, ..)| id.0 == t)) else {
+            camera.is_active = false;
+            continue;
+        };
+        let column = cell.slot as u32 % session.grid_size;
+        let row = cell.slot as u32 / session.grid_size;
+        camera.is_active = size.x > 0 && size.y > 0;
+        camera.viewport = Some(bevy::camera::Viewport {
+            physical_position: UVec2::new(column * size.x, row * size.y),
+            physical_size: size.max(UVec2::ONE),
+            ..default()
+        });
+        transform.translation = player.translation
+            + stance.map(|s| s.eye_offset(yaw.0, &lean_config)).unwrap_or(Vec3::Y * EYE_HEIGHT);
+        transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0);
+    }
+}
+
+/// Referee hotkeys (observers only): F9 pause/resume, F10 restart round.
+/// The server re-checks that we are an observer before acting.
+fn observer_admin_keys(
+    session: Res<ObserverSession>,
+    keys: Res<ButtonInput<KeyCode>>,
+    paused_query: Query<(), With<MatchPaused>>,
+    mut sender_query: Query<&mut MessageSender<AdminCommandMessage>, With<Connected>>,
+) {
+    if !session.active { return; }
+    let command = if keys.just_pressed(KeyCode::F9) {
+        if paused_query.is_empty() { AdminCommand::Pause } else { AdminCommand::Resume }
+    } else if keys.just_pressed(KeyCode::F10) {
+        AdminCommand::RestartRound
+    } else {
+        return;
+    };
+    for mut sender in sender_query.iter_mut() {
+        sender.send::<ObserverChannel>(AdminCommandMessage(command));
+        info!("[ADMIN] Sent {:?}", command);
+    }
+}
+
+/// Observer HUD — who is being followed and the referee hotkeys.
+fn observer_hud(
+    mut contexts: EguiContexts,
+    session: Res<ObserverSession>,
+    players: Query<(&PlayerId, Has<PlayerDead>)>,
+    stats_query: Query<(&PlayerId, &PlayerHealth, &PlayerEquipped, Option<&Stamina>, Option<&PlayerStats>)>,
+) {
+    if !session.active { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("observer_hud")));
+    let following = session
+        .target
+        .and_then(|target| players.iter().find(|(id, _)| id.0 == target))
+        .map(|(id, dead)| {
+            let name = multiplayer::auth::client_id_to_base58(id.0);
+            if dead { format!("Following {} (dead)", name) } else { format!("Following {}", name) }
+        })
+        .unwrap_or_else(|| "Free camera".to_string());
+
+    painter.text(egui::pos2(16.0, 16.0), egui::Align2::LEFT_TOP, "OBSERVER", cinzel_bold(18.0), blue(0.9));
+    painter.text(egui::pos2(16.0, 40.0), egui::Align2::LEFT_TOP, following, chakra_semi(14.0), cream(0.8));
+    painter.text(
+        egui::pos2(16.0, 60.0),
+        egui::Align2::LEFT_TOP,
+        format!(
+            "Tab next player · F free camera · G {} grid · H {} stats · F9 pause · F10 restart round ({} players)",
+            if session.grid { "close" } else { "open" },
+            if session.hide_stats { "show" } else { "hide" },
+            players.iter().count()
+        ),
+        chakra(12.0),
+        cream(0.5),
+    );
+
+    // Grid: name each view in its top-left corner
+    if session.grid {
+        let cell = screen.size() / session.grid_size as f32;
+        for (slot, id) in session.grid_targets.iter().enumerate() {
+            let column = (slot as u32 % session.grid_size) as f32;
+            let row = (slot as u32 / session.grid_size) as f32;
+            let corner = screen.min + egui::vec2(column * cell.x, row * cell.y);
+            let dead = players.iter().any(|(pid, dead)| pid.0 == *id && dead);
+            painter.text(
+                corner + egui::vec2(8.0, if row == 0.0 { 84.0 } else { 8.0 }),
+                egui::Align2::LEFT_TOP,
+                format!("{}{}", multiplayer::auth::client_id_to_base58(*id), if dead { " (dead)" } else { "" }),
+                chakra_semi(13.0),
+                cream(0.9),
+            );
+        }
+        // The stats panel belongs to a single view
+        return;
+    }
+
+    // Followed player's live state, where our own health bar would be
+    if session.hide_stats { return; }
+    let Some((_, health, equipped, stamina, stats)) = session
+        .target
+        .and_then(|target| stats_query.iter().find(|(id, ..)| id.0 == target))
+    else {
+        return;
+    };
+    let stats = stats.cloned().unwrap_or_default();
+    let lines = [
+        format!("Health {}", health.0.max(0)),
+        format!("Holding {}", equipped.0.as_deref().unwrap_or("nothing")),
+        format!("Stamina {:.0}%", stamina.map(|s| s.0).unwrap_or(1.0) * 100.0),
+        format!("K {} · D {} · A {}", stats.kills, stats.deaths, stats.assists),
+    ];
+    let panel = egui::Rect::from_min_size(
+        egui::pos2(screen.center().x - 110.0, screen.bottom() - 130.0),
+        egui::vec2(220.0, 20.0 * lines.len() as f32 + 12.0),
+    );
+    painter.rect_filled(panel, 4.0, egui::Color32::from_rgba_unmultiplied(0, 0, 0, 140));
+    for (i, line) in lines.iter().enumerate() {
+        painter.text(
+            egui::pos2(panel.left() + 12.0, panel.top() + 6.0 + 20.0 * i as f32),
+            egui::Align2::LEFT_TOP,
+            line,
+            chakra_semi(14.0),
+            cream(0.9),
+        );
+    }
+}
+
+// ========================================
+// Sound effects
+// ========================================
+
+/// Footstep and bullet impact clips, loaded when the client starts.
+#[derive(Resource)]
+struct FootstepAudio {
+    footstep: Handle<AudioSource>,
+    impact: Handle<AudioSource>,
+}
+
+/// Meters walked per footstep — faster movement steps more often.
+const FOOTSTEP_STRIDE: f32 = 1.8;
+/// More than this many meters in one frame is a teleport (respawn), not a step.
+const FOOTSTEP_MAX_FRAME_MOVE: f32 = 3.0;
+/// Vertical movement per frame above which a player counts as airborne.
+const FOOTSTEP_AIRBORNE_DY: f32 = 0.01;
+/// Sounds fade out linearly to silence at this distance from the camera.
+const SFX_HEARING_RANGE: f32 = 25.0;
+/// Impacts carry a bit further than footsteps.
+const IMPACT_HEARING_RANGE: f32 = 40.0;
+
+fn load_sound_effects(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(FootstepAudio {
+        footstep: asset_server.load("audio/footstep.wav"),
+        impact: asset_server.load("audio/impact.wav"),
+    });
+}
+
+/// Gain and left/right pan for a sound at `source` heard from `listener`,
+/// or None when it's out of earshot.
+fn spatial_mix(listener: &GlobalTransform, source: Vec3, range: f32) -> Option<(f32, f32)> {
+    let offset = source - listener.translation();
+    let gain = 1.0 - offset.length() / range;
+    if gain <= 0.0 {
+        return None;
+    }
+    let pan = listener.right().dot(offset.normalize_or_zero());
+    Some((gain, pan))
+}
+
+/// Play `sound` at `gain` (0-1, scaled by the sfx volume setting) and `pan`
+/// (-1 left to 1 right).
+fn play_sfx(audio: &Audio, sound: &Handle<AudioSource>, volume: f32, gain: f32, pan: f32) {
+    let amplitude = volume * gain;
+    if amplitude <= 0.0 {
+        return;
+    }
+    audio
+        .play(sound.clone())
+        .with_volume(20.0 * amplitude.log10())
+        .with_panning(pan.clamp(-1.0, 1.0));
+}
+
+/// Footsteps for every living player on the ground, one per FOOTSTEP_STRIDE
+/// walked. Our own are played straight; everyone else's come from where
+/// they are, relative to the active camera.
+fn play_footsteps(
+    audio: Res<Audio>,
+    sounds: Option<Res<FootstepAudio>>,
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    players: Query<(Entity, &avian3d::prelude::Position, Has<Controlled>), (With<PlayerId>, Without<PlayerDead>)>,
+    camera_query: Query<(&Camera, &GlobalTransform), Or<(With<WorldModelCamera>, With<ObserverCamera>, With<SpectatorCamera>)>>,
+    // Per player: last position and meters walked since the last step
+    mut walked: Local<std::collections::HashMap<Entity, (Vec3, f32)>>,
+) {
+    let Some(sounds) = sounds else { return; };
+    let listener = camera_query.iter().find(|(camera, _)| camera.is_active).map(|(_, t)| t);
+
+    walked.retain(|entity, _| players.contains(*entity));
+    for (entity, position, controlled) in players.iter() {
+        let (last, distance) = walked.entry(entity).or_insert((position.0, 0.0));
+        let delta = position.0 - *last;
+        *last = position.0;
+        let step = Vec2::new(delta.x, delta.z).length();
+        if step > FOOTSTEP_MAX_FRAME_MOVE || delta.y.abs() > FOOTSTEP_AIRBORNE_DY {
+            *distance = 0.0;
+            continue;
+        }
+        *distance += step;
+        if *distance < FOOTSTEP_STRIDE {
+            continue;
+        }
+        *distance = 0.0;
+
+        if controlled {
+            play_sfx(&audio, &sounds.footstep, settings.sfx_volume, 0.6, 0.0);
+        } else if let Some((gain, pan)) = listener.and_then(|l| spatial_mix(l, position.0, SFX_HEARING_RANGE)) {
+            play_sfx(&audio, &sounds.footstep, settings.sfx_volume, gain, pan);
+        }
+    }
+}
+
+/// Client-only observer: a thunk where one of our own rounds lands.
+fn play_local_impact(
+    trigger: On<multiplayer::world::ShotFired>,
+    audio: Res<Audio>,
+    sounds: Option<Res<FootstepAudio>>,
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    camera_query: Query<(&Camera, &GlobalTransform), Or<(With<WorldModelCamera>, With<ObserverCamera>, With<SpectatorCamera>)>>,
+) {
+    let Some(sounds) = sounds else { return; };
+    let Some((_, listener)) = camera_query.iter().find(|(camera, _)| camera.is_active) else { return; };
+    if let Some((gain, pan)) = spatial_mix(listener, trigger.event().hit_point, IMPACT_HEARING_RANGE) {
+        play_sfx(&audio, &sounds.impact, settings.sfx_volume, gain, pan);
+    }
+}
+
+/// A thunk where a remote player's round lands (only replicated rounds —
+/// see LastShot).
+fn play_remote_impacts(
+    audio: Res<Audio>,
+    sounds: Option<Res<FootstepAudio>>,
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    shots: Query<&LastShot, (Changed<LastShot>, With<Interpolated>)>,
+    camera_query: Query<(&Camera, &GlobalTransform), Or<(With<WorldModelCamera>, With<ObserverCamera>, With<SpectatorCamera>)>>,
+) {
+    let Some(sounds) = sounds else { return; };
+    let Some((_, listener)) = camera_query.iter().find(|(camera, _)| camera.is_active) else { return; };
+    for shot in shots.iter() {
+        if shot.tick == 0 { continue; } // default, no shot yet
+        if let Some((gain, pan)) = spatial_mix(listener, shot.hit_point, IMPACT_HEARING_RANGE) {
+            play_sfx(&audio, &sounds.impact, settings.sfx_volume, gain, pan);
+        }
+    }
+}
+
+// ========================================
+// Highlight clips
+// ========================================
+
+/// Seconds the "clip saved" notice stays up.
+const CLIP_NOTICE_SECS: f32 = 3.0;
+
+/// Samples every player into the clip buffer and saves it on CLIP_KEY, or
+/// after a multi-kill when --auto-clip is on.
+fn record_clip(
+    mut recorder: ResMut<multiplayer::clips::ClipRecorder>,
+    players: Query<(&PlayerId, &avian3d::prelude::Position, &PlayerYaw, &PlayerPitch, Has<PlayerDead>)>,
+    me: Query<(&PlayerId, Option<&PlayerStats>, &ActionState<PlayerActions>), With<Controlled>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    focus: Res<ChatFocus>,
+    time: Res<Time>,
+    mut last_kills: Local<Option<u32>>,
+) {
+    let now = time.elapsed_secs();
+    if recorder.wants_sample(now) {
+        let players = players
+            .iter()
+            .map(|(id, pos, yaw, pitch, dead)| multiplayer::clips::ClipPlayer {
+                id: id.0,
+                position: pos.0.to_array(),
+                yaw: yaw.0,
+                pitch: pitch.0,
+                dead,
+            })
+            .collect();
+        let input = me.single().ok().map(|(id, _, action)| multiplayer::replay::RecordedInput::capture(id.0, action));
+        recorder.push(multiplayer::clips::ClipFrame { t: now, players, input });
+    }
+
+    let Ok((my_id, stats, _)) = me.single() else { return; };
+    let kills = stats.map_or(0, |s| s.kills);
+    let mut multi_kill = false;
+    if let Some(last) = *last_kills {
+        for _ in last..kills {
+            multi_kill |= recorder.note_kill(now);
+        }
+    }
+    *last_kills = Some(kills);
+
+    let wanted = (!focus.typing && keys.just_pressed(multiplayer::clips::CLIP_KEY))
+        || (multi_kill && recorder.settings.auto_multi_kill);
+    if wanted {
+        if let Err(e) = recorder.save(my_id.0, now) {
+            warn!("[CLIP] Could not save clip: {}", e);
+        }
+    }
+}
+
+/// Brief "clip saved" notice under the crosshair.
+fn clip_hud(
+    mut contexts: EguiContexts,
+    recorder: Res<multiplayer::clips::ClipRecorder>,
+    time: Res<Time>,
+) {
+    let Some((ref key, saved_at)) = recorder.last_saved else { return; };
+    let age = time.elapsed_secs() - saved_at;
+    if age > CLIP_NOTICE_SECS { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("clip_hud")));
+    painter.text(
+        egui::pos2(screen.center().x, screen.height() * 0.62),
+        egui::Align2::CENTER_CENTER,
+        format!("Clip saved: {}", key),
+        chakra_semi(14.0),
+        cream(1.0 - age / CLIP_NOTICE_SECS),
+    );
+}
+
+/// Client-only: a clip being played back (--play-clip). `start` is when the
+/// current loop began.
+#[derive(Resource)]
+struct ClipPlayback {
+    clip: multiplayer::clips::Clip,
+    start: Option<f32>,
+}
+
+/// A ghost player in clip playback.
+#[derive(Component)]
+struct ClipGhost {
+    id: u64,
+}
+
+/// Plays the clip on a loop: a translucent ghost per recorded player (the
+/// recorder's in gold) moving and turning as they did.
+fn play_clip(
+    mut playback: ResMut<ClipPlayback>,
+    mut ghosts: Query<(Entity, &ClipGhost, &mut Transform, &mut Visibility)>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    let start = *playback.start.get_or_insert(now);
+    let duration = playback.clip.duration().max(0.01);
+    let t = (now - start) % duration;
+
+    if ghosts.is_empty() {
+        let mut ids: Vec<u64> = playback
+            .clip
+            .frames
+            .iter()
+            .flat_map(|f| f.players.iter().map(|p| p.id))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let capsule = meshes.add(Capsule3d::new(CAPSULE_RADIUS, CAPSULE_HEIGHT));
+        for id in ids {
+            let color = if id == playback.clip.recorded_by {
+                Color::srgba(1.0, 0.8, 0.2, 0.6)
+            } else {
+                Color::srgba(0.6, 0.8, 1.0, 0.45)
+            };
+            commands.spawn((
+                ClipGhost { id },
+                Mesh3d(capsule.clone()),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: color,
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..default()
+                })),
+                Transform::default(),
+                Visibility::Hidden,
+                NotShadowCaster,
+            ));
+        }
+        return;
+    }
+
+    let Some((a, b, blend)) = playback.clip.frames_at(t) else { return; };
+    for (_, ghost, mut transform, mut visibility) in ghosts.iter_mut() {
+        let from = a.players.iter().find(|p| p.id == ghost.id);
+        let to = b.players.iter().find(|p| p.id == ghost.id);
+        let (Some(from), Some(to)) = (from.or(to), to.or(from)) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = if to.dead { Visibility::Hidden } else { Visibility::Visible };
+        let position = Vec3::from_array(from.position).lerp(Vec3::from_array(to.position), blend);
+        // Recorded positions are the capsule center, like Position
+        transform.translation = position;
+        let turn = (to.yaw - from.yaw + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+        transform.rotation = Quat::from_rotation_y(from.yaw + turn * blend);
+    }
+}
+
+// ========================================
+// Spectating (dead players)
+// ========================================
+
+/// Client-only: the camera we watch through while dead.
+#[derive(Component)]
+struct SpectatorCamera;
+
+/// Client-only spectator state while our player is dead. `target` is the
+/// followed player's id, None for the free-fly camera.
+#[derive(Resource, Default)]
+struct SpectatorState {
+    target: Option<u64>,
+    look: FreeLook,
+}
+
+/// While our player is dead, swaps our own cameras for a SpectatorCamera:
+/// left click follows the next living player (teammates only when we have a
+/// team), F flies free. Respawning hands the view back to our own cameras.
+/// Observers have their own camera and skip this.
+fn spectate_while_dead(
+    mut state: ResMut<SpectatorState>,
+    observer: Res<ObserverSession>,
+    me: Query<(&Transform, &PlayerYaw, &PlayerPitch, Has<PlayerDead>, Option<&Team>), (With<Controlled>, Without<SpectatorCamera>)>,
+    players: Query<
+        (&PlayerId, &Transform, &PlayerYaw, &PlayerPitch, Option<&PlayerStance>, Option<&Team>),
+        (Without<PlayerDead>, Without<Controlled>, Without<SpectatorCamera>),
+    >,
+    mut spectator_query: Query<(Entity, &mut Transform), (With<SpectatorCamera>, Without<PlayerId>)>,
+    mut own_cameras: Query<
+        &mut Camera,
+        (Or<(With<WorldModelCamera>, With<ViewModelCamera>, With<TeammateOutlineCamera>)>, Without<SpectatorCamera>),
+    >,
+    keys: Res<ButtonInput<KeyCode>>,
+    chat_focus: Res<ChatFocus>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mouse_motion: Res<bevy::input::mouse::AccumulatedMouseMotion>,
+    cursor_state: Res<CursorState>,
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    bindings: Res<multiplayer::settings::KeyBindings>,
+    base_fov: Res<BaseFov>,
+    lean_config: Res<LeanConfig>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let spectating = !observer.active && me.single().is_ok_and(|(_, _, _, dead, _)| dead);
+    let camera = spectator_query.single_mut().ok();
+    // Typing in chat doesn't fly the camera or press F
+    let no_keys = ButtonInput::default();
+    let keys = if chat_focus.typing { &no_keys } else { keys.as_ref() };
+
+    if !spectating {
+        if let Some((entity, _)) = camera {
+            commands.entity(entity).despawn();
+            for mut own in own_cameras.iter_mut() {
+                own.is_active = true;
+            }
+            *state = SpectatorState::default();
+        }
+        return;
+    }
+    let Ok((my_transform, my_yaw, my_pitch, _, my_team)) = me.single() else { return; };
+
+    let Some((_, mut cam)) = camera else {
+        // Just died: start from where we were looking
+        for mut own in own_cameras.iter_mut() {
+            own.is_active = false;
+        }
+        state.look = FreeLook { yaw: my_yaw.0, pitch: my_pitch.0 };
+        commands.spawn((
+            SpectatorCamera,
+            Camera3d::default(),
+            Projection::from(PerspectiveProjection {
+                fov: base_fov.0,
+                ..default()
+            }),
+            Transform::from_translation(my_transform.translation + Vec3::Y * EYE_HEIGHT)
+                .with_rotation(Quat::from_euler(EulerRot::YXZ, my_yaw.0, my_pitch.0, 0.0)),
+        ));
+        return;
+    };
+
+    let watchable = |team: Option<&Team>| my_team.is_none() || team == my_team;
+    if mouse.just_pressed(MouseButton::Left) {
+        let mut ids: Vec<u64> = players
+            .iter()
+            .filter(|(.., team)| watchable(*team))
+            .map(|(id, ..)| id.0)
+            .collect();
+        ids.sort_unstable();
+        state.target = match state.target {
+            None => ids.first().copied(),
+            Some(current) => ids.iter().copied().find(|id| *id > current).or(ids.first().copied()),
+        };
+    }
+    if keys.just_pressed(KeyCode::KeyF) {
+        state.target = None;
+    }
+
+    if let Some(target) = state.target {
+        if let Some((_, transform, yaw, pitch, stance, _)) = players.iter().find(|(id, ..)| id.0 == target) {
+            cam.translation = transform.translation
+                + stance.map(|s| s.eye_offset(yaw.0, &lean_config)).unwrap_or(Vec3::Y * EYE_HEIGHT);
+            cam.rotation = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0);
+            state.look = FreeLook { yaw: yaw.0, pitch: pitch.0 };
+            return;
+        }
+        // Followed player died or left
+        state.target = None;
+    }
+    state.look = fly_free_camera(
+        &mut cam,
+        state.look,
+        keys,
+        cursor_state.locked.then_some(mouse_motion.delta),
+        &settings,
+        &bindings,
+        time.delta_secs(),
+    );
+}
+
+/// Who we're watching while dead, and the spectator keys.
+fn spectator_hud(
+    mut contexts: EguiContexts,
+    state: Res<SpectatorState>,
+    camera_query: Query<(), With<SpectatorCamera>>,
+    ids: Query<(&PlayerId, &PlayerDisplayId)>,
+) {
+    if camera_query.is_empty() { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("spectator_hud")));
+
+    let watching = state
+        .target
+        .and_then(|target| ids.iter().find(|(id, _)| id.0 == target))
+        .map(|(_, display_id)| format!("Watching Player {}", display_id.0))
+        .unwrap_or_else(|| "Free camera".to_string());
+    painter.text(
+        egui::pos2(screen.center().x, screen.bottom() - 60.0),
+        egui::Align2::CENTER_CENTER,
+        watching,
+        chakra_semi(16.0),
+        cream(0.9),
+    );
+    painter.text(
+        egui::pos2(screen.center().x, screen.bottom() - 38.0),
+        egui::Align2::CENTER_CENTER,
+        "Click next player · F free camera",
+        chakra(12.0),
+        cream(0.5),
+    );
+}
+
+/// "PAUSED" banner for everyone while a referee has the match paused.
+fn pause_banner(
+    mut contexts: EguiContexts,
+    paused_query: Query<(), With<MatchPaused>>,
+) {
+    if paused_query.is_empty() { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("pause_banner")));
+    painter.text(
+        egui::pos2(screen.center().x, screen.height() * 0.35),
+        egui::Align2::CENTER_CENTER,
+        "PAUSED",
+        cinzel_black(48.0),
+        cream(1.0),
+    );
+    painter.text(
+        egui::pos2(screen.center().x, screen.height() * 0.35 + 40.0),
+        egui::Align2::CENTER_CENTER,
+        "The referee has paused the match",
+        chakra(16.0),
+        cream(0.6),
+    );
+}
+
+// ========================================
+// Message of the day
+// ========================================
+
+/// The server's join message, shown until dismissed. None when there's nothing
+/// to show (or it was dismissed).
+#[derive(Resource, Default)]
+struct MotdPanel(Option<MotdMessage>);
+
+/// Stores the MOTD the server sends on connect.
+fn receive_motd(
+    mut receiver_query: Query<&mut MessageReceiver<MotdMessage>>,
+    mut panel: ResMut<MotdPanel>,
+) {
+    for mut receiver in receiver_query.iter_mut() {
+        for motd in receiver.receive() {
+            if motd.text.is_none() && motd.rules.is_empty() {
+                continue;
+            }
+            info!("[MOTD] {:?} rules={:?}", motd.text, motd.rules);
+            panel.0 = Some(motd);
+        }
+    }
+}
+
+/// Join panel with the server's MOTD and active rules. Enter or the button
+/// dismisses it.
+fn motd_panel(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<MotdPanel>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let Some(motd) = panel.0.as_ref() else { return; };
+    if keys.just_pressed(KeyCode::Enter) {
+        panel.0 = None;
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let mut dismissed = false;
+    egui::Window::new("Welcome")
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.set_max_width(420.0);
+            if let Some(text) = &motd.text {
+                ui.label(egui::RichText::new(text).font(chakra(14.0)).color(egui::Color32::WHITE));
+            }
+            if !motd.rules.is_empty() {
+                ui.add_space(6.0);
+                ui.label(egui::RichText::new("RULES").font(chakra_semi(12.0)).color(cream(0.7)));
+                for rule in &motd.rules {
+                    ui.label(egui::RichText::new(format!("• {}", rule)).font(chakra(12.0)).color(cream(0.9)));
+                }
+            }
+            ui.add_space(8.0);
+            dismissed = ui.button("Got it (Enter)").clicked();
+        });
+    if dismissed {
+        panel.0 = None;
+    }
+}
+
+/// Lines kept in the chat log.
+const CHAT_LOG_LINES: usize = 8;
+/// Typed into chat, asks the server to move us to the other team instead of
+/// being sent as a line.
+const TEAM_SWAP_COMMAND: &str = "/team";
+/// Seconds the server's answer to a team swap stays up.
+const TEAM_SWAP_NOTICE_SECS: f32 = 4.0;
+/// Seconds a chat line stays visible while the box is closed.
+const CHAT_LINE_SECS: f32 = 10.0;
+
+/// Client-only: received chat lines (with arrival time) and the line being typed.
+#[derive(Resource, Default)]
+struct ChatLog {
+    lines: std::collections::VecDeque<(ChatBroadcast, f32)>,
+    draft: String,
+}
+
+fn receive_chat(
+    mut receiver_query: Query<&mut MessageReceiver<ChatBroadcast>>,
+    mut log: ResMut<ChatLog>,
+    time: Res<Time>,
+) {
+    for mut receiver in receiver_query.iter_mut() {
+        for line in receiver.receive() {
+            log.lines.push_back((line, time.elapsed_secs()));
+            if log.lines.len() > CHAT_LOG_LINES {
+                log.lines.pop_front();
+            }
+        }
+    }
+}
+
+/// Client-only: the server's last answer to a team swap and when it came.
+#[derive(Resource, Default)]
+struct TeamSwapNotice(Option<(TeamSwapReplyMessage, f32)>);
+
+/// Shows the server's answer to `/team` under the crosshair for a few seconds.
+fn team_swap_notice(
+    mut contexts: EguiContexts,
+    mut receiver_query: Query<&mut MessageReceiver<TeamSwapReplyMessage>>,
+    mut notice: ResMut<TeamSwapNotice>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for mut receiver in receiver_query.iter_mut() {
+        for reply in receiver.receive() {
+            notice.0 = Some((reply, now));
+        }
+    }
+    let Some((reply, at)) = &notice.0 else { return; };
+    if now - at > TEAM_SWAP_NOTICE_SECS {
+        notice.0 = None;
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("team_swap")));
+    let color = if reply.accepted { cream(0.9) } else { egui::Color32::from_rgb(230, 110, 90) };
+    painter.text(
+        egui::pos2(screen.center().x, screen.center().y + 90.0),
+        egui::Align2::CENTER_CENTER,
+        &reply.text,
+        chakra_semi(16.0),
+        color,
+    );
+}
+
+/// Client-only: the server's idle warning — seconds left when it came, and when.
+#[derive(Resource, Default)]
+struct AfkNotice(Option<(u32, f32)>);
+
+/// Counts down to the server's AFK kick under the crosshair until we move,
+/// look around or press something.
+fn afk_notice(
+    mut contexts: EguiContexts,
+    mut receiver_query: Query<&mut MessageReceiver<AfkWarningMessage>>,
+    player_query: Query<&ActionState<PlayerActions>, With<Controlled>>,
+    mut notice: ResMut<AfkNotice>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for mut receiver in receiver_query.iter_mut() {
+        for warning in receiver.receive() {
+            notice.0 = Some((warning.secs_left, now));
+        }
+    }
+    let Some((secs_left, at)) = notice.0 else { return; };
+    let active = player_query.single().is_ok_and(|action| {
+        action.axis_pair(&PlayerActions::Move) != Vec2::ZERO
+            || action.axis_pair(&PlayerActions::Look) != Vec2::ZERO
+            || !action.get_just_pressed().is_empty()
+    });
+    let remaining = secs_left as f32 - (now - at);
+    if active || remaining <= 0.0 {
+        notice.0 = None;
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("afk_notice")));
+    painter.text(
+        egui::pos2(screen.center().x, screen.center().y + 120.0),
+        egui::Align2::CENTER_CENTER,
+        format!("Idle — you'll be kicked in {:.0}s unless you move", remaining.ceil()),
+        chakra_semi(16.0),
+        egui::Color32::from_rgb(230, 110, 90),
+    );
+}
+
+/// Chat log above the inventory, plus the input box while typing. Enter opens
+/// the box (unless the MOTD panel is up — Enter dismisses that first), Enter
+/// again sends and closes it, Escape closes it without sending.
+fn chat_ui(
+    mut contexts: EguiContexts,
+    mut log: ResMut<ChatLog>,
+    mut focus: ResMut<ChatFocus>,
+    motd: Res<MotdPanel>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut sender_query: Query<&mut MessageSender<ChatMessage>, With<Connected>>,
+    mut team_sender_query: Query<&mut MessageSender<TeamSwapRequestMessage>, With<Connected>>,
+) {
+    let opening = !focus.typing && !focus.wheel && motd.0.is_none() && keys.just_pressed(KeyCode::Enter);
+    if opening {
+        focus.typing = true;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    let now = time.elapsed_secs();
+
+    let mut send = None;
+    egui::Area::new(egui::Id::new("chat"))
+        .fixed_pos(egui::pos2(16.0, screen.height() - 340.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            ui.set_width(360.0);
+            for (line, at) in &log.lines {
+                if !focus.typing && now - at > CHAT_LINE_SECS {
+                    continue;
+                }
+                ui.label(
+                    egui::RichText::new(format!("Player {}: {}", line.sender, line.text))
+                        .font(chakra(13.0))
+                        .color(cream(0.9)),
+                );
+            }
+            if !focus.typing {
+                return;
+            }
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut log.draft)
+                    .char_limit(MAX_CHAT_LEN)
+                    .desired_width(360.0)
+                    .hint_text("Say something"),
+            );
+            if opening {
+                response.request_focus();
+            } else if response.lost_focus() {
+                // Enter or Escape (or a click elsewhere) ends typing
+                if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    send = Some(std::mem::take(&mut log.draft));
+                }
+                log.draft.clear();
+                focus.typing = false;
+            }
+        });
+
+    let Some(text) = send.filter(|t| !t.trim().is_empty()) else { return; };
+    if text.trim() == TEAM_SWAP_COMMAND {
+        for mut sender in team_sender_query.iter_mut() {
+            sender.send::<TeamChannel>(TeamSwapRequestMessage);
+        }
+        return;
+    }
+    for mut sender in sender_query.iter_mut() {
+        sender.send::<ChatChannel>(ChatMessage { text: text.clone() });
+    }
+}
+
+/// Mouse travel (counts) from the wheel's center before a segment is picked.
+const QUICK_CHAT_DEADZONE: f32 = 20.0;
+/// Cap on how far the wheel pointer can wander, so reversing is quick.
+const QUICK_CHAT_MAX_AIM: f32 = 100.0;
+const QUICK_CHAT_RADIUS: f32 = 120.0;
+
+/// Client-only: mouse motion accumulated since the quick-chat wheel opened.
+#[derive(Resource, Default)]
+struct QuickChatWheel {
+    aim: Vec2,
+}
+
+impl QuickChatWheel {
+    /// Segment the pointer is in (0 = top, clockwise), if it's out of the dead zone.
+    fn selected(&self, segments: usize) -> Option<usize> {
+        if segments == 0 || self.aim.length() < QUICK_CHAT_DEADZONE {
+            return None;
+        }
+        // Screen y grows downward, so "up" is -y
+        let angle = self.aim.x.atan2(-self.aim.y).rem_euclid(std::f32::consts::TAU);
+        let step = std::f32::consts::TAU / segments as f32;
+        Some(((angle / step).round() as usize) % segments)
+    }
+}
+
+/// Hold the quick-chat key to open a wheel of preset phrases, move the mouse
+/// toward one and release to send it as a chat line. Releasing in the middle
+/// cancels.
+fn quick_chat_wheel(
+    mut contexts: EguiContexts,
+    mut wheel: ResMut<QuickChatWheel>,
+    mut focus: ResMut<ChatFocus>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<multiplayer::settings::KeyBindings>,
+    settings: Res<multiplayer::settings::QuickChatSettings>,
+    mouse_motion: Res<bevy::input::mouse::AccumulatedMouseMotion>,
+    mut sender_query: Query<&mut MessageSender<ChatMessage>, With<Connected>>,
+) {
+    if focus.typing { return; }
+    let segments = settings.phrases.len();
+    if keys.just_released(bindings.quick_chat) && focus.wheel {
+        focus.wheel = false;
+        if let Some(phrase) = wheel.selected(segments).map(|i| settings.phrases[i].clone()) {
+            for mut sender in sender_query.iter_mut() {
+                sender.send::<ChatChannel>(ChatMessage { text: phrase.clone() });
+            }
+        }
+        wheel.aim = Vec2::ZERO;
+        return;
+    }
+    if !keys.pressed(bindings.quick_chat) || segments == 0 { return; }
+    focus.wheel = true;
+    wheel.aim = (wheel.aim + mouse_motion.delta).clamp_length_max(QUICK_CHAT_MAX_AIM);
+
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let center = ctx.screen_rect().center();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("quick_chat")));
+    painter.circle_filled(center, QUICK_CHAT_RADIUS + 40.0, egui::Color32::from_rgba_unmultiplied(10, 10, 14, 150));
+    let selected = wheel.selected(segments);
+    let step = std::f32::consts::TAU / segments as f32;
+    for (i, phrase) in settings.phrases.iter().enumerate() {
+        let angle = i as f32 * step;
+        let pos = center + egui::vec2(angle.sin(), -angle.cos()) * QUICK_CHAT_RADIUS;
+        let active = selected == Some(i);
+        if active {
+            painter.circle_filled(pos, 34.0, blue(0.5));
+        }
+        painter.text(
+            pos,
+            egui::Align2::CENTER_CENTER,
+            phrase,
+            chakra_semi(if active { 15.0 } else { 13.0 }),
+            cream(if active { 1.0 } else { 0.75 }),
+        );
+    }
+    // Pointer, scaled from mouse travel to the wheel
+    let pointer = center + egui::vec2(wheel.aim.x, wheel.aim.y) / QUICK_CHAT_MAX_AIM * (QUICK_CHAT_RADIUS * 0.6);
+    painter.circle_filled(pointer, 4.0, cream(0.9));
+}
+
+// ========================================
+// Player spawn
+// ========================================
+
+/// F4: toggle collider wireframes (local player, remote players, world, sensors).
+fn toggle_collider_debug(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut gizmo_store: ResMut<GizmoConfigStore>,
+) {
+    if !keys.just_pressed(KeyCode::F4) { return; }
+    let (config, _) = gizmo_store.config_mut::<avian3d::prelude::PhysicsGizmos>();
+    config.enabled = !config.enabled;
+    info!("[DEBUG] Collider wireframes {}", if config.enabled { "on" } else { "off" });
+}
+
+// ========================================
+// Graphics settings
+// ========================================
+
+fn msaa_from_samples(samples: u32) -> Msaa {
+    match samples {
+        2 => Msaa::Sample2,
+        4 => Msaa::Sample4,
+        8 => Msaa::Sample8,
+        _ => Msaa::Off,
+    }
+}
+
+/// Applies `GraphicsSettings` to the local player's cameras whenever the
+/// settings, the cameras or the window size change.
+///
+/// Below a render scale of 1.0 both 3D cameras render into an offscreen image
+/// at the reduced resolution, which `UpscaleCamera` stretches over the window.
+/// egui draws on its own camera afterwards, so the HUD stays at native
+/// resolution.
+fn apply_graphics_settings(
+    settings: Res<multiplayer::settings::GraphicsSettings>,
+    mut target: ResMut<ScaledRenderTarget>,
+    window_query: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    world_camera: Query<Entity, With<WorldModelCamera>>,
+    overlay_cameras: Query<Entity, Or<(With<ViewModelCamera>, With<TeammateOutlineCamera>)>>,
+    new_cameras: Query<(), Or<(Added<WorldModelCamera>, Added<ViewModelCamera>, Added<TeammateOutlineCamera>)>>,
+    upscale_query: Query<Entity, With<UpscaleCamera>>,
+    mut images: ResMut<Assets<Image>>,
+    mut commands: Commands,
+) {
+    use bevy::anti_alias::{fxaa::Fxaa, taa::TemporalAntiAliasing};
+    use bevy::camera::RenderTarget;
+    use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+
+    let Ok(window) = window_query.single() else { return; };
+    let scaled = settings.render_scale < 1.0;
+    let size = settings.scaled_size(window.physical_width(), window.physical_height());
+    let resized = scaled && target.size != size;
+    if !settings.is_changed() && new_cameras.is_empty() && !resized {
+        return;
+    }
+
+    // Offscreen target + upscale pass, or straight to the window
+    let render_target = if scaled {
+        if resized || target.image.is_none() {
+            let mut image = Image::new_fill(
+                Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+                TextureDimension::D2,
+                &[0, 0, 0, 255],
+                TextureFormat::Rgba8UnormSrgb,
+                bevy::asset::RenderAssetUsages::default(),
+            );
+            image.texture_descriptor.usage =
+                TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+            target.image = Some(images.add(image));
+            target.size = size;
+            for entity in upscale_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            let handle = target.image.clone().unwrap();
+            let camera = commands
+                .spawn((
+                    UpscaleCamera,
+                    Camera2d,
+                    Camera { order: 3, ..default() },
+                    IsDefaultUiCamera,
+                ))
+                .id();
+            commands.spawn((
+                UpscaleCamera,
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                ImageNode::new(handle),
+                UiTargetCamera(camera),
+            ));
+            info!("[GRAPHICS] Rendering at {}x{} ({:.0}%)", size.0, size.1, settings.render_scale * 100.0);
+        }
+        RenderTarget::Image(target.image.clone().unwrap().into())
+    } else {
+        if target.image.take().is_some() {
+            for entity in upscale_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            info!("[GRAPHICS] Rendering at native resolution");
+        }
+        RenderTarget::default()
+    };
+
+    // All 3D cameras share the target, so they must agree on MSAA
+    let msaa = msaa_from_samples(settings.effective_msaa_samples());
+    for entity in overlay_cameras.iter() {
+        commands.entity(entity).insert((msaa, render_target.clone()));
+    }
+    for entity in world_camera.iter() {
+        let mut camera = commands.entity(entity);
+        camera
+            .insert((msaa, render_target.clone()))
+            .remove::<Fxaa>()
+            .remove_with_requires::<TemporalAntiAliasing>();
+        match settings.anti_aliasing {
+            multiplayer::settings::AntiAliasing::Off => {}
+            multiplayer::settings::AntiAliasing::Fxaa => {
+                camera.insert(Fxaa::default());
+            }
+            multiplayer::settings::AntiAliasing::Taa => {
+                camera.insert(TemporalAntiAliasing::default());
+            }
+        }
+    }
+}
+
+/// F7 toggles the graphics panel: MSAA, post-process AA and render scale.
+fn graphics_panel(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<multiplayer::settings::GraphicsSettings>,
+    mut visible: Local<bool>,
+) {
+    use multiplayer::settings::{AntiAliasing, MIN_RENDER_SCALE, MSAA_SAMPLES};
+
+    if keys.just_pressed(KeyCode::F7) {
+        *visible = !*visible;
+    }
+    if !*visible { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    // Edit a copy so change detection only fires on real changes
+    let mut edited = settings.clone();
+    egui::Window::new("Graphics")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-16.0, 16.0))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("MSAA");
+                for samples in MSAA_SAMPLES {
+                    let label = if samples == 1 { "Off".to_string() } else { format!("{}x", samples) };
+                    ui.radio_value(&mut edited.msaa_samples, samples, label);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Anti-aliasing");
+                for aa in AntiAliasing::ALL {
+                    ui.radio_value(&mut edited.anti_aliasing, aa, aa.label());
+                }
+            });
+            if edited.anti_aliasing == AntiAliasing::Taa {
+                ui.label("MSAA is disabled while TAA is active");
+            }
+            ui.checkbox(&mut edited.teammate_outlines, "Teammate outlines through walls");
+            ui.add(
+                egui::Slider::new(&mut edited.render_scale, MIN_RENDER_SCALE..=1.0)
+                    .text("Render scale")
+                    .step_by(0.05),
+            );
+        });
+    if edited != *settings {
+        *settings = edited;
+    }
+}
+
+/// Slider range for horizontal sensitivity, in cm of mouse travel per 360°.
+const CM_PER_360_RANGE: std::ops::RangeInclusive<f32> = 2.0..=80.0;
+/// Slider range for vertical sensitivity as a fraction of horizontal.
+const VERTICAL_RATIO_RANGE: std::ops::RangeInclusive<f32> = 0.2..=2.0;
+
+/// Client-only: what the Escape menu shows.
+#[derive(Default)]
+struct EscapeMenu {
+    open: bool,
+    options: bool,
+}
+
+/// Escape (which also frees the cursor) opens a small menu; its Options
+/// page has the mouse sensitivity sliders, invert-Y and the recoil trainer
+/// toggle. Edits go straight
+/// into PlayerSettings (saved by persist_player_settings) and from there
+/// into our CameraSensitivity. Grabbing the cursor again closes the menu.
+/// Runs after grab_mouse, so on the Escape press it already sees the cursor
+/// freed and opens.
+fn escape_menu(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    chat_focus: Res<ChatFocus>,
+    mut cursor_state: ResMut<CursorState>,
+    mut settings: ResMut<multiplayer::settings::PlayerSettings>,
+    mut menu: Local<EscapeMenu>,
+) {
+    if keys.just_pressed(KeyCode::Escape) && !chat_focus.typing {
+        menu.open = !cursor_state.locked;
+        menu.options = false;
+    }
+    if cursor_state.locked {
+        menu.open = false;
+    }
+    if !menu.open { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    egui::Window::new("Menu")
+        .resizable(false)
+        .collapsible(false)
+        .title_bar(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                if ui.button(egui::RichText::new("Resume").font(chakra(14.0))).clicked() {
+                    cursor_state.locked = true;
+                }
+                if ui.button(egui::RichText::new("Options").font(chakra(14.0))).clicked() {
+                    menu.options = !menu.options;
+                }
+            });
+            if !menu.options { return; }
+
+            ui.separator();
+            // Edit a copy so change detection only fires on real changes
+            let mut edited = settings.clone();
+            ui.add(
+                egui::Slider::new(&mut edited.cm_per_360, CM_PER_360_RANGE)
+                    .text("Horizontal (cm/360°)")
+                    .logarithmic(true),
+            );
+            ui.add(
+                egui::Slider::new(&mut edited.vertical_ratio, VERTICAL_RATIO_RANGE)
+                    .text("Vertical (× horizontal)")
+                    .step_by(0.05),
+            );
+            ui.add(
+                egui::Slider::new(&mut edited.mouse_dpi, 100.0..=12800.0)
+                    .text("Mouse DPI")
+                    .logarithmic(true),
+            );
+            ui.checkbox(&mut edited.invert_y, "Invert Y");
+            ui.checkbox(&mut edited.recoil_trainer, "Recoil trainer");
+            if edited != *settings {
+                *settings = edited;
+            }
+        });
+}
+
+/// Client-only: keeps our CameraSensitivity in step with PlayerSettings, so
+/// sensitivity edits apply immediately.
+fn sync_camera_sensitivity(
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    mut query: Query<&mut CameraSensitivity, With<Controlled>>,
+) {
+    if !settings.is_changed() { return; }
+    for mut sensitivity in query.iter_mut() {
+        *sensitivity = CameraSensitivity::from_settings(&settings);
+    }
+}
+
+/// Most control points a look curve can have.
+const MAX_LOOK_CURVE_POINTS: usize = 8;
+
+/// F6 toggles the look curve editor: pick a preset, or drag the points to
+/// shape how sensitivity scales with mouse speed / stick deflection. The ends
+/// only move up and down; points in between stay between their neighbours.
+fn look_curve_panel(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<multiplayer::settings::PlayerSettings>,
+    mut visible: Local<bool>,
+) {
+    use multiplayer::settings::{LookCurvePreset, MAX_LOOK_CURVE_MULTIPLIER};
+
+    if keys.just_pressed(KeyCode::F6) {
+        *visible = !*visible;
+    }
+    if !*visible { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    // Edit a copy so change detection only fires on real changes
+    let mut curve = settings.look_curve.clone();
+    egui::Window::new("Look curve")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-16.0, 220.0))
+        .show(ctx, |ui| {
+            let preset = curve.preset();
+            ui.horizontal(|ui| {
+                for option in LookCurvePreset::ALL {
+                    if ui.radio(preset == Some(option), option.label()).clicked() {
+                        curve = option.curve();
+                    }
+                }
+                if preset.is_none() {
+                    ui.label("(custom)");
+                }
+            });
+
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(260.0, 140.0), egui::Sense::hover());
+            let to_screen = |[x, y]: [f32; 2]| {
+                egui::pos2(rect.left() + x * rect.width(), rect.bottom() - y / MAX_LOOK_CURVE_MULTIPLIER * rect.height())
+            };
+            let painter = ui.painter_at(rect);
+            painter.rect_filled(rect, 4.0, egui::Color32::from_rgba_unmultiplied(0, 0, 0, 120));
+            // Reference line at 1x
+            painter.line_segment([to_screen([0.0, 1.0]), to_screen([1.0, 1.0])], egui::Stroke::new(1.0, cream(0.2)));
+            let samples: Vec<egui::Pos2> = (0..=64)
+                .map(|i| {
+                    let x = i as f32 / 64.0;
+                    to_screen([x, curve.multiplier(x)])
+                })
+                .collect();
+            painter.add(egui::Shape::line(samples, egui::Stroke::new(2.0, blue(0.9))));
+
+            let last = curve.points.len().saturating_sub(1);
+            for i in 0..curve.points.len() {
+                let center = to_screen(curve.points[i]);
+                let handle = egui::Rect::from_center_size(center, egui::vec2(14.0, 14.0));
+                let response = ui.interact(handle, ui.id().with(("look_curve_point", i)), egui::Sense::drag());
+                if let Some(pointer) = response.interact_pointer_pos().filter(|_| response.dragged()) {
+                    let y = (rect.bottom() - pointer.y) / rect.height() * MAX_LOOK_CURVE_MULTIPLIER;
+                    curve.points[i][1] = y.clamp(0.0, MAX_LOOK_CURVE_MULTIPLIER);
+                    if i != 0 && i != last {
+                        let x = (pointer.x - rect.left()) / rect.width();
+                        curve.points[i][0] = x.clamp(curve.points[i - 1][0], curve.points[i + 1][0]);
+                    }
+                }
+                let radius = if response.hovered() || response.dragged() { 6.0 } else { 4.0 };
+                painter.circle_filled(center, radius, cream(0.95));
+            }
+
+            ui.horizontal(|ui| {
+                let can_add = curve.points.len() < MAX_LOOK_CURVE_POINTS;
+                if ui.add_enabled(can_add, egui::Button::new("Add point")).clicked() {
+                    // Split the widest segment at its midpoint
+                    let widest = curve
+                        .points
+                        .windows(2)
+                        .enumerate()
+                        .max_by(|(_, a), (_, b)| (a[1][0] - a[0][0]).total_cmp(&(b[1][0] - b[0][0])))
+                        .map(|(i, _)| i);
+                    if let Some(i) = widest {
+                        let x = (curve.points[i][0] + curve.points[i + 1][0]) / 2.0;
+                        curve.points.insert(i + 1, [x, curve.multiplier(x)]);
+                    }
+                }
+                let can_remove = curve.points.len() > 2;
+                if ui.add_enabled(can_remove, egui::Button::new("Remove point")).clicked() {
+                    curve.points.remove(curve.points.len() - 2);
+                }
+            });
+            ui.label(
+                egui::RichText::new("Left: slow aim · right: fast flicks / full stick. Height is the sensitivity multiplier.")
+                    .font(chakra(11.0))
+                    .color(cream(0.6)),
+            );
+        });
+    curve.normalize();
+    if curve != settings.look_curve {
+        settings.look_curve = curve;
+    }
+}
+
+/// Log health changes for debugging.
+fn log_health_changes(
+    query: Query<(Entity, &PlayerHealth, Has<Controlled>), Changed<PlayerHealth>>,
+) {
+    for (entity, health, is_local) in query.iter() {
+        let tag = if is_local { "LOCAL" } else { "REMOTE" };
+        info!("[HEALTH] {} {:?} health={}", tag, entity, health.0);
+    }
+}
+
+/// HUD: health bar at the bottom-center of the screen, with a thin stamina
+/// bar under it while stamina is below full.
+fn health_hud(
+    mut contexts: EguiContexts,
+    player_query: Query<(&PlayerHealth, Option<&Stamina>), With<Controlled>>,
+    hardcore_query: Query<(), With<Hardcore>>,
+) {
+    // Hardcore: no health readout
+    if !hardcore_query.is_empty() { return; }
+    let Ok((health, stamina)) = player_query.single() else { return; };
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let screen = ctx.screen_rect();
+    let bar_w = 200.0;
+    let bar_h = 16.0;
+    let bar_x = (screen.width() - bar_w) / 2.0;
+    let bar_y = screen.height() - 50.0;
+
+    egui::Area::new(egui::Id::new("health_hud"))
+        .fixed_pos(egui::pos2(bar_x, bar_y))
+        .order(egui::Order::Foreground)
+        .interactable(false)
+        .show(ctx, |ui| {
+            let hp = health.0.max(0) as f32;
+            let pct = (hp / 100.0).clamp(0.0, 1.0);
+
+            // Bar color: green → yellow → red as health drops
+            let color = if pct > 0.5 {
+                egui::Color32::from_rgb(50, 200, 80)
+            } else if pct > 0.25 {
+                egui::Color32::from_rgb(220, 180, 30)
+            } else {
+                egui::Color32::from_rgb(220, 40, 40)
+            };
+
+            let (rect, _) = ui.allocate_exact_size(
+                egui::vec2(bar_w, bar_h),
+                egui::Sense::hover(),
+            );
+
+            // Background
+            ui.painter().rect_filled(rect, 4.0, egui::Color32::from_rgba_unmultiplied(0, 0, 0, 160));
+            // Health fill
+            let fill_rect = egui::Rect::from_min_size(
+                rect.min,
+                egui::vec2(bar_w * pct, bar_h),
+            );
+            ui.painter().rect_filled(fill_rect, 4.0, color);
+            // Border
+            ui.painter().rect_stroke(rect, 4.0, egui::Stroke::new(1.0, egui::Color32::from_white_alpha(80)), egui::StrokeKind::Outside);
+            // Text
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                format!("{}", health.0.max(0)),
+                chakra(12.0),
+                egui::Color32::WHITE,
+            );
+
+            if let Some(stamina) = stamina.filter(|s| s.0 < 1.0) {
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(bar_w, 4.0), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 2.0, egui::Color32::from_rgba_unmultiplied(0, 0, 0, 160));
+                let fill_rect = egui::Rect::from_min_size(rect.min, egui::vec2(bar_w * stamina.0.clamp(0.0, 1.0), 4.0));
+                ui.painter().rect_filled(fill_rect, 2.0, egui::Color32::from_rgb(230, 230, 120));
+            }
+        });
+}
+
+/// Client-only: the `--crosshair-image` texture, while it is (being) loaded.
+#[derive(Resource)]
+struct CrosshairImage(Handle<Image>);
+
+fn load_crosshair_image(
+    mut commands: Commands,
+    settings: Res<multiplayer::settings::CrosshairSettings>,
+    asset_server: Res<AssetServer>,
+) {
+    if let Some(ref path) = settings.image {
+        info!("[HUD] Crosshair image: {}", path);
+        commands.insert_resource(CrosshairImage(asset_server.load(path.clone())));
+    }
+}
+
+/// Crosshair — small cross at screen center when a gun is equipped, or the
+/// `--crosshair-image` texture once it has loaded.
+fn crosshair_hud(
+    mut contexts: EguiContexts,
+    player_query: Query<&PlayerEquipped, With<Controlled>>,
+    settings: Res<multiplayer::settings::CrosshairSettings>,
+    image: Option<Res<CrosshairImage>>,
+    images: Res<Assets<Image>>,
+) {
+    let Ok(equipped) = player_query.single() else { return; };
+    let Some(ref name) = equipped.0 else { return; };
+    // Only show crosshair for guns
+    if !multiplayer::world::ItemKind::of(name).is_weapon() {
+        return;
+    }
+    // A missing or broken image never loads, which leaves the drawn cross
+    let texture = image
+        .filter(|image| images.contains(&image.0))
+        .map(|image| contexts.add_image(bevy_egui::EguiTextureHandle::Strong(image.0.clone())));
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    let center = egui::pos2(screen.width() / 2.0, screen.height() / 2.0);
+    let color = egui::Color32::from_rgba_unmultiplied(255, 255, 255, 180);
+    let stroke = egui::Stroke::new(1.5, color);
+    let size = 8.0;
+    let gap = 3.0;
+
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("crosshair"),
+    ));
+
+    if let Some(texture) = texture {
+        painter.image(
+            texture,
+            egui::Rect::from_center_size(center, egui::Vec2::splat(settings.size)),
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+        return;
+    }
+
+    // Horizontal lines
+    painter.line_segment([egui::pos2(center.x - size, center.y), egui::pos2(center.x - gap, center.y)], stroke);
+    painter.line_segment([egui::pos2(center.x + gap, center.y), egui::pos2(center.x + size, center.y)], stroke);
+    // Vertical lines
+    painter.line_segment([egui::pos2(center.x, center.y - size), egui::pos2(center.x, center.y - gap)], stroke);
+    painter.line_segment([egui::pos2(center.x, center.y + gap), egui::pos2(center.x, center.y + size)], stroke);
+    // Center dot
+    painter.circle_filled(center, 1.0, color);
+}
+
+/// Inventory HUD — bottom-left, shows equipped item, carried inventory and
+/// gathered materials.
+fn inventory_hud(
+    mut contexts: EguiContexts,
+    player_query: Query<(&PlayerEquipped, &PlayerInventory, Option<&Resources>), With<Controlled>>,
+    mut frame_count: Local<u32>,
+) {
+    *frame_count += 1;
+    if *frame_count <= 2 { return; }
+    let Ok((equipped, inventory, resources)) = player_query.single() else { return; };
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let ore = resources.map_or(0, |r| r.ore);
+
+    // Only show if the player has something equipped, in inventory or gathered
+    if equipped.0.is_none() && inventory.items.is_empty() && ore == 0 {
+        return;
+    }
+
+    let screen = ctx.screen_rect();
+
+    egui::Area::new(egui::Id::new("inventory_hud"))
+        .fixed_pos(egui::pos2(16.0, screen.height() - 140.0))
+        .order(egui::Order::Foreground)
+        .interactable(false)
+        .show(ctx, |ui| {
+            let bg = egui::Color32::from_rgba_unmultiplied(0, 0, 0, 140);
+            let frame = egui::Frame::NONE
+                .fill(bg)
+                .inner_margin(egui::Margin::same(8))
+                .corner_radius(4.0);
+
+            frame.show(ui, |ui| {
+                // Equipped item
+                if let Some(ref name) = equipped.0 {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(">")
+                                .font(chakra(13.0))
+                                .color(egui::Color32::from_rgb(100, 160, 255)),
+                        );
+                        ui.label(
+                            egui::RichText::new(name)
+                                .font(chakra(13.0))
+                                .color(egui::Color32::WHITE),
+                        );
+                    });
+                }
+
+                // Inventory items
+                if !inventory.items.is_empty() {
+                    if equipped.0.is_some() {
+                        ui.add_space(2.0);
+                        ui.separator();
+                        ui.add_space(2.0);
+                    }
+                    for item in &inventory.items {
+                        ui.label(
+                            egui::RichText::new(format!("  {}", item))
+                                .font(chakra(11.0))
+                                .color(cream(0.6)),
+                        );
+                    }
+                }
+
+                // Materials
+                if ore > 0 {
+                    if equipped.0.is_some() || !inventory.items.is_empty() {
+                        ui.add_space(2.0);
+                        ui.separator();
+                        ui.add_space(2.0);
+                    }
+                    ui.label(
+                        egui::RichText::new(format!("Ore  {}", ore))
+                            .font(chakra(11.0))
+                            .color(egui::Color32::from_rgb(200, 150, 90)),
+                    );
+                }
+            });
+        });
+}
+
+/// The inventory key (KeyBindings::inventory, I by default) toggles a panel
+/// listing what we carry, with a count per item name and per material, and
+/// buttons to drop ore as a loose chunk.
+fn inventory_panel(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<multiplayer::settings::KeyBindings>,
+    chat_focus: Res<ChatFocus>,
+    player_query: Query<(&PlayerEquipped, &PlayerInventory, Option<&Resources>), With<Controlled>>,
+    mut sender_query: Query<&mut MessageSender<DropOreMessage>, With<Connected>>,
+    mut visible: Local<bool>,
+) {
+    if keys.just_pressed(bindings.inventory) && !chat_focus.typing {
+        *visible = !*visible;
+    }
+    if !*visible { return; }
+    let Ok((equipped, inventory, resources)) = player_query.single() else { return; };
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let mut stacks: Vec<(&String, u32)> = Vec::new();
+    for item in equipped.0.iter().chain(inventory.items.iter()) {
+        match stacks.iter_mut().find(|(name, _)| *name == item) {
+            Some((_, count)) => *count += 1,
+            None => stacks.push((item, 1)),
+        }
+    }
+    let ore = resources.map_or(0, |r| r.ore);
+
+    let mut drop = None;
+    egui::Window::new("Inventory")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(16.0, -160.0))
+        .show(ctx, |ui| {
+            if stacks.is_empty() && ore == 0 {
+                ui.label(egui::RichText::new("Empty").font(chakra(12.0)).color(cream(0.5)));
+            }
+            egui::Grid::new("inventory_grid").num_columns(2).show(ui, |ui| {
+                for (name, count) in &stacks {
+                    let held = equipped.0.as_ref() == Some(*name);
+                    let color = if held { egui::Color32::WHITE } else { cream(0.7) };
+                    ui.label(egui::RichText::new(name.as_str()).font(chakra(12.0)).color(color));
+                    ui.label(egui::RichText::new(format!("x{}", count)).font(chakra(12.0)).color(color));
+                    ui.end_row();
+                }
+                if ore > 0 {
+                    ui.label(egui::RichText::new("Ore").font(chakra(12.0)).color(egui::Color32::from_rgb(200, 150, 90)));
+                    ui.label(egui::RichText::new(format!("x{}", ore)).font(chakra(12.0)).color(egui::Color32::from_rgb(200, 150, 90)));
+                    ui.end_row();
+                }
+            });
+            if ore > 0 {
+                ui.horizontal(|ui| {
+                    if ui.button("Drop 1 ore").clicked() {
+                        drop = Some(1);
+                    }
+                    if ui.button("Drop all ore").clicked() {
+                        drop = Some(ore);
+                    }
+                });
+            }
+        });
+
+    if let Some(count) = drop {
+        for mut sender in sender_query.iter_mut() {
+            sender.send::<InventoryChannel>(DropOreMessage { count });
+        }
+        info!("[INVENTORY] Dropping {} ore", count);
+    }
+}
+
+/// Number keys that buy the matching `TRADE_OFFERS` entry at a station.
+const TRADE_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4, KeyCode::Digit5,
+    KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+];
+
+/// Buy menu shown while we stand at a trading station. Number keys send a
+/// TradeRequestMessage (not while typing in chat); the server checks range
+/// and price again.
+fn trade_station_menu(
+    mut contexts: EguiContexts,
+    player_query: Query<(&avian3d::prelude::Position, Option<&Resources>), (With<Controlled>, Without<PlayerDead>)>,
+    stations: Query<(&multiplayer::world::TradeStation, &avian3d::prelude::Position)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    chat_focus: Res<ChatFocus>,
+    mut sender_query: Query<&mut MessageSender<TradeRequestMessage>, With<Connected>>,
+) {
+    let Ok((position, resources)) = player_query.single() else { return; };
+    let in_range = stations
+        .iter()
+        .any(|(station, station_pos)| position.0.distance(station_pos.0) <= station.interaction_distance);
+    if !in_range { return; }
+    let resources = resources.copied().unwrap_or_default();
+
+    for (index, key) in TRADE_KEYS.iter().enumerate().take(TRADE_OFFERS.len()) {
+        if !keys.just_pressed(*key) || chat_focus.typing { continue; }
+        for mut sender in sender_query.iter_mut() {
+            sender.send::<TradeChannel>(TradeRequestMessage { offer: index as u32 });
+        }
+        info!("[TRADE] Requested {}", TRADE_OFFERS[index].item);
+    }
+
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    egui::Area::new(egui::Id::new("trade_station_menu"))
+        .fixed_pos(egui::pos2(screen.width() - 236.0, screen.height() / 2.0 - 60.0))
+        .order(egui::Order::Foreground)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::NONE
+                .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 160))
+                .inner_margin(egui::Margin::same(10))
+                .corner_radius(4.0)
+                .show(ui, |ui| {
+                    ui.set_width(200.0);
+                    ui.label(egui::RichText::new("TRADING STATION").font(chakra(13.0)).color(egui::Color32::WHITE));
+                    ui.label(
+                        egui::RichText::new(format!("You have {} ore", resources.ore))
+                            .font(chakra(11.0))
+                            .color(egui::Color32::from_rgb(200, 150, 90)),
+                    );
+                    ui.add_space(4.0);
+                    for (index, offer) in TRADE_OFFERS.iter().enumerate().take(TRADE_KEYS.len()) {
+                        let color = if offer.affordable(&resources) { egui::Color32::WHITE } else { cream(0.4) };
+                        ui.label(
+                            egui::RichText::new(format!("[{}] {} — {} ore", index + 1, offer.item, offer.ore_cost))
+                                .font(chakra(12.0))
+                                .color(color),
+                        );
+                    }
+                });
+        });
+}
+
+/// Profile panel — lifetime stats for the local player, toggled with P.
+/// PlayerStats is loaded by the server from its stats file on connect.
+fn profile_panel(
+    mut contexts: EguiContexts,
+    player_query: Query<&multiplayer::protocol::PlayerStats, With<Controlled>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    chat_focus: Res<ChatFocus>,
+    mut visible: Local<bool>,
+) {
+    if keys.just_pressed(KeyCode::KeyP) && !chat_focus.typing {
+        *visible = !*visible;
+    }
+    if !*visible { return; }
+    let Ok(stats) = player_query.single() else { return; };
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let screen = ctx.screen_rect();
+
+    egui::Area::new(egui::Id::new("profile_panel"))
+        .fixed_pos(egui::pos2(screen.right() - 220.0, 16.0))
+        .order(egui::Order::Foreground)
+        .interactable(false)
+        .show(ctx, |ui| {
+            let bg = egui::Color32::from_rgba_unmultiplied(0, 0, 0, 160);
+            let frame = egui::Frame::NONE
+                .fill(bg)
+                .inner_margin(egui::Margin::same(10))
+                .corner_radius(4.0);
+
+            frame.show(ui, |ui| {
+                ui.set_min_width(180.0);
+                ui.label(
+                    egui::RichText::new("PROFILE")
+                        .font(cinzel_bold(16.0))
+                        .color(cream(1.0)),
+                );
+                ui.add_space(4.0);
+
+                let kd = stats.kills as f32 / stats.deaths.max(1) as f32;
+                let rows = [
+                    ("Kills", stats.kills.to_string()),
+                    ("Deaths", stats.deaths.to_string()),
+                    ("Assists", stats.assists.to_string()),
+                    ("Rounds won", stats.wins.to_string()),
+                    ("K/D", format!("{:.2}", kd)),
+                    ("Ore mined", stats.ore_mined.to_string()),
+                    ("Distance", format!("{:.0} m", stats.distance_traveled)),
+                ];
+                for (label, value) in rows {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(label)
+                                .font(chakra(12.0))
+                                .color(cream(0.6)),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(
+                                egui::RichText::new(value)
+                                    .font(chakra_semi(12.0))
+                                    .color(egui::Color32::WHITE),
+                            );
+                        });
+                    });
+                }
+            });
+        });
+}
+
+/// Build version — bottom-right corner, always visible, muted gray.
+/// Version from Cargo.toml + short git commit hash baked in at compile time.
+fn build_version_hud(mut contexts: EguiContexts) {
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+
+    let version = concat!("v", env!("CARGO_PKG_VERSION"), "-", env!("GIT_SHORT_HASH"));
+
+    ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("build_version"),
+    ))
+    .text(
+        egui::pos2(screen.right() - 12.0, screen.bottom() - 14.0),
+        egui::Align2::RIGHT_BOTTOM,
+        version,
+        chakra(10.0),
+        egui::Color32::from_rgba_unmultiplied(180, 180, 180, 60),
+    );
+}
+
+/// Death screen overlay — shown when the controlled player has PlayerDead.
+/// Counts down the server's respawn delay (`RespawnDelay`, or the default).
+fn death_screen(
+    mut contexts: EguiContexts,
+    player_query: Query<(Has<multiplayer::protocol::PlayerDead>, Has<Eliminated>), With<Controlled>>,
+    hardcore_query: Query<(), With<Hardcore>>,
+    wave_query: Query<&RespawnWave>,
+    delay_query: Query<&RespawnDelay>,
+    spectator_query: Query<(), With<SpectatorCamera>>,
+    time: Res<Time>,
+    mut death_start: Local<Option<f32>>,
+    mut frame_count: Local<u32>,
+) {
+    *frame_count += 1;
+    if *frame_count <= 2 { return; }
+    let Ok((is_dead, is_eliminated)) = player_query.single() else { return; };
+
+    if !is_dead {
+        *death_start = None;
+        return;
+    }
+
+    let now = time.elapsed_secs();
+    let start = *death_start.get_or_insert(now);
+    let elapsed = now - start;
+    let delay = delay_query
+        .single()
+        .map(|d| d.secs)
+        .unwrap_or(multiplayer::rules::DEFAULT_RESPAWN_DELAY);
+    let remaining = (delay - elapsed).max(0.0).ceil() as u32;
+
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("death_overlay")));
+
+    // Dark red overlay — kept faint and out of the way while spectating
+    let spectating = !spectator_query.is_empty();
+    painter.rect_filled(
+        screen,
+        0.0,
+        egui::Color32::from_rgba_unmultiplied(80, 0, 0, if spectating { 40 } else { 140 }),
+    );
+    let title_pos = if spectating {
+        egui::pos2(screen.center().x, screen.height() * 0.2)
+    } else {
+        screen.center()
+    };
+    // "YOU DIED" text
+    painter.text(
+        title_pos,
+        egui::Align2::CENTER_CENTER,
+        "YOU DIED",
+        cinzel_black(72.0),
+        egui::Color32::from_rgb(220, 40, 40),
+    );
+    // Countdown timer (elimination: out until the round ends; hardcore: none)
+    if !hardcore_query.is_empty() { return; }
+    let subtitle = if is_eliminated {
+        "Eliminated — spectating until the round ends".to_string()
+    } else if let Ok(wave) = wave_query.single() {
+        // Wave respawns: everyone comes back together with the next wave
+        format!("Respawning with the next wave in {}s", wave.next_in_secs)
+    } else {
+        format!("Respawning in {}s", remaining)
+    };
+    painter.text(
+        title_pos + egui::vec2(0.0, 60.0),
+        egui::Align2::CENTER_CENTER,
+        subtitle,
+        chakra(16.0),
+        cream(0.5),
+    );
+}
+
+/// Seconds the headshot-only hit/miss marker stays on screen.
+const TRAINING_FEEDBACK_SECS: f32 = 0.6;
+
+/// Headshot-only training: accuracy readout under the crosshair, plus a
+/// hit marker per shot — "HEADSHOT" in red, "BODY" greyed out (no damage),
+/// "MISS" — derived from changes to our replicated TrainingStats.
+fn training_hud(
+    mut contexts: EguiContexts,
+    mode_query: Query<(), With<HeadshotOnly>>,
+    player_query: Query<&TrainingStats, With<Controlled>>,
+    time: Res<Time>,
+    mut last_stats: Local<TrainingStats>,
+    mut feedback: Local<Option<(&'static str, egui::Color32, f32)>>,
+) {
+    if mode_query.is_empty() { return; }
+    let Ok(stats) = player_query.single() else { return; };
+    let now = time.elapsed_secs();
+
+    if *stats != *last_stats {
+        *feedback = if stats.headshots > last_stats.headshots {
+            Some(("HEADSHOT", egui::Color32::from_rgb(235, 60, 45), now))
+        } else if stats.body_hits > last_stats.body_hits {
+            Some(("BODY — no damage", egui::Color32::from_rgb(150, 150, 150), now))
+        } else if stats.shots > last_stats.shots {
+            Some(("MISS", cream(0.6), now))
+        } else {
+            *feedback
+        };
+        *last_stats = *stats;
+    }
+
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    let center = screen.center();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("training_hud")));
+
+    if let Some((label, color, at)) = *feedback {
+        let age = now - at;
+        if age < TRAINING_FEEDBACK_SECS {
+            let alpha = 1.0 - age / TRAINING_FEEDBACK_SECS;
+            let color = color.gamma_multiply(alpha);
+            if label == "HEADSHOT" {
+                // Hit marker: four diagonal ticks around the crosshair
+                let stroke = egui::Stroke::new(2.0, color);
+                for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+                    painter.line_segment(
+                        [egui::pos2(center.x + dx * 5.0, center.y + dy * 5.0), egui::pos2(center.x + dx * 11.0, center.y + dy * 11.0)],
+                        stroke,
+                    );
+                }
+            }
+            painter.text(
+                egui::pos2(center.x, center.y + 28.0),
+                egui::Align2::CENTER_CENTER,
+                label,
+                chakra_semi(14.0),
+                color,
+            );
+        }
+    }
+
+    painter.text(
+        egui::pos2(center.x, screen.height() - 140.0),
+        egui::Align2::CENTER_CENTER,
+        format!(
+            "Accuracy {:.0}%  ·  {} / {} headshots  ·  {} body",
+            stats.accuracy(),
+            stats.headshots,
+            stats.shots,
+            stats.body_hits
+        ),
+        chakra(14.0),
+        cream(0.85),
+    );
+}
+
+/// Seconds the recoil trainer plot stays up after the last shot of a burst.
+const RECOIL_TRAINER_HOLD_SECS: f32 = 2.0;
+/// Recoil trainer plot scale, in pixels per radian of aim.
+const RECOIL_TRAINER_SCALE: f32 = 1500.0;
+/// Most shots of one burst the recoil trainer keeps.
+const RECOIL_TRAINER_MAX_SHOTS: usize = 30;
+
+/// Client-only: `--recoil-trainer` record of the current burst — the weapon,
+/// our [yaw, pitch] at its first shot and our aim offset from there at each
+/// shot since.
+#[derive(Resource, Default)]
+struct RecoilTrainer {
+    weapon: Option<String>,
+    start: Vec2,
+    drift: Vec<Vec2>,
+    last_shot: f32,
+}
+
+/// Client-only observer: notes our aim at each of our shots with a weapon
+/// that has a spread pattern. A pause longer than the pattern's reset time
+/// (or another weapon) starts a new burst, like `weapons::burst_index`.
+fn record_recoil_trainer_shot(
+    _trigger: On<multiplayer::world::ShotFired>,
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    player_query: Query<(&PlayerEquipped, &PlayerYaw, &PlayerPitch), With<Controlled>>,
+    mut trainer: ResMut<RecoilTrainer>,
+    time: Res<Time>,
+) {
+    if !settings.recoil_trainer { return; }
+    let Ok((equipped, yaw, pitch)) = player_query.single() else { return; };
+    let Some(name) = equipped.0.as_deref() else { return; };
+    let Some(pattern) = multiplayer::weapons::weapon_spec(name).and_then(|w| w.spread) else { return; };
+    let now = time.elapsed_secs();
+    let aim = Vec2::new(yaw.0, pitch.0);
+    let same_burst = trainer.weapon.as_deref() == Some(name)
+        && now - trainer.last_shot <= pattern.reset_secs
+        && trainer.drift.len() < RECOIL_TRAINER_MAX_SHOTS;
+    if !same_burst {
+        trainer.weapon = Some(name.to_string());
+        trainer.start = aim;
+        trainer.drift.clear();
+    }
+    let start = trainer.start;
+    trainer.drift.push(aim - start);
+    trainer.last_shot = now;
+}
+
+/// `--recoil-trainer`: while firing (and RECOIL_TRAINER_HOLD_SECS after),
+/// plots next to the crosshair the aim path that cancels the weapon's spread
+/// pattern (the reverse of the pattern) against the path our aim actually
+/// took, with a dot where each round really went. A perfect pull keeps
+/// every dot on the center mark.
+fn recoil_trainer_hud(
+    mut contexts: EguiContexts,
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    trainer: Res<RecoilTrainer>,
+    time: Res<Time>,
+) {
+    if !settings.recoil_trainer || trainer.drift.is_empty() { return; }
+    let age = time.elapsed_secs() - trainer.last_shot;
+    if age > RECOIL_TRAINER_HOLD_SECS { return; }
+    let Some(name) = trainer.weapon.as_deref() else { return; };
+    let Some(pattern) = multiplayer::weapons::weapon_spec(name).and_then(|w| w.spread) else { return; };
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let screen = ctx.screen_rect();
+    let rect = egui::Rect::from_center_size(
+        egui::pos2(screen.center().x + 180.0, screen.center().y),
+        egui::vec2(180.0, 180.0),
+    );
+    let origin = egui::pos2(rect.center().x, rect.top() + 40.0);
+    // Positive yaw turns left and positive pitch looks up
+    let to_screen = |aim: Vec2| egui::pos2(origin.x - aim.x * RECOIL_TRAINER_SCALE, origin.y - aim.y * RECOIL_TRAINER_SCALE);
+    let fade = 1.0 - (age / RECOIL_TRAINER_HOLD_SECS).clamp(0.0, 1.0) * 0.7;
+
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("recoil_trainer")));
+    painter.rect_filled(rect, 4.0, egui::Color32::from_rgba_unmultiplied(0, 0, 0, (110.0 * fade) as u8));
+    painter.text(
+        egui::pos2(rect.center().x, rect.top() + 6.0),
+        egui::Align2::CENTER_TOP,
+        "RECOIL",
+        chakra(10.0),
+        cream(0.6 * fade),
+    );
+
+    let shots = trainer.drift.len().max(pattern.offsets.len());
+    let ideal: Vec<egui::Pos2> = (0..shots)
+        .map(|i| {
+            let [yaw, pitch] = multiplayer::weapons::spread_offset(name, i as u32);
+            to_screen(-Vec2::new(yaw, pitch))
+        })
+        .collect();
+    painter.add(egui::Shape::line(ideal, egui::Stroke::new(2.0, cream(0.35 * fade))));
+
+    let actual: Vec<egui::Pos2> = trainer.drift.iter().map(|d| to_screen(*d)).collect();
+    painter.add(egui::Shape::line(actual.clone(), egui::Stroke::new(2.0, blue(0.9 * fade))));
+    for point in &actual {
+        painter.circle_filled(*point, 2.0, blue(fade));
+    }
+
+    // Where each round went: our drift plus the pattern offset
+    for (i, drift) in trainer.drift.iter().enumerate() {
+        let [yaw, pitch] = multiplayer::weapons::spread_offset(name, i as u32);
+        let landed = *drift + Vec2::new(yaw, pitch);
+        painter.circle_filled(to_screen(landed), 2.5, egui::Color32::from_rgba_unmultiplied(255, 255, 255, (220.0 * fade) as u8));
+    }
+    painter.circle_stroke(origin, 5.0, egui::Stroke::new(1.0, cream(0.8 * fade)));
+}
+
+/// Capture-the-flag scoreboard: team scores at the top of the screen, our
+/// team, and a reminder while we carry the enemy flag.
+fn ctf_hud(
+    mut contexts: EguiContexts,
+    score_query: Query<&CtfScore>,
+    local_query: Query<(&PlayerId, &Team), With<Controlled>>,
+    flag_query: Query<&multiplayer::world::Flag>,
+    compass: Res<multiplayer::settings::CompassSettings>,
+) {
+    let Ok(score) = score_query.single() else { return; };
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("ctf_hud")));
+
+    let team_color = |team: Team| match team {
+        Team::Red => egui::Color32::from_rgb(230, 60, 45),
+        Team::Blue => egui::Color32::from_rgb(60, 110, 240),
+    };
+    let top = 24.0 + hud_top(&compass);
+    painter.text(
+        egui::pos2(screen.center().x - 30.0, top),
+        egui::Align2::RIGHT_CENTER,
+        format!("RED {}", score.red),
+        cinzel_black(22.0),
+        team_color(Team::Red),
+    );
+    painter.text(
+        egui::pos2(screen.center().x + 30.0, top),
+        egui::Align2::LEFT_CENTER,
+        format!("{} BLUE", score.blue),
+        cinzel_black(22.0),
+        team_color(Team::Blue),
+    );
+
+    let Ok((local_id, team)) = local_query.single() else { return; };
+    painter.text(
+        egui::pos2(screen.center().x, top + 24.0),
+        egui::Align2::CENTER_CENTER,
+        format!("You are on {}", team.name()),
+        chakra(13.0),
+        team_color(*team),
+    );
+    if flag_query.iter().any(|flag| flag.carrier == Some(local_id.0)) {
+        painter.text(
+            egui::pos2(screen.center().x, screen.height() * 0.25),
+            egui::Align2::CENTER_CENTER,
+            "You have the flag — return it to your base",
+            chakra_semi(16.0),
+            cream(0.9),
+        );
+    }
+}
+
+/// Warning banner while the entity count is over `--entity-warning`, with the
+/// count, so a screenshot of a runaway-spawn bug carries the number.
+fn entity_warning_hud(
+    mut contexts: EguiContexts,
+    warning: Res<multiplayer::diagnostics::EntityCountWarning>,
+) {
+    if !warning.tripped { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
     let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("entity_warning")));
+    painter.text(
+        egui::pos2(12.0, screen.bottom() - 14.0),
+        egui::Align2::LEFT_BOTTOM,
+        format!("{} entities (warning above {}) — please report this", warning.count, warning.threshold),
+        chakra_semi(13.0),
+        egui::Color32::from_rgb(255, 170, 40),
+    );
+}
 
-    egui::Area::new(egui::Id::new("inventory_hud"))
-        .fixed_pos(egui::pos2(16.0, screen.height() - 140.0))
-        .order(egui::Order::Foreground)
-        .interactable(false)
-        .show(ctx, |ui| {
-            let bg = egui::Color32::from_rgba_unmultiplied(0, 0, 0, 140);
-            let frame = egui::Frame::NONE
-                .fill(bg)
-                .inner_margin(egui::Margin::same(8))
-                .corner_radius(4.0);
+/// Height of the compass strip; top-of-screen banners start below it.
+const COMPASS_HEIGHT: f32 = 30.0;
+const COMPASS_WIDTH: f32 = 480.0;
 
-            frame.show(ui, |ui| {
-                // Equipped item
-                if let Some(ref name) = equipped.0 {
-                    ui.horizontal(|ui| {
-                        ui.label(
-                            egui::RichText::new(">")
-                                .font(chakra(13.0))
-                                .color(egui::Color32::from_rgb(100, 160, 255)),
-                        );
-                        ui.label(
-                            egui::RichText::new(name)
-                                .font(chakra(13.0))
-                                .color(egui::Color32::WHITE),
-                        );
-                    });
-                }
+/// Offset for HUD text anchored to the top of the screen, so it clears the compass.
+fn hud_top(compass: &multiplayer::settings::CompassSettings) -> f32 {
+    if compass.enabled { COMPASS_HEIGHT } else { 0.0 }
+}
 
-                // Inventory items
-                if !inventory.items.is_empty() {
-                    if equipped.0.is_some() {
-                        ui.add_space(2.0);
-                        ui.separator();
-                        ui.add_space(2.0);
-                    }
-                    for item in &inventory.items {
-                        ui.label(
-                            egui::RichText::new(format!("  {}", item))
-                                .font(chakra(11.0))
-                                .color(cream(0.6)),
-                        );
-                    }
-                }
-            });
-        });
+/// Compass heading (radians, clockwise from north = -Z) of a horizontal direction.
+fn compass_heading(dir: Vec3) -> f32 {
+    dir.x.atan2(-dir.z)
 }
 
-/// Build version — bottom-right corner, always visible, muted gray.
-/// Version from Cargo.toml + short git commit hash baked in at compile time.
-fn build_version_hud(mut contexts: EguiContexts) {
+/// Compass strip along the top of the screen: cardinal directions and ticks
+/// slide with our yaw, and objectives (flags, trading stations) sit at their
+/// bearing from us. Markers outside the visible arc are pinned to its edge.
+fn compass_hud(
+    mut contexts: EguiContexts,
+    settings: Res<multiplayer::settings::CompassSettings>,
+    player_query: Query<(&avian3d::prelude::Position, &PlayerYaw), With<Controlled>>,
+    flag_query: Query<(&multiplayer::world::Flag, &avian3d::prelude::Position)>,
+    station_query: Query<&avian3d::prelude::Position, With<multiplayer::world::TradeStation>>,
+) {
+    use std::f32::consts::{PI, TAU};
+
+    if !settings.enabled { return; }
+    let Ok((position, yaw)) = player_query.single() else { return; };
     let Ok(ctx) = contexts.ctx_mut() else { return; };
     let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("compass_hud")));
 
-    let version = concat!("v", env!("CARGO_PKG_VERSION"), "-", env!("GIT_SHORT_HASH"));
+    let strip = egui::Rect::from_center_size(
+        egui::pos2(screen.center().x, 4.0 + COMPASS_HEIGHT / 2.0),
+        egui::vec2(COMPASS_WIDTH, COMPASS_HEIGHT - 8.0),
+    );
+    painter.rect_filled(strip, 3.0, egui::Color32::from_rgba_unmultiplied(10, 10, 14, 120));
+
+    // Facing -Z at yaw 0, turning left as yaw grows
+    let heading = -yaw.0;
+    let half_arc = settings.arc_degrees.to_radians() / 2.0;
+    // Bearing relative to our heading, in -PI..PI (negative = to our left)
+    let relative = |bearing: f32| (bearing - heading + PI).rem_euclid(TAU) - PI;
+    // Horizontal position on the strip for a bearing, or None outside the arc
+    let strip_x = |bearing: f32| {
+        let relative = relative(bearing);
+        (relative.abs() <= half_arc).then(|| strip.center().x + relative / half_arc * strip.width() / 2.0)
+    };
 
-    ctx.layer_painter(egui::LayerId::new(
-        egui::Order::Foreground,
-        egui::Id::new("build_version"),
-    ))
-    .text(
-        egui::pos2(screen.right() - 12.0, screen.bottom() - 14.0),
-        egui::Align2::RIGHT_BOTTOM,
-        version,
-        chakra(10.0),
-        egui::Color32::from_rgba_unmultiplied(180, 180, 180, 60),
+    for step in 0..24 {
+        let degrees = step * 15;
+        let Some(x) = strip_x((degrees as f32).to_radians()) else { continue; };
+        let label = match degrees {
+            0 => Some("N"),
+            45 => Some("NE"),
+            90 => Some("E"),
+            135 => Some("SE"),
+            180 => Some("S"),
+            225 => Some("SW"),
+            270 => Some("W"),
+            315 => Some("NW"),
+            _ => None,
+        };
+        match label {
+            Some(label) => {
+                painter.text(
+                    egui::pos2(x, strip.center().y),
+                    egui::Align2::CENTER_CENTER,
+                    label,
+                    chakra_bold(if label.len() == 1 { 15.0 } else { 12.0 }),
+                    cream(0.9),
+                );
+            }
+            None => {
+                painter.line_segment(
+                    [egui::pos2(x, strip.bottom() - 6.0), egui::pos2(x, strip.bottom() - 1.0)],
+                    egui::Stroke::new(1.0, cream(0.5)),
+                );
+            }
+        }
+    }
+    // Center notch: where we're looking
+    painter.line_segment(
+        [egui::pos2(strip.center().x, strip.top()), egui::pos2(strip.center().x, strip.top() + 4.0)],
+        egui::Stroke::new(2.0, cream(0.9)),
     );
+
+    let mut markers: Vec<(Vec3, egui::Color32)> = station_query
+        .iter()
+        .map(|p| (p.0, egui::Color32::from_rgb(230, 190, 60)))
+        .collect();
+    markers.extend(flag_query.iter().map(|(flag, p)| {
+        let color = match flag.team {
+            Team::Red => egui::Color32::from_rgb(230, 60, 45),
+            Team::Blue => egui::Color32::from_rgb(60, 110, 240),
+        };
+        (p.0, color)
+    }));
+    for (target, color) in markers {
+        let offset = (target - position.0).with_y(0.0);
+        if offset.length_squared() < 0.01 { continue; }
+        let bearing = compass_heading(offset);
+        // Off the arc: pin to the nearer edge
+        let x = strip_x(bearing)
+            .unwrap_or(if relative(bearing) < 0.0 { strip.left() } else { strip.right() });
+        painter.circle_filled(egui::pos2(x, strip.bottom() + 4.0), 4.0, color);
+    }
 }
 
-/// Death screen overlay — shown when the controlled player has PlayerDead.
-/// Respawn delay must match server's RESPAWN_DELAY.
-const RESPAWN_DELAY: f32 = 20.0;
+/// Tab (hold) scoreboard: one row per player with the columns and sort order
+/// from `ScoreboardSettings`, read from replicated stats, team and ping.
+/// Observers use Tab to cycle the follow camera, so it's hidden for them.
+fn scoreboard(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    chat_focus: Res<ChatFocus>,
+    settings: Res<multiplayer::settings::ScoreboardSettings>,
+    observer: Res<ObserverSession>,
+    players: Query<(&PlayerId, &PlayerDisplayId, Option<&PlayerStats>, Option<&PlayerPing>, Option<&Team>, Has<Bot>, Has<Controlled>)>,
+) {
+    use multiplayer::settings::{ScoreboardColumn, ScoreboardSort};
 
-fn death_screen(
+    if observer.active || chat_focus.typing || !keys.pressed(KeyCode::Tab) { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    // Bots don't keep lifetime stats — they show as zeros
+    let mut rows: Vec<_> = players
+        .iter()
+        .map(|(id, display_id, stats, ping, team, is_bot, is_local)| {
+            let ping = ping.map(|p| p.0).unwrap_or(0);
+            (id.0, display_id.0, stats.cloned().unwrap_or_default(), ping, team.copied(), is_bot, is_local)
+        })
+        .collect();
+    rows.sort_by_key(|(id, ..)| *id);
+    match settings.sort {
+        ScoreboardSort::Score => rows.sort_by_key(|(_, _, stats, ..)| std::cmp::Reverse(stats.score())),
+        ScoreboardSort::Kills => rows.sort_by_key(|(_, _, stats, ..)| std::cmp::Reverse(stats.kills)),
+        ScoreboardSort::Deaths => rows.sort_by_key(|(_, _, stats, ..)| std::cmp::Reverse(stats.deaths)),
+        ScoreboardSort::Ping => rows.sort_by_key(|(_, _, _, ping, ..)| *ping),
+    }
+
+    egui::Area::new(egui::Id::new("scoreboard"))
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 90.0))
+        .order(egui::Order::Foreground)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::NONE
+                .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 180))
+                .inner_margin(egui::Margin::same(12))
+                .corner_radius(4.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("scoreboard_grid").spacing(egui::vec2(24.0, 4.0)).show(ui, |ui| {
+                        let header = |ui: &mut egui::Ui, text: &str| {
+                            ui.label(egui::RichText::new(text).font(chakra_semi(12.0)).color(cream(0.6)));
+                        };
+                        header(ui, "Player");
+                        for column in &settings.columns {
+                            header(ui, column.label());
+                        }
+                        ui.end_row();
+
+                        for (_, display_id, stats, ping, team, is_bot, is_local) in rows {
+                            let color = if is_local { egui::Color32::WHITE } else { cream(0.85) };
+                            let cell = |ui: &mut egui::Ui, text: String| {
+                                ui.label(egui::RichText::new(text).font(chakra(13.0)).color(color));
+                            };
+                            cell(ui, format!("Player {}", display_id));
+                            for column in &settings.columns {
+                                let text = match column {
+                                    ScoreboardColumn::Team => team.map(|t| t.name()).unwrap_or("-").to_string(),
+                                    ScoreboardColumn::Kills => stats.kills.to_string(),
+                                    ScoreboardColumn::Deaths => stats.deaths.to_string(),
+                                    ScoreboardColumn::Assists => stats.assists.to_string(),
+                                    ScoreboardColumn::Score => stats.score().to_string(),
+                                    ScoreboardColumn::Ping if is_bot => "BOT".to_string(),
+                                    ScoreboardColumn::Ping => format!("{} ms", ping),
+                                };
+                                cell(ui, text);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+        });
+}
+
+/// Downed state HUD. While we are downed: a dim overlay with the bleed-out
+/// countdown. While standing next to a downed player: a "Hold E to revive"
+/// prompt (with the bound interact key) that becomes a progress bar while it
+/// is held.
+/// Timers are measured locally — the replicated Downed timestamps are in
+/// server time.
+fn revive_hud(
     mut contexts: EguiContexts,
-    player_query: Query<Has<multiplayer::protocol::PlayerDead>, With<Controlled>>,
+    local_query: Query<(&avian3d::prelude::Position, Option<&Downed>, Has<PlayerDead>), With<Controlled>>,
+    downed_query: Query<(&avian3d::prelude::Position, &Downed), Without<Controlled>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    chat_focus: Res<ChatFocus>,
+    bindings: Res<multiplayer::settings::KeyBindings>,
     time: Res<Time>,
-    mut death_start: Local<Option<f32>>,
-    mut frame_count: Local<u32>,
+    mut downed_start: Local<Option<f32>>,
+    mut hold_start: Local<Option<f32>>,
 ) {
-    *frame_count += 1;
-    if *frame_count <= 2 { return; }
-    let Ok(is_dead) = player_query.single() else { return; };
+    let Ok((local_pos, local_downed, is_dead)) = local_query.single() else { return; };
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let now = time.elapsed_secs();
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("revive_hud")));
 
-    if !is_dead {
-        *death_start = None;
+    if let Some(downed) = local_downed {
+        *hold_start = None;
+        let start = *downed_start.get_or_insert(now);
+        let remaining = (downed.window - (now - start)).max(0.0).ceil() as u32;
+        painter.rect_filled(screen, 0.0, egui::Color32::from_rgba_unmultiplied(60, 0, 0, 90));
+        painter.text(
+            egui::pos2(screen.center().x, screen.height() * 0.3),
+            egui::Align2::CENTER_CENTER,
+            "DOWNED",
+            cinzel_black(48.0),
+            egui::Color32::from_rgb(220, 60, 40),
+        );
+        let subtitle = if downed.revive_start_secs.is_some() {
+            "Being revived…".to_string()
+        } else {
+            format!("Bleeding out in {}s — a nearby player can revive you", remaining)
+        };
+        painter.text(
+            egui::pos2(screen.center().x, screen.height() * 0.3 + 40.0),
+            egui::Align2::CENTER_CENTER,
+            subtitle,
+            chakra(16.0),
+            cream(0.7),
+        );
         return;
     }
+    *downed_start = None;
+    if is_dead { return; }
+    // Interact is released on the server side while we type
+    let holding = keys.pressed(bindings.interact) && !chat_focus.typing;
 
-    let now = time.elapsed_secs();
-    let start = *death_start.get_or_insert(now);
-    let elapsed = now - start;
-    let remaining = (RESPAWN_DELAY - elapsed).max(0.0).ceil() as u32;
+    let nearby = downed_query
+        .iter()
+        .any(|(pos, downed)| !downed.bled_out && pos.0.distance(local_pos.0) <= multiplayer::rules::REVIVE_DISTANCE);
+    if !nearby || !holding {
+        *hold_start = None;
+    }
+    if !nearby { return; }
+
+    let center = egui::pos2(screen.center().x, screen.height() - 110.0);
+    match *hold_start {
+        None if !holding => {
+            let hint = format!("Hold {} to revive", multiplayer::settings::key_label(bindings.interact));
+            painter.text(center, egui::Align2::CENTER_CENTER, hint, chakra_semi(15.0), cream(0.9));
+        }
+        _ => {
+            let start = *hold_start.get_or_insert(now);
+            let pct = ((now - start) / multiplayer::rules::REVIVE_TIME).clamp(0.0, 1.0);
+            let rect = egui::Rect::from_center_size(center, egui::vec2(200.0, 12.0));
+            painter.rect_filled(rect, 4.0, egui::Color32::from_rgba_unmultiplied(0, 0, 0, 160));
+            painter.rect_filled(
+                egui::Rect::from_min_size(rect.min, egui::vec2(rect.width() * pct, rect.height())),
+                4.0,
+                egui::Color32::from_rgb(50, 200, 80),
+            );
+            painter.text(
+                egui::pos2(center.x, center.y - 18.0),
+                egui::Align2::CENTER_CENTER,
+                "Reviving…",
+                chakra_semi(13.0),
+                cream(0.9),
+            );
+        }
+    }
+}
 
+/// Round banner — elimination mode only (a MatchState entity exists).
+/// Shows the round number at the top, and the winner during intermission.
+fn round_banner(
+    mut contexts: EguiContexts,
+    match_query: Query<&MatchState>,
+    compass: Res<multiplayer::settings::CompassSettings>,
+) {
+    let Ok(match_state) = match_query.single() else { return; };
     let Ok(ctx) = contexts.ctx_mut() else { return; };
 
     let screen = ctx.screen_rect();
-    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("death_overlay")));
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("round_banner")));
+
+    painter.text(
+        egui::pos2(screen.center().x, 18.0 + hud_top(&compass)),
+        egui::Align2::CENTER_TOP,
+        format!("ROUND {}", match_state.round),
+        cinzel_bold(18.0),
+        cream(0.8),
+    );
+
+    if match_state.intermission {
+        let result = match match_state.last_winner {
+            Some(ref winner) => format!("{} WINS THE ROUND", winner),
+            None => "DRAW".to_string(),
+        };
+        painter.text(
+            egui::pos2(screen.center().x, screen.height() * 0.25),
+            egui::Align2::CENTER_CENTER,
+            result,
+            cinzel_black(40.0),
+            cream(1.0),
+        );
+        painter.text(
+            egui::pos2(screen.center().x, screen.height() * 0.25 + 40.0),
+            egui::Align2::CENTER_CENTER,
+            "Next round starting…",
+            chakra(16.0),
+            cream(0.6),
+        );
+    }
+}
+
+/// Hazard overlay — pulsing orange screen edges while the server reports the
+/// controlled player standing in a damage volume (InHazard).
+fn hazard_overlay(
+    mut contexts: EguiContexts,
+    player_query: Query<(Has<InHazard>, Has<multiplayer::protocol::PlayerDead>), With<Controlled>>,
+    time: Res<Time>,
+) {
+    let Ok((in_hazard, is_dead)) = player_query.single() else { return; };
+    if !in_hazard || is_dead { return; }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
 
-    // Dark red overlay
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Background, egui::Id::new("hazard_overlay")));
+
+    let pulse = 0.5 + 0.5 * (time.elapsed_secs() * 6.0).sin();
+    let edge = 60.0;
+    let steps = 12;
+    for i in 0..steps {
+        let t = i as f32 / steps as f32;
+        let alpha = ((1.0 - t) * (60.0 + 60.0 * pulse)) as u8;
+        let inset = t * edge;
+        painter.rect_stroke(
+            screen.shrink(inset),
+            0.0,
+            egui::Stroke::new(edge / steps as f32, egui::Color32::from_rgba_unmultiplied(255, 90, 0, alpha)),
+            egui::StrokeKind::Inside,
+        );
+    }
+}
+
+/// Flashbang overlay — the screen goes white when the server flashes us,
+/// holds for most of the duration, then fades out.
+fn flash_overlay(
+    mut contexts: EguiContexts,
+    player_query: Query<Option<Ref<multiplayer::utility::Flashed>>, With<Controlled>>,
+    time: Res<Time>,
+    mut flash: Local<Option<(f32, f32)>>,
+) {
+    let now = time.elapsed_secs();
+    if let Ok(Some(flashed)) = player_query.single() {
+        if flashed.is_changed() {
+            *flash = Some((now, flashed.secs));
+        }
+    }
+    let Some((start, secs)) = *flash else { return; };
+    let t = (now - start) / secs.max(0.01);
+    if t >= 1.0 {
+        *flash = None;
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    // Full white for the first 60%, then fade
+    let alpha = if t < 0.6 { 1.0 } else { 1.0 - (t - 0.6) / 0.4 };
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("flash_overlay")));
     painter.rect_filled(
-        screen,
+        ctx.screen_rect(),
         0.0,
-        egui::Color32::from_rgba_unmultiplied(80, 0, 0, 140),
-    );
-    // "YOU DIED" text
-    painter.text(
-        screen.center(),
-        egui::Align2::CENTER_CENTER,
-        "YOU DIED",
-        cinzel_black(72.0),
-        egui::Color32::from_rgb(220, 40, 40),
+        egui::Color32::from_rgba_unmultiplied(255, 255, 255, (alpha * 255.0) as u8),
     );
-    // Countdown timer
+}
+
+/// Utility readout (bottom-right): the selected throwable, how many of each
+/// are left, and the throw / cycle keys.
+fn utility_hud(
+    mut contexts: EguiContexts,
+    bindings: Res<multiplayer::settings::KeyBindings>,
+    player_query: Query<&multiplayer::utility::UtilityPouch, (With<Controlled>, Without<PlayerDead>)>,
+) {
+    let Ok(pouch) = player_query.single() else { return; };
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("utility_hud")));
+
+    let mut y = screen.bottom() - 48.0;
+    for kind in multiplayer::utility::UtilityKind::ALL.into_iter().rev() {
+        let selected = kind == pouch.selected;
+        let count = pouch.count(kind);
+        let color = if selected && count > 0 {
+            egui::Color32::WHITE
+        } else {
+            cream(if count > 0 { 0.6 } else { 0.25 })
+        };
+        painter.text(
+            egui::pos2(screen.right() - 16.0, y),
+            egui::Align2::RIGHT_BOTTOM,
+            format!("{}{}  x{}", if selected { "> " } else { "" }, kind.name(), count),
+            if selected { chakra_semi(14.0) } else { chakra(12.0) },
+            color,
+        );
+        y -= 18.0;
+    }
     painter.text(
-        egui::pos2(screen.center().x, screen.center().y + 60.0),
-        egui::Align2::CENTER_CENTER,
-        format!("Respawning in {}s", remaining),
-        chakra(16.0),
+        egui::pos2(screen.right() - 16.0, screen.bottom() - 16.0),
+        egui::Align2::RIGHT_BOTTOM,
+        format!(
+            "{} throw  {} next",
+            multiplayer::settings::key_label(bindings.throw),
+            multiplayer::settings::key_label(bindings.cycle_utility)
+        ),
+        chakra(11.0),
         cream(0.5),
     );
 }
@@ -1065,6 +4011,7 @@ const KILL_FEED_DURATION: f32 = 5.0;
 fn kill_feed_ui(
     mut contexts: EguiContexts,
     feed_query: Query<&multiplayer::protocol::KillFeedEntry>,
+    streak_query: Query<&multiplayer::protocol::KillStreakEntry>,
     time: Res<Time>,
     mut frame_count: Local<u32>,
 ) {
@@ -1075,12 +4022,16 @@ fn kill_feed_ui(
     let now = time.elapsed_secs();
     let screen = ctx.screen_rect();
 
-    // Collect recent kills (within KILL_FEED_DURATION seconds)
-    let mut entries: Vec<&multiplayer::protocol::KillFeedEntry> = feed_query
+    // Collect recent kills and streak announcements (within KILL_FEED_DURATION seconds)
+    let mut entries: Vec<(f32, String)> = feed_query
         .iter()
-        .filter(|e| now - e.timestamp < KILL_FEED_DURATION)
+        .map(|e| (e.timestamp, format!("{} killed {}", e.killer_name, e.victim_name)))
+        .chain(streak_query.iter().map(|e| {
+            (e.timestamp, format!("{} is on a {}-kill streak — {}", e.player_name, e.streak, e.reward))
+        }))
+        .filter(|(timestamp, _)| now - timestamp < KILL_FEED_DURATION)
         .collect();
-    entries.sort_by(|a, b| b.timestamp.partial_cmp(&a.timestamp).unwrap());
+    entries.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
 
     if entries.is_empty() { return; }
 
@@ -1089,12 +4040,11 @@ fn kill_feed_ui(
         egui::Id::new("kill_feed"),
     ));
 
-    for (i, entry) in entries.iter().take(5).enumerate() {
+    for (i, (timestamp, text)) in entries.into_iter().take(5).enumerate() {
         let y = screen.bottom() - 90.0 - (i as f32 * 24.0);
-        let alpha = ((KILL_FEED_DURATION - (now - entry.timestamp)) / KILL_FEED_DURATION).clamp(0.0, 1.0);
+        let alpha = ((KILL_FEED_DURATION - (now - timestamp)) / KILL_FEED_DURATION).clamp(0.0, 1.0);
 
         // Background pill
-        let text = format!("{} killed {}", entry.killer_name, entry.victim_name);
         let text_galley = painter.layout_no_wrap(text.clone(), chakra(13.0), cream(alpha));
         let text_w = text_galley.size().x;
         let pill_rect = egui::Rect::from_center_size(
@@ -1122,6 +4072,10 @@ fn on_predicted_spawn(
     trigger: On<Add, (PlayerId, Predicted)>,
     query: Query<(&PlayerId, Has<Controlled>)>,
     position_query: Query<&avian3d::prelude::Position>,
+    settings: Res<multiplayer::settings::PlayerSettings>,
+    bindings: Res<multiplayer::settings::KeyBindings>,
+    gamepad: Res<multiplayer::settings::GamepadSettings>,
+    base_fov: Res<BaseFov>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
@@ -1151,24 +4105,42 @@ fn on_predicted_spawn(
         return;
     }
 
-    commands.entity(entity).insert(CameraSensitivity::default());
+    commands.entity(entity).insert(CameraSensitivity::from_settings(&settings));
 
     let arm = meshes.add(Cuboid::new(0.1, 0.1, 0.5));
     let arm_material = materials.add(Color::from(tailwind::TEAL_200));
 
     commands.entity(entity).with_children(|parent| {
+        parent
+            .spawn((
+                WorldModelCamera,
+                Camera3d::default(),
+                Projection::from(PerspectiveProjection {
+                    fov: base_fov.0,
+                    ..default()
+                }),
+            ))
+            .with_children(|camera| {
+                camera.spawn((
+                    TeammateOutlineCamera,
+                    Camera3d::default(),
+                    Camera {
+                        order: 1,
+                        clear_color: ClearColorConfig::None,
+                        ..default()
+                    },
+                    Projection::from(PerspectiveProjection {
+                        fov: 90.0_f32.to_radians(),
+                        ..default()
+                    }),
+                    RenderLayers::layer(TEAMMATE_OUTLINE_RENDER_LAYER),
+                ));
+            });
         parent.spawn((
-            WorldModelCamera,
-            Camera3d::default(),
-            Projection::from(PerspectiveProjection {
-                fov: 90.0_f32.to_radians(),
-                ..default()
-            }),
-        ));
-        parent.spawn((
+            ViewModelCamera,
             Camera3d::default(),
             Camera {
-                order: 1,
+                order: 2,
                 clear_color: ClearColorConfig::None,
                 ..default()
             },
@@ -1200,15 +4172,60 @@ fn on_predicted_spawn(
     // Single InputMap component on the controlled player entity — leafwing reads
     // this each tick to populate `ActionState<PlayerActions>` (which the server
     // then receives via lightyear's leafwing input plugin).
+    commands.entity(entity).insert(player_input_map(&bindings, &gamepad));
+}
+
+/// Input map for our player from the current key bindings, plus the fixed
+/// gamepad layout. Right-stick look is added separately by `apply_gamepad_look`.
+fn player_input_map(
+    bindings: &multiplayer::settings::KeyBindings,
+    gamepad: &multiplayer::settings::GamepadSettings,
+) -> InputMap<PlayerActions> {
     let mut input_map = InputMap::default();
-    input_map.insert_dual_axis(PlayerActions::Move, VirtualDPad::wasd());
+    input_map.insert_dual_axis(
+        PlayerActions::Move,
+        VirtualDPad::new(bindings.forward, bindings.back, bindings.left, bindings.right),
+    );
+    input_map.insert_dual_axis(PlayerActions::Move, GamepadStick::LEFT.with_circle_deadzone(gamepad.deadzone));
     input_map.insert_dual_axis(PlayerActions::Look, MouseMove::default());
-    input_map.insert(PlayerActions::Jump, KeyCode::Space);
-    input_map.insert(PlayerActions::Interact, KeyCode::KeyE);
-    input_map.insert(PlayerActions::Drop, KeyCode::KeyG);
-    input_map.insert(PlayerActions::Jab, KeyCode::KeyQ);
+    input_map.insert(PlayerActions::Jump, bindings.jump);
+    input_map.insert(PlayerActions::Interact, bindings.interact);
+    input_map.insert(PlayerActions::Drop, bindings.drop);
+    input_map.insert(PlayerActions::Jab, bindings.melee);
     input_map.insert(PlayerActions::Primary, MouseButton::Left);
-    commands.entity(entity).insert(input_map);
+    input_map.insert(PlayerActions::Aim, MouseButton::Right);
+    input_map.insert(PlayerActions::Crouch, bindings.crouch);
+    input_map.insert(PlayerActions::Sprint, bindings.sprint);
+    input_map.insert(PlayerActions::LeanLeft, bindings.lean_left);
+    input_map.insert(PlayerActions::LeanRight, bindings.lean_right);
+    input_map.insert(PlayerActions::Throw, bindings.throw);
+    input_map.insert(PlayerActions::CycleUtility, bindings.cycle_utility);
+
+    input_map.insert(PlayerActions::Jump, GamepadButton::South);
+    input_map.insert(PlayerActions::Interact, GamepadButton::West);
+    input_map.insert(PlayerActions::Crouch, GamepadButton::East);
+    input_map.insert(PlayerActions::Drop, GamepadButton::North);
+    input_map.insert(PlayerActions::Primary, GamepadButton::RightTrigger2);
+    input_map.insert(PlayerActions::Aim, GamepadButton::LeftTrigger2);
+    input_map.insert(PlayerActions::Sprint, GamepadButton::LeftThumb);
+    input_map.insert(PlayerActions::Jab, GamepadButton::RightThumb);
+    input_map.insert(PlayerActions::LeanLeft, GamepadButton::DPadLeft);
+    input_map.insert(PlayerActions::LeanRight, GamepadButton::DPadRight);
+    input_map
+}
+
+/// Rebuilds our player's InputMap when KeyBindings (or GamepadSettings) is
+/// edited at runtime.
+fn apply_key_bindings(
+    bindings: Res<multiplayer::settings::KeyBindings>,
+    gamepad: Res<multiplayer::settings::GamepadSettings>,
+    mut query: Query<&mut InputMap<PlayerActions>, With<Controlled>>,
+) {
+    let changed = (bindings.is_changed() && !bindings.is_added()) || (gamepad.is_changed() && !gamepad.is_added());
+    if !changed { return; }
+    for mut input_map in query.iter_mut() {
+        *input_map = player_input_map(&bindings, &gamepad);
+    }
 }
 
 /// Remote player: interpolated entity — smooth, slightly delayed, no rubberbanding.
@@ -1227,12 +4244,236 @@ fn on_interpolated_spawn(
 
     info!("[SPAWN] Remote interpolated player spawned: {:?} (id={})", entity, player_id.0);
 
+    let body_color = Color::srgb(0.8, 0.7, 0.6);
+    let body_mesh = meshes.add(Capsule3d::default());
+    // Shortened from the top, matching player_collider(true)
+    let crouch_mesh = meshes.add(
+        Mesh::from(Capsule3d::new(CAPSULE_RADIUS, CROUCH_CAPSULE_HEIGHT)).translated_by(Vec3::NEG_Y * CROUCH_CENTER_DROP),
+    );
+    // Cheap stand-in drawn past the LOD distance (see update_remote_player_lod)
+    let billboard = commands
+        .spawn((
+            PlayerBillboard,
+            Mesh3d(meshes.add(Rectangle::new(1.0, 2.0))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: body_color,
+                unlit: true,
+                ..default()
+            })),
+            Transform::default(),
+            Visibility::Hidden,
+            NotShadowCaster,
+            RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+        ))
+        .id();
+    let body_material = materials.add(body_color);
+    let silhouette = commands
+        .spawn((
+            TeammateSilhouette,
+            Mesh3d(body_mesh.clone()),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::WHITE.with_alpha(TEAMMATE_SILHOUETTE_ALPHA),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })),
+            Transform::default(),
+            Visibility::Hidden,
+            NotShadowCaster,
+            RenderLayers::layer(TEAMMATE_OUTLINE_RENDER_LAYER),
+        ))
+        .id();
+    let lean_head = commands
+        .spawn((
+            LeanHead,
+            Mesh3d(meshes.add(Sphere::new(LEAN_HEAD_RADIUS))),
+            MeshMaterial3d(body_material.clone()),
+            Transform::default(),
+            Visibility::Hidden,
+            RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+        ))
+        .id();
+
     commands.entity(entity).insert((
         player_physics_bundle(),
         Player { id: player_id.0 },
-        Mesh3d(meshes.add(Capsule3d::default())),
-        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Mesh3d(body_mesh.clone()),
+        MeshMaterial3d(body_material),
         Visibility::default(),
         RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
-    ));
+        RemotePlayerLod { body_mesh, crouch_mesh, billboard, far: false, crouching: false },
+    ))
+    .add_children(&[billboard, lean_head, silhouette]);
+}
+
+/// Opacity of teammate silhouettes drawn through walls.
+const TEAMMATE_SILHOUETTE_ALPHA: f32 = 0.45;
+
+/// Shows the silhouette of living teammates (same Team as us), tinted with
+/// the team color, and keeps the outline camera's FOV in step with the world
+/// camera (ADS zoom, FOV changes). Enemies never get one.
+fn update_teammate_silhouettes(
+    settings: Res<multiplayer::settings::GraphicsSettings>,
+    local_query: Query<&Team, With<Controlled>>,
+    players: Query<(Option<&Team>, Has<PlayerDead>, &Children), With<RemotePlayerLod>>,
+    mut silhouettes: Query<(&mut Visibility, &MeshMaterial3d<StandardMaterial>), With<TeammateSilhouette>>,
+    world_camera: Query<&Projection, (With<WorldModelCamera>, Without<TeammateOutlineCamera>)>,
+    mut outline_camera: Query<&mut Projection, With<TeammateOutlineCamera>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if let (Ok(Projection::Perspective(world)), Ok(mut outline)) = (world_camera.single(), outline_camera.single_mut()) {
+        if let Projection::Perspective(ref mut outline) = outline.as_mut() {
+            if outline.fov != world.fov {
+                outline.fov = world.fov;
+            }
+        }
+    }
+
+    let local_team = local_query.single().ok().copied();
+    for (team, is_dead, children) in players.iter() {
+        let teammate = settings.teammate_outlines && !is_dead && team.is_some() && team.copied() == local_team;
+        for child in children.iter() {
+            let Ok((mut visibility, material)) = silhouettes.get_mut(child) else { continue; };
+            if !teammate {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            }
+            if *visibility != Visibility::Inherited {
+                *visibility = Visibility::Inherited;
+                if let (Some(team), Some(material)) = (team, materials.get_mut(&material.0)) {
+                    let color = match team {
+                        Team::Red => Color::srgb(1.0, 0.3, 0.25),
+                        Team::Blue => Color::srgb(0.3, 0.5, 1.0),
+                    };
+                    material.base_color = color.with_alpha(TEAMMATE_SILHOUETTE_ALPHA);
+                }
+            }
+        }
+    }
+}
+
+/// Remote players: puts the head sphere at their eye offset while they lean
+/// (hidden when upright or drawn as a LOD billboard).
+fn sync_remote_lean_heads(
+    players: Query<(&PlayerStance, &PlayerYaw, &GlobalTransform, &RemotePlayerLod, &Children), With<Interpolated>>,
+    mut heads: Query<(&mut Transform, &mut Visibility), With<LeanHead>>,
+    lean_config: Res<LeanConfig>,
+) {
+    for (stance, yaw, global, lod, children) in players.iter() {
+        for child in children.iter() {
+            let Ok((mut transform, mut visibility)) = heads.get_mut(child) else { continue; };
+            if stance.lean == 0.0 || lod.far {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            }
+            visibility.set_if_neq(Visibility::Inherited);
+            // Eye offset is in world space; the body rotates with yaw/pitch
+            transform.translation = global.rotation().inverse() * stance.eye_offset(yaw.0, &lean_config);
+        }
+    }
+}
+
+/// Remote player LOD: past `player_lod_distance` the body mesh and held item
+/// are swapped for a camera-facing billboard, and swapped back up close.
+/// Up close the body mesh follows their crouch.
+fn update_remote_player_lod(
+    settings: Res<multiplayer::settings::GraphicsSettings>,
+    camera_query: Query<(&Camera, &GlobalTransform), Or<(With<WorldModelCamera>, With<ObserverCamera>, With<SpectatorCamera>)>>,
+    mut players: Query<(Entity, &GlobalTransform, &mut RemotePlayerLod, Option<&PlayerStance>, Option<&Children>)>,
+    mut billboards: Query<(&mut Transform, &mut Visibility), (With<PlayerBillboard>, Without<multiplayer::world::RemoteEquippedItem>)>,
+    mut held_items: Query<&mut Visibility, (With<multiplayer::world::RemoteEquippedItem>, Without<PlayerBillboard>)>,
+    mut commands: Commands,
+) {
+    let Some((_, camera)) = camera_query.iter().find(|(camera, _)| camera.is_active) else { return; };
+    let camera_pos = camera.translation();
+
+    for (entity, transform, mut lod, stance, children) in players.iter_mut() {
+        let to_camera = camera_pos - transform.translation();
+        let far = to_camera.length() > settings.player_lod_distance;
+        let crouching = stance.is_some_and(|s| s.crouching);
+        if far != lod.far || crouching != lod.crouching {
+            lod.far = far;
+            lod.crouching = crouching;
+            if far {
+                commands.entity(entity).remove::<Mesh3d>();
+            } else if crouching {
+                commands.entity(entity).insert(Mesh3d(lod.crouch_mesh.clone()));
+            } else {
+                commands.entity(entity).insert(Mesh3d(lod.body_mesh.clone()));
+            }
+        }
+
+        // Held items are spawned visible — keep them in line with the LOD
+        let item_visibility = if far { Visibility::Hidden } else { Visibility::Inherited };
+        if let Some(children) = children {
+            for child in children.iter() {
+                if let Ok(mut visibility) = held_items.get_mut(child) {
+                    visibility.set_if_neq(item_visibility);
+                }
+            }
+        }
+
+        let Ok((mut billboard, mut visibility)) = billboards.get_mut(lod.billboard) else { continue; };
+        if !far {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+        // Face the camera around the vertical axis, undoing the body's own rotation
+        let yaw = Quat::from_rotation_y(to_camera.x.atan2(to_camera.z));
+        billboard.rotation = transform.rotation().inverse() * yaw;
+    }
+}
+
+/// Nameplates over remote players within `nameplate_distance` that the
+/// camera can actually see: walls and smoke hide the name along with the
+/// player, so nameplates can't be used to spot enemies through cover.
+fn nameplate_hud(
+    mut contexts: EguiContexts,
+    settings: Res<multiplayer::settings::GraphicsSettings>,
+    camera_query: Query<(&Camera, &GlobalTransform), Or<(With<WorldModelCamera>, With<ObserverCamera>, With<SpectatorCamera>)>>,
+    players: Query<(Entity, &GlobalTransform, &PlayerDisplayId), (With<RemotePlayerLod>, Without<PlayerDead>)>,
+    local_player: Query<Entity, With<Controlled>>,
+    smoke_query: Query<(&avian3d::prelude::Position, &multiplayer::utility::SmokeCloud)>,
+    spatial_query: avian3d::prelude::SpatialQuery,
+) {
+    let Some((camera, camera_transform)) = camera_query.iter().find(|(camera, _)| camera.is_active) else { return; };
+    let clouds: Vec<(Vec3, f32)> = smoke_query.iter().map(|(pos, cloud)| (pos.0, cloud.radius)).collect();
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    let screen = ctx.screen_rect();
+    // The world camera may render at a reduced internal resolution
+    let Some(viewport) = camera.logical_viewport_size() else { return; };
+    let scale = egui::vec2(screen.width() / viewport.x, screen.height() / viewport.y);
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Background, egui::Id::new("nameplates")));
+
+    // Our own capsule surrounds the camera; don't let it block every ray
+    let filter = avian3d::prelude::SpatialQueryFilter::from_excluded_entities(local_player.iter());
+
+    for (player, transform, display_id) in players.iter() {
+        let head = transform.translation() + Vec3::Y * 1.3;
+        let eye = camera_transform.translation();
+        let distance = head.distance(eye);
+        if distance > settings.nameplate_distance {
+            continue;
+        }
+        if multiplayer::utility::smoke_between(eye, head, clouds.iter().copied()) {
+            continue;
+        }
+        let Ok(dir) = Dir3::new(head - eye) else { continue; };
+        if spatial_query
+            .cast_ray(eye, dir, distance, true, &filter)
+            .is_some_and(|hit| hit.entity != player)
+        {
+            continue;
+        }
+        let Ok(pos) = camera.world_to_viewport(camera_transform, head) else { continue; };
+        let alpha = (1.0 - distance / settings.nameplate_distance).clamp(0.3, 1.0);
+        painter.text(
+            egui::pos2(pos.x * scale.x, pos.y * scale.y),
+            egui::Align2::CENTER_BOTTOM,
+            format!("Player {}", display_id.0),
+            chakra_semi(13.0),
+            cream(alpha),
+        );
+    }
 }
@@ -0,0 +1,298 @@
+//! Headless load-test client: connects to the server like a player would,
+//! sends random but valid movement / look / jump / fire input every tick and
+//! receives replication, without a window, renderer or audio.
+//!
+//! `--clients <n>` starts n clients (n - 1 extra processes of this binary),
+//! `--duration <secs>` quits after that long. Each client gets a throwaway
+//! keypair, so they all join as separate players. The server address comes
+//! from `ANIMA_SERVER_ADDR` (localhost if unset) and `--transport`.
+//!
+//! ```text
+//! ANIMA_SERVER_ADDR=127.0.0.1 cargo run --release --bin loadtest -- --clients 50
+//! ```
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use leafwing_input_manager::plugin::InputManagerSystem;
+use leafwing_input_manager::prelude::*;
+use lightyear::prelude::client::*;
+use lightyear::prelude::*;
+
+use multiplayer::auth::ClientIdentity;
+use multiplayer::player::{player_physics_bundle, Player, PLAYER_SPAWN_POS};
+use multiplayer::protocol::{AuthChannel, PlayerActions, PlayerDead, PlayerId, WalletAuthMessage};
+use multiplayer::world::spawn_world_physics;
+use multiplayer::{SharedPlugin, FIXED_TIMESTEP_HZ, PROTOCOL_ID, SERVER_PORT};
+
+/// Delay between starting extra client processes, so the server isn't hit
+/// with every handshake in the same instant.
+const SPAWN_STAGGER: Duration = Duration::from_millis(50);
+/// Seconds between status lines.
+const STATUS_INTERVAL: f32 = 5.0;
+/// Each bot keeps a heading for between this many seconds and twice as long.
+const HEADING_SECS: f32 = 1.5;
+/// Per-tick chances of pressing jump / fire, and of sprinting on a new heading.
+const JUMP_CHANCE: f32 = 0.01;
+const FIRE_CHANCE: f32 = 0.05;
+const SPRINT_CHANCE: f32 = 0.3;
+/// Largest random look change per tick (radians).
+const MAX_LOOK_STEP: f32 = 0.03;
+
+/// When to quit (`--duration`), in elapsed seconds.
+#[derive(Resource)]
+struct Deadline(f32);
+
+/// The bot's current wandering plan.
+#[derive(Resource, Default)]
+struct Wander {
+    heading: Vec2,
+    sprint: bool,
+    until: f32,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+    };
+    let clients = value("--clients").and_then(|v| v.parse::<usize>().ok()).unwrap_or(1).max(1);
+    let duration = value("--duration").and_then(|v| v.parse::<f32>().ok()).filter(|d| *d > 0.0);
+    if clients > 1 {
+        spawn_extra_clients(clients - 1, &args);
+    }
+
+    let identity = ClientIdentity::ephemeral();
+
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .build()
+            .set(multiplayer::logging::log_plugin_from_args())
+            .disable::<bevy::winit::WinitPlugin>()
+            .disable::<bevy::render::RenderPlugin>()
+            .disable::<bevy::core_pipeline::CorePipelinePlugin>()
+            .disable::<bevy::pbr::PbrPlugin>()
+            .disable::<bevy::gltf::GltfPlugin>()
+            .disable::<bevy::sprite::SpritePlugin>()
+            .disable::<bevy::ui::UiPlugin>()
+            .disable::<bevy::text::TextPlugin>()
+            .set(bevy::window::WindowPlugin {
+                primary_window: None,
+                primary_cursor_options: None,
+                exit_condition: bevy::window::ExitCondition::DontExit,
+                close_when_requested: false,
+            }),
+    );
+    app.add_plugins(bevy::app::ScheduleRunnerPlugin::run_loop(
+        Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
+    ));
+    app.add_plugins(ClientPlugins {
+        tick_duration: Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
+    });
+    app.add_plugins(SharedPlugin);
+
+    // World colliders so our predicted player stands on the same ground as on the server
+    app.add_systems(Startup, spawn_world_physics);
+    app.insert_resource(multiplayer::transport::transport_from_args());
+    app.insert_resource(identity);
+    app.init_resource::<Wander>();
+    app.add_systems(Startup, connect);
+    app.add_systems(Update, (send_wallet_auth, report_status));
+    app.add_systems(
+        FixedPreUpdate,
+        drive_input
+            .in_set(InputManagerSystem::ManualControl)
+            .before(lightyear::prelude::client::input::InputSystems::BufferClientInputs)
+            .run_if(not(lightyear::prelude::is_in_rollback)),
+    );
+    app.add_observer(on_predicted_spawn);
+    if let Some(secs) = duration {
+        app.insert_resource(Deadline(secs));
+        app.add_systems(Update, quit_at_deadline);
+    }
+    app.run();
+}
+
+/// Starts `count` more copies of this binary with the same args, minus
+/// `--clients`.
+fn spawn_extra_clients(count: usize, args: &[String]) {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("[LOAD] Can't find our own executable, running 1 client: {}", e);
+            return;
+        }
+    };
+    let mut forwarded = Vec::new();
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        if arg == "--clients" {
+            rest.next();
+        } else {
+            forwarded.push(arg.clone());
+        }
+    }
+    for i in 0..count {
+        if let Err(e) = std::process::Command::new(&exe).args(&forwarded).spawn() {
+            eprintln!("[LOAD] Failed to start client {}: {}", i + 2, e);
+        }
+        std::thread::sleep(SPAWN_STAGGER);
+    }
+}
+
+/// Spawns the lightyear client entity and starts connecting.
+fn connect(
+    mut commands: Commands,
+    identity: Res<ClientIdentity>,
+    transport: Res<multiplayer::transport::ActiveTransport>,
+) {
+    let server_ip: Ipv4Addr = std::env::var("ANIMA_SERVER_ADDR")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(Ipv4Addr::LOCALHOST);
+    let server_addr = SocketAddr::new(server_ip.into(), SERVER_PORT);
+    let client_addr = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0);
+
+    let auth = Authentication::Manual {
+        server_addr,
+        client_id: identity.client_id,
+        private_key: [0; 32],
+        protocol_id: PROTOCOL_ID,
+    };
+    let netcode_config = NetcodeConfig {
+        client_timeout_secs: 10,
+        token_expire_secs: 120,
+        ..default()
+    };
+
+    let mut client = commands.spawn((
+        Client::default(),
+        Link::default(),
+        NetcodeClient::new(auth, netcode_config).expect("Failed to create netcode client"),
+        ReplicationReceiver::default(),
+        PredictionManager::default(),
+        ReplicationSender::new(
+            Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
+            SendUpdatesMode::SinceLastAck,
+            false,
+        ),
+    ));
+    transport.0.insert_client_io(&mut client, client_addr, server_addr);
+    let client_entity = client.id();
+    commands.trigger(Connect { entity: client_entity });
+    info!("[LOAD] Connecting to {} as id {}", server_addr, identity.client_id);
+}
+
+/// Sends the signed wallet proof once connected, like the game client.
+fn send_wallet_auth(
+    mut sender_query: Query<(&mut MessageSender<WalletAuthMessage>, Ref<Connected>)>,
+    identity: Res<ClientIdentity>,
+) {
+    for (mut sender, connected) in sender_query.iter_mut() {
+        if !connected.is_added() {
+            continue;
+        }
+        let (pubkey, signature) = identity.sign_auth();
+        sender.send::<AuthChannel>(WalletAuthMessage { pubkey, signature });
+        info!("[LOAD] Connected, sent wallet auth");
+    }
+}
+
+/// Predicted players get physics like in the game client. Ours also gets an
+/// (empty) input map, so lightyear buffers and sends its ActionState.
+fn on_predicted_spawn(
+    trigger: On<Add, (PlayerId, Predicted)>,
+    query: Query<(&PlayerId, Has<Controlled>, Option<&avian3d::prelude::Position>)>,
+    mut commands: Commands,
+) {
+    let entity = trigger.entity;
+    let Ok((player_id, is_controlled, position)) = query.get(entity) else {
+        return;
+    };
+    commands.entity(entity).insert((
+        player_physics_bundle(),
+        Player { id: player_id.0 },
+        Transform::from_translation(position.map_or(PLAYER_SPAWN_POS, |p| p.0)),
+    ));
+    if is_controlled {
+        commands.entity(entity).insert(InputMap::<PlayerActions>::default());
+        info!("[LOAD] Our player spawned (id={})", player_id.0);
+    }
+}
+
+/// Random wandering: walk (sometimes sprint) in a world-space direction for
+/// a second or two, look around a little, now and then jump or fire.
+/// Dead players send nothing.
+fn drive_input(
+    mut query: Query<(&mut ActionState<PlayerActions>, Has<PlayerDead>), With<Controlled>>,
+    mut wander: ResMut<Wander>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    if now >= wander.until {
+        let angle = rand::random::<f32>() * std::f32::consts::TAU;
+        // One plan in five stands still
+        wander.heading = if rand::random::<f32>() < 0.2 { Vec2::ZERO } else { Vec2::from_angle(angle) };
+        wander.sprint = rand::random::<f32>() < SPRINT_CHANCE;
+        wander.until = now + HEADING_SECS * (1.0 + rand::random::<f32>());
+    }
+    let press = |action: &mut ActionState<PlayerActions>, which: &PlayerActions, on: bool| {
+        if on {
+            action.press(which);
+        } else {
+            action.release(which);
+        }
+    };
+
+    for (mut action, dead) in query.iter_mut() {
+        if dead {
+            action.reset_all();
+            continue;
+        }
+        action.set_axis_pair(&PlayerActions::Move, wander.heading);
+        let look = Vec2::new(rand::random::<f32>() * 2.0 - 1.0, (rand::random::<f32>() * 2.0 - 1.0) * 0.3);
+        action.set_axis_pair(&PlayerActions::Look, look * MAX_LOOK_STEP);
+        press(&mut action, &PlayerActions::Sprint, wander.sprint && wander.heading != Vec2::ZERO);
+        press(&mut action, &PlayerActions::Jump, rand::random::<f32>() < JUMP_CHANCE);
+        press(&mut action, &PlayerActions::Primary, rand::random::<f32>() < FIRE_CHANCE);
+    }
+}
+
+/// Logs connection state, our position and how many players we can see.
+fn report_status(
+    links: Query<(Has<Connected>, &Link), With<Client>>,
+    me: Query<&avian3d::prelude::Position, (With<Controlled>, With<PlayerId>)>,
+    players: Query<(), With<PlayerId>>,
+    time: Res<Time>,
+    mut next_report: Local<f32>,
+) {
+    let now = time.elapsed_secs();
+    if now < *next_report {
+        return;
+    }
+    *next_report = now + STATUS_INTERVAL;
+    for (connected, link) in links.iter() {
+        if !connected {
+            info!("[LOAD] Not connected yet");
+            continue;
+        }
+        let position = me.single().map(|p| format!("{:.1?}", p.0)).unwrap_or_else(|_| "no player".to_string());
+        info!(
+            "[LOAD] rtt {:.0}ms · at {} · {} players visible",
+            link.stats.rtt.as_secs_f64() * 1000.0,
+            position,
+            players.iter().count(),
+        );
+    }
+}
+
+fn quit_at_deadline(deadline: Res<Deadline>, time: Res<Time>, mut exit: MessageWriter<AppExit>) {
+    if time.elapsed_secs() >= deadline.0 {
+        info!("[LOAD] --duration reached, disconnecting");
+        exit.write(AppExit::Success);
+    }
+}
@@ -7,19 +7,36 @@ use lightyear::prelude::server::*;
 use lightyear::prelude::*;
 use lightyear::interpolation::plugin::InterpolationDelay;
 use lightyear_avian3d::prelude::{LagCompensationHistory, LagCompensationPlugin, LagCompensationSpatialQuery};
-use avian3d::prelude::SpatialQueryFilter;
+use avian3d::prelude::{RayHitData, SpatialQuery, SpatialQueryFilter};
+use bevy_egui::{EguiContexts, EguiPlugin, egui};
 
 use multiplayer::auth::{self, VerifiedWallets};
-use multiplayer::player::{player_physics_bundle, player_replicated_bundle, select_spawn_point};
-use multiplayer::protocol::{KillFeedEntry, LastDamagedBy, PlayerActions, PlayerId, PlayerDead, PlayerEquipped, PlayerHealth, PlayerDisplayId, PlayerInventory, PlayerYaw, PlayerPitch, WalletAuthMessage};
+use multiplayer::interest::{self, AoiConfig, PhysicsCullConfig};
+use multiplayer::autosave::{self, ItemSnapshot, MatchAutosave, MatchSnapshot, PlayerSnapshot};
+use multiplayer::player::{player_physics_bundle, player_replicated_bundle, select_spawn_point, SPAWN_POINTS};
+use multiplayer::protocol::{InHazard, KillFeedEntry, LastDamagedBy, PlayerActions, PlayerId, PlayerDead, PlayerEquipped, PlayerHealth, PlayerDisplayId, PlayerInventory, PlayerPreferencesMessage, PlayerStats, PlayerYaw, PlayerPitch, WalletAuthMessage};
+use multiplayer::protocol::{AutoCrouchAds, AutoEquip, Bot, CtfScore, Downed, Eliminated, Hardcore, LoadoutItems, MatchState, Team};
+use multiplayer::protocol::{HeadshotOnly, KillStreak, KillStreakEntry, PlayerPing, Resources, RespawnDelay, RespawnWave, SpeedBoost, Stamina, TradeRequestMessage, TrainingStats, DropOreMessage};
+use multiplayer::protocol::{AdminCommand, AdminCommandMessage, AfkWarningMessage, CharacterVelocity, MatchPaused, MotdMessage, ObserverChannel, ObserverLoginMessage, ObserverStatusMessage, ServerInfoChannel};
+use multiplayer::protocol::{ChatBroadcast, ChatChannel, ChatMessage, MAX_CHAT_LEN, TeamChannel, TeamSwapReplyMessage, TeamSwapRequestMessage};
+use multiplayer::observer::{self, match_running, Observer, ObserverTokens};
+use multiplayer::replay::{self, DeterminismCheck, InputRecording, RecordedInput, RecordingWriter, ReplayMode};
+use multiplayer::rules::{self, AfkConfig, BotFill, KillStreakRewards, LoadoutConfig, Ruleset, ServerMotd, StreakReward};
 use multiplayer::solana::{self, RespawnAuth, RespawnConfig, WalletAddress};
-use multiplayer::world::{spawn_server_interactive_objects, spawn_world_physics, Equippable};
+use multiplayer::stats::StatsStore;
+use multiplayer::transport::{transport_from_args, ActiveTransport};
+use multiplayer::trade::TRADE_OFFERS;
+use multiplayer::utility::{self, Flashed, HomingSettings, SmokeCloud, SmokeSettings, SmokeShots, ThrownUtility, UtilityKind, UtilityLoadout, UtilityPouch};
+use multiplayer::world::{respawn_map_weapons, spawn_server_interactive_objects, spawn_world_physics, DamageVolume, Equippable, Flag, OreMined, TradeStation, WeaponSpawn};
 use multiplayer::{SharedPlugin, FIXED_TIMESTEP_HZ, PROTOCOL_ID, SERVER_PORT};
 
 use avian3d::prelude::Position;
 
-/// Respawn delay in seconds before a dead player can respawn.
-const RESPAWN_DELAY: f32 = 20.0;
+/// How recently (seconds) a player must have damaged a victim to get an assist.
+const ASSIST_WINDOW_SECS: f32 = 10.0;
+
+/// Seconds a strafing training bot moves one way before turning around.
+const TRAINING_STRAFE_SECS: f32 = 1.5;
 
 fn main() {
     eprintln!(
@@ -31,28 +48,83 @@ fn main() {
 
     let mut app = App::new();
 
-    // Headless server: no window
-    app.add_plugins(
-        DefaultPlugins
-            .build()
-            .disable::<bevy::winit::WinitPlugin>()
-            .disable::<bevy::render::RenderPlugin>()
-            .disable::<bevy::core_pipeline::CorePipelinePlugin>()
-            .disable::<bevy::pbr::PbrPlugin>()
-            .disable::<bevy::gltf::GltfPlugin>()
-            .disable::<bevy::sprite::SpritePlugin>()
-            .disable::<bevy::ui::UiPlugin>()
-            .disable::<bevy::text::TextPlugin>()
-            .set(bevy::window::WindowPlugin {
-                primary_window: None,
-                primary_cursor_options: None,
-                exit_condition: bevy::window::ExitCondition::DontExit,
-                close_when_requested: false,
-            }),
-    );
-    app.add_plugins(bevy::app::ScheduleRunnerPlugin::run_loop(
-        Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
-    ));
+    // Per-category log levels (--log network=warn,physics=off / --log-level)
+    let log_plugin = multiplayer::logging::log_plugin_from_args();
+    let inspector = inspector_mode();
+    if inspector {
+        // --inspector: windowed server with the live entity inspector
+        app.add_plugins(
+            DefaultPlugins
+                .set(bevy::window::WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Anima Server Inspector".into(),
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .set(log_plugin),
+        );
+    } else {
+        // Headless server: no window
+        app.add_plugins(
+            DefaultPlugins
+                .build()
+                .set(log_plugin)
+                .disable::<bevy::winit::WinitPlugin>()
+                .disable::<bevy::render::RenderPlugin>()
+                .disable::<bevy::core_pipeline::CorePipelinePlugin>()
+                .disable::<bevy::pbr::PbrPlugin>()
+                .disable::<bevy::gltf::GltfPlugin>()
+                .disable::<bevy::sprite::SpritePlugin>()
+                .disable::<bevy::ui::UiPlugin>()
+                .disable::<bevy::text::TextPlugin>()
+                .set(bevy::window::WindowPlugin {
+                    primary_window: None,
+                    primary_cursor_options: None,
+                    exit_condition: bevy::window::ExitCondition::DontExit,
+                    close_when_requested: false,
+                }),
+        );
+        app.add_plugins(bevy::app::ScheduleRunnerPlugin::run_loop(
+            Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
+        ));
+    }
+
+    // Deterministic mode: every loop iteration advances time by exactly one tick,
+    // regardless of wall-clock, so physics (FixedUpdate) steps once per update with
+    // a constant dt and replays of the same inputs produce the same states.
+    // Replaying recorded inputs always runs this way.
+    let replay_mode = replay::parse_replay_mode();
+    if deterministic_mode() || matches!(replay_mode, Some(ReplayMode::Replay(_))) {
+        app.insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(
+            Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
+        ));
+        app.add_systems(FixedLast, log_state_checksum);
+        info!("[DETERMINISM] Manual fixed-step time enabled");
+    }
+
+    // Input recording (--record-inputs <path>) and headless playback with a
+    // determinism check (--replay-inputs <path>)
+    match replay_mode {
+        Some(ReplayMode::Record(path)) => match RecordingWriter::create(&path) {
+            Ok(writer) => {
+                info!("[REPLAY] Recording inputs to {}", path.display());
+                app.insert_resource(InputRecorder { writer });
+                app.add_systems(FixedLast, record_inputs);
+                app.add_systems(Last, flush_recording_on_exit);
+            }
+            Err(e) => error!("[REPLAY] {}", e),
+        },
+        Some(ReplayMode::Replay(path)) => match InputRecording::load(&path) {
+            Ok(recording) => {
+                info!("[REPLAY] Replaying {} ticks from {}", recording.ticks.len(), path.display());
+                app.insert_resource(InputReplay { path, recording, tick: 0 });
+                app.add_systems(FixedPreUpdate, replay_inputs);
+            }
+            Err(e) => error!("[REPLAY] {}", e),
+        },
+        None => {}
+    }
 
     // Lightyear server
     app.add_plugins(ServerPlugins {
@@ -61,15 +133,30 @@ fn main() {
 
     // Shared: protocol, physics, frame interpolation, movement observer
     app.add_plugins(SharedPlugin);
+    // ADS across weapon switches (--ads-switch cancel|carry), replicated to clients
+    app.insert_resource(multiplayer::player::parse_ads_config());
+    // Peek lean distance (--lean-max <meters>), replicated to clients
+    app.insert_resource(multiplayer::player::parse_lean_config());
 
     // Lag compensation — maintains collider history so hits can be rewound
     // to where targets were when the client saw them
     app.add_plugins(LagCompensationPlugin);
 
+    // Entity inspector (--inspector): egui entity list, collider wireframes
+    // and a debug camera that jumps to the selected entity
+    if inspector {
+        app.add_plugins(EguiPlugin::default());
+        app.add_plugins(avian3d::prelude::PhysicsDebugPlugin::default());
+        app.add_systems(Startup, spawn_inspector_camera);
+        app.add_systems(Update, entity_inspector_panel);
+    }
+
     // World — physics only, no rendering on headless server
     app.add_systems(Startup, spawn_world_physics);
+    app.insert_resource(transport_from_args());
     app.add_systems(Startup, spawn_server);
     app.add_systems(Startup, spawn_server_interactive_objects);
+    app.add_systems(FixedUpdate, respawn_map_weapons);
 
     // Player ID counter
     app.init_resource::<PlayerCounter>();
@@ -80,47 +167,173 @@ fn main() {
 
     // Death and respawn
     app.init_resource::<PendingRespawns>();
-    app.add_systems(FixedUpdate, (kill_plane, check_player_death, process_respawns).chain());
+    app.init_resource::<DamageContributors>();
+    app.init_resource::<HazardDamage>();
+    app.init_resource::<NextRoundAt>();
+    app.insert_resource(rules::parse_ruleset());
+    // Spawn loadouts (--loadout / --loadout-red / --loadout-blue)
+    app.insert_resource(rules::parse_loadouts());
+    // Kill-streak rewards (--streak-rewards, off by default)
+    app.insert_resource(rules::parse_streak_rewards());
+    app.add_systems(Update, expire_feed_entries);
+    // CSV network metrics (--net-diagnostics <file>)
+    if let Some(export) = multiplayer::diagnostics::parse_net_diagnostics() {
+        multiplayer::diagnostics::add_net_diagnostics_export(&mut app, export);
+    }
+    app.add_systems(Startup, spawn_match_state);
+    app.add_systems(Update, update_respawn_wave);
+    app.add_systems(
+        FixedUpdate,
+        (
+            record_damage_contributors,
+            kill_plane,
+            apply_hazard_damage,
+            process_downed,
+            check_player_death,
+            run_elimination_rounds.run_if(|rules: Res<Ruleset>| rules.elimination),
+            run_capture_the_flag.run_if(|rules: Res<Ruleset>| rules.ctf),
+            grant_streak_rewards,
+            expire_speed_boosts,
+            process_respawns,
+        )
+            .chain()
+            .run_if(match_running),
+    );
+
+    // Bot auto-balance (--bot-fill <count>)
+    app.insert_resource(rules::parse_bot_fill());
+    app.add_systems(Update, balance_bots.run_if(|fill: Res<BotFill>| fill.target > 0).run_if(match_running));
+    app.add_systems(
+        FixedPreUpdate,
+        (
+            drive_training_bots.run_if(|rules: Res<Ruleset>| rules.headshot_only),
+            drive_bots.run_if(|rules: Res<Ruleset>, fill: Res<BotFill>| !rules.headshot_only && fill.target > 0),
+        ),
+    );
+
+    // Lifetime stats: loaded from ~/.anima/player_stats.json, saved periodically
+    app.insert_resource(StatsStore::load());
+    app.add_systems(FixedUpdate, track_distance_traveled.run_if(match_running));
+    app.add_systems(Update, (persist_player_stats, update_player_ping));
+
+    // Match autosave (--autosave <secs>): restored once the world and match
+    // entities exist, then written periodically
+    if let Some(autosave) = autosave::parse_autosave() {
+        app.insert_resource(autosave);
+        app.add_systems(PostStartup, restore_match_autosave);
+        app.add_systems(Update, autosave_match);
+    }
+    app.add_observer(count_ore_mined);
 
     // Wallet auth: process incoming auth messages from clients
     app.add_systems(Update, process_wallet_auth);
 
+    // Player preferences (auto-equip, ...) sent by clients on connect
+    app.add_systems(Update, process_player_preferences);
+
+    // Message of the day + rules for joining clients (--motd, --motd-file)
+    app.insert_resource(rules::parse_motd());
+    app.add_systems(Update, send_motd);
+
+    // Anti-AFK (--afk-timeout <secs>): warn, then kick idle players
+    if let Some(afk) = rules::parse_afk_config() {
+        app.insert_resource(afk);
+        app.add_systems(FixedUpdate, kick_afk_players);
+    }
+
+    // Purchases at trading stations
+    app.add_systems(Update, process_trade_requests);
+
+    // Stackable ore: chunk pickups and drops go through Resources
+    app.add_systems(FixedUpdate, collect_ore_chunks);
+    app.add_systems(Update, process_ore_drops);
+
+    // Chat: relay each line to everyone
+    app.add_systems(Update, relay_chat);
+
+    // Team swaps, refused when they would unbalance the teams (--team-swap-imbalance)
+    app.add_systems(Update, process_team_swaps);
+
+    // Throwable utility (--utility frag=2,flash=2,smoke=1): throws, fuses, effects
+    app.insert_resource(utility::parse_utility_loadout());
+    app.insert_resource(utility::parse_smoke_settings());
+    app.add_systems(
+        FixedUpdate,
+        (throw_utility, detonate_utilities, expire_utility_effects)
+            .chain()
+            .run_if(match_running),
+    );
+    // Homing utilities (--homing <kind,...>): steered toward enemies in flight
+    if let Some(homing) = utility::parse_homing_settings() {
+        app.insert_resource(homing);
+        app.add_systems(FixedUpdate, steer_homing_utilities.after(throw_utility).run_if(match_running));
+    }
+
+    // Observer/referee clients (--observer-tokens): login + admin commands
+    app.insert_resource(observer::parse_observer_tokens());
+    app.add_systems(Update, (process_observer_login, process_admin_commands));
+
+    // Area-of-interest culling (--aoi-radius <m>): players and loose items
+    // are only replicated to clients near them
+    if let Some(aoi) = interest::parse_aoi_config() {
+        app.insert_resource(aoi);
+        app.add_observer(cull_players);
+        app.add_observer(cull_items);
+        app.add_observer(cull_utilities);
+        app.init_resource::<InterestVisibility>();
+        app.add_systems(Update, update_interest);
+        // Inspector: per-client interest sphere and culled-entity markers
+        if inspector {
+            app.init_resource::<InterestDebug>();
+            app.add_systems(Update, (interest_debug_panel, draw_interest_debug));
+        }
+    }
+
+    // Physics culling (--physics-cull-radius <m>): loose bodies far from every
+    // player are left out of the physics step
+    if let Some(cull) = interest::parse_physics_cull_config() {
+        app.insert_resource(cull);
+        app.add_systems(FixedUpdate, cull_far_physics);
+    }
+
     // Client handling
     app.add_observer(handle_new_client);
     app.add_observer(handle_connected);
     app.add_observer(handle_disconnected);
+    app.add_observer(drop_items_on_leave);
 
     // Lag-compensated hitscan damage — FixedUpdate system querying ActionState.
     // The shared world::shared_primary_action_system handles tracer prediction
     // on the client. This system runs on the server and rewinds targets to
     // where the shooter saw them (using the shooter's replicated InterpolationDelay).
-    app.add_systems(FixedUpdate, server_shoot_with_lag_comp);
+    app.add_systems(FixedUpdate, server_shoot_with_lag_comp.run_if(match_running));
 
     app.run();
 }
 
-fn spawn_server(mut commands: Commands) {
+fn spawn_server(mut commands: Commands, transport: Res<ActiveTransport>, mut exit: MessageWriter<AppExit>) {
     let server_addr = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), SERVER_PORT);
 
-    let server_entity = commands
-        .spawn((
-            NetcodeServer::new(NetcodeConfig {
-                protocol_id: PROTOCOL_ID,
-                private_key: [0; 32],
-                // Short timeout — stale client IDs clear quickly so reconnects work
-                client_timeout_secs: 10,
-                ..Default::default()
-            }),
-            LocalAddr(server_addr),
-            ServerUdpIo::default(),
-        ))
-        .id();
+    let mut server = commands.spawn(NetcodeServer::new(NetcodeConfig {
+        protocol_id: PROTOCOL_ID,
+        private_key: [0; 32],
+        // Short timeout — stale client IDs clear quickly so reconnects work
+        client_timeout_secs: 10,
+        ..Default::default()
+    }));
+    if let Err(e) = transport.0.insert_server_io(&mut server, server_addr) {
+        error!(target: "network", "[TRANSPORT] {}", e);
+        server.despawn();
+        exit.write(AppExit::error());
+        return;
+    }
+    let server_entity = server.id();
 
     commands.trigger(Start {
         entity: server_entity,
     });
 
-    info!("Server listening on {}", server_addr);
+    info!(target: "network", "Server listening on {} ({})", server_addr, transport.0.name());
 }
 
 /// When a new link is created, add ReplicationSender + ReplicationReceiver.
@@ -128,7 +341,7 @@ fn spawn_server(mut commands: Commands) {
 /// ReplicationReceiver: enables receiving BEI Action entities from this client.
 fn handle_new_client(trigger: On<Add, LinkOf>, mut commands: Commands) {
     let entity = trigger.entity;
-    info!("New client link: {:?}", entity);
+    info!(target: "network", "New client link: {:?}", entity);
     commands.entity(entity).insert((
         ReplicationSender::new(
             Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
@@ -148,8 +361,13 @@ fn handle_connected(
     trigger: On<Add, Connected>,
     query: Query<(&RemoteId, Has<ReplicationSender>), With<ClientOf>>,
     living_query: Query<&Position, (With<PlayerId>, Without<PlayerDead>)>,
+    team_query: Query<&Team>,
     mut commands: Commands,
     mut counter: ResMut<PlayerCounter>,
+    ruleset: Res<Ruleset>,
+    loadouts: Res<LoadoutConfig>,
+    utility_loadout: Res<UtilityLoadout>,
+    mut joined: Local<Vec<(Entity, Team)>>,
 ) {
     let entity = trigger.entity;
     let Ok((remote_id, has_sender)) = query.get(entity) else {
@@ -159,6 +377,7 @@ fn handle_connected(
     let client_id = remote_id.0;
     let client_id_bits = client_id.to_bits();
     info!(
+        target: "network",
         "Client connected: {} (entity={:?}, has_replication_sender={})",
         client_id_bits, entity, has_sender
     );
@@ -166,7 +385,7 @@ fn handle_connected(
     // Ensure ReplicationSender is present (should be from handle_new_client,
     // but if command flush ordering caused it to be missing, add it now)
     if !has_sender {
-        warn!("ReplicationSender missing on client entity {:?}, adding now", entity);
+        warn!(target: "network", "ReplicationSender missing on client entity {:?}, adding now", entity);
         commands.entity(entity).insert(
             ReplicationSender::new(
                 Duration::from_millis(100),
@@ -186,12 +405,16 @@ fn handle_connected(
     counter.0 += 1;
     let display_id = counter.0;
 
-    commands.spawn((
+    let player = commands.spawn((
         player_replicated_bundle(client_id_bits),
         player_physics_bundle(),
         PlayerDisplayId(display_id),
         // WalletAddress starts empty — populated after auth verification
         WalletAddress::default(),
+        // Lifetime stats from previous sessions are restored once the
+        // client proves it owns this id (process_wallet_auth)
+        PlayerStats::default(),
+        DistanceTracker { last: spawn_pos, pending: 0.0 },
         Replicate::to_clients(NetworkTarget::All),
         PredictionTarget::to_clients(NetworkTarget::Single(client_id)),
         InterpolationTarget::to_clients(NetworkTarget::AllExceptSingle(client_id)),
@@ -204,7 +427,24 @@ fn handle_connected(
         LagCompensationHistory::default(),
     ))
     // Set spawn position after spawn — player_replicated_bundle already includes Position
-    .insert(Position(spawn_pos));
+    .insert(Position(spawn_pos))
+    .id();
+
+    // Teams inserted for earlier joins may still be in the command queue
+    // (several clients connecting in one frame); count those too
+    joined.retain(|(earlier, _)| !team_query.contains(*earlier));
+    let team = ruleset.ctf.then(|| {
+        rules::smaller_team(team_query.iter().copied().chain(joined.iter().map(|(_, team)| *team)))
+    });
+    if let Some(team) = team {
+        joined.push((player, team));
+        commands.entity(player).insert(team);
+        info!("[CTF] Player {} joins {}", display_id, team.name());
+    }
+    commands.entity(player).insert((loadouts.for_team(team).grant(), utility_loadout.pouch()));
+    if ruleset.headshot_only {
+        commands.entity(player).insert(TrainingStats::default());
+    }
 
     info!("[SPAWN] Player {} spawning at {:?}", display_id, spawn_pos);
 }
@@ -216,6 +456,7 @@ fn handle_disconnected(
     trigger: On<Add, Disconnected>,
     query: Query<&RemoteId, With<ClientOf>>,
     mut verified_wallets: ResMut<VerifiedWallets>,
+    mut stats_store: ResMut<StatsStore>,
 ) {
     let entity = trigger.entity;
     let Ok(remote_id) = query.get(entity) else {
@@ -223,12 +464,15 @@ fn handle_disconnected(
     };
 
     let client_id = remote_id.0.to_bits();
-    info!("[DISCONNECT] Client {} (entity={:?}) disconnected", client_id, entity);
+    info!(target: "network", "[DISCONNECT] Client {} (entity={:?}) disconnected", client_id, entity);
 
     // Remove from verified wallets
     if verified_wallets.remove(client_id) {
-        info!("[DISCONNECT] Removed wallet verification for client {}", client_id);
+        info!(target: "network", "[DISCONNECT] Removed wallet verification for client {}", client_id);
     }
+
+    // Flush lifetime stats so they survive a server restart right after leaving
+    stats_store.save_if_dirty();
 }
 
 /// Server-only FixedUpdate system: handles hitscan damage with lag compensation.
@@ -247,21 +491,32 @@ fn server_shoot_with_lag_comp(
         &PlayerEquipped,
         &PlayerId,
         Option<&ControlledBy>,
-    )>,
+        Has<Bot>,
+    ), Without<Downed>>,
     client_query: Query<&InterpolationDelay, With<ClientOf>>,
+    stance_query: Query<&multiplayer::protocol::PlayerStance>,
+    lean_query: Query<(Entity, &Position, &PlayerYaw, &multiplayer::protocol::PlayerStance), Without<PlayerDead>>,
+    lean_config: Res<multiplayer::player::LeanConfig>,
     mut health_query: Query<(&mut PlayerHealth, Option<&mut LastDamagedBy>)>,
+    victim_query: Query<&Position, With<PlayerId>>,
+    mut training_query: Query<&mut TrainingStats>,
     lag_query: LagCompensationSpatialQuery,
+    spatial_query: SpatialQuery,
+    smoke_query: Query<(&Position, &SmokeCloud)>,
+    smoke_settings: Res<SmokeSettings>,
+    ruleset: Res<Ruleset>,
     mut last_shot: Local<std::collections::HashMap<Entity, f32>>,
+    mut bursts: Local<std::collections::HashMap<Entity, (f32, u32)>>,
     time: Res<Time>,
 ) {
-    for (shooter, action, pos, yaw, pitch, equipped, attacker_id, controlled_by) in player_query.iter() {
+    for (shooter, action, pos, yaw, pitch, equipped, attacker_id, controlled_by, is_bot) in player_query.iter() {
         if !action.just_pressed(&PlayerActions::Primary) {
             continue;
         }
 
         // Only run for gun shots
         let Some(ref name) = equipped.0 else { continue; };
-        if !(name.contains("AK") || name.contains("ak") || name.contains("gun")) {
+        if !multiplayer::world::ItemKind::of(name).is_weapon() {
             continue;
         }
 
@@ -272,41 +527,158 @@ fn server_shoot_with_lag_comp(
             continue;
         }
         last_shot.insert(shooter, current);
-
-        // Get the shooter's InterpolationDelay so we know how far back to rewind
-        let Some(controlled) = controlled_by else {
-            warn!("[SHOOT-SERVER] Shooter {:?} has no ControlledBy", shooter);
-            continue;
-        };
-        let Ok(delay) = client_query.get(controlled.owner) else {
-            warn!("[SHOOT-SERVER] No InterpolationDelay for client {:?}", controlled.owner);
-            continue;
+        // Where this shot falls in the weapon's spread pattern
+        let burst = multiplayer::weapons::burst_index(name, bursts.get(&shooter).copied(), current);
+        bursts.insert(shooter, (current, burst));
+        let [spread_yaw, spread_pitch] = multiplayer::weapons::spread_offset(name, burst);
+
+        // Get the shooter's InterpolationDelay so we know how far back to rewind.
+        // Bots see the present, so their shots aren't rewound.
+        let delay = match controlled_by {
+            Some(controlled) => match client_query.get(controlled.owner) {
+                Ok(delay) => Some(*delay),
+                Err(_) => {
+                    warn!("[SHOOT-SERVER] No InterpolationDelay for client {:?}", controlled.owner);
+                    continue;
+                }
+            },
+            None if is_bot => None,
+            None => {
+                warn!("[SHOOT-SERVER] Shooter {:?} has no ControlledBy", shooter);
+                continue;
+            }
         };
 
-        let eye_pos = pos.0 + Vec3::Y * 0.8;
-        let ray_dir = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0) * Vec3::NEG_Z;
-        let mut filter = SpatialQueryFilter::from_excluded_entities([shooter]);
+        let eye_pos = pos.0
+            + stance_query
+                .get(shooter)
+                .map(|s| s.eye_offset(yaw.0, &lean_config))
+                .unwrap_or(Vec3::Y * multiplayer::player::EYE_HEIGHT);
+        let ray_dir = Dir3::new(
+            Quat::from_euler(EulerRot::YXZ, yaw.0 + spread_yaw, pitch.0 + spread_pitch, 0.0) * Vec3::NEG_Z,
+        )
+        .unwrap_or(Dir3::NEG_Z);
+
+        // A leaning head sticks out past the capsule, which is all the rewound
+        // cast can hit — test head spheres too, at their current positions
+        // (like the headshot check below).
+        let leaning_heads: Vec<(Entity, Vec3)> = lean_query
+            .iter()
+            .filter(|(entity, _, _, stance)| *entity != shooter && stance.lean != 0.0)
+            .map(|(entity, pos, yaw, stance)| (entity, pos.0 + stance.eye_offset(yaw.0, &lean_config)))
+            .collect();
+
+        // Players are rewound; walls are static, so their thickness is measured
+        // with the regular spatial query inside trace_shot.
+        let trace_along = |ray_dir: Dir3| {
+            let mut filter = SpatialQueryFilter::from_excluded_entities([shooter]);
+            multiplayer::world::trace_shot(
+                eye_pos,
+                ray_dir,
+                multiplayer::weapons::penetration(name),
+                if ruleset.ricochets { multiplayer::weapons::ricochets(name) } else { 0 },
+                &mut filter,
+                &spatial_query,
+                |e| health_query.contains(e),
+                |origin, dir, max, filter| {
+                    let hit = match delay {
+                        Some(delay) => lag_query.cast_ray(delay, origin, dir, max, true, filter),
+                        None => spatial_query.cast_ray(origin, dir, max, true, filter),
+                    };
+                    let head = leaning_heads
+                        .iter()
+                        .filter_map(|(entity, center)| {
+                            multiplayer::player::ray_sphere(origin, *dir, *center, multiplayer::player::LEAN_HEAD_RADIUS)
+                                .filter(|distance| *distance <= max)
+                                .map(|distance| (*entity, distance, *center))
+                        })
+                        .min_by(|a, b| a.1.total_cmp(&b.1));
+                    match (hit, head) {
+                        (Some(hit), Some((_, distance, _))) if hit.distance <= distance => Some(hit),
+                        (_, Some((entity, distance, center))) => Some(RayHitData {
+                            entity,
+                            distance,
+                            normal: (origin + *dir * distance - center).normalize_or_zero(),
+                        }),
+                        (hit, None) => hit,
+                    }
+                },
+            )
+        };
+        let mut trace = trace_along(ray_dir);
 
-        if let Some(hit) = lag_query.cast_ray(
-            *delay,
+        // Smoke on the way to whatever the shot hit either stops it or
+        // throws it off by up to SMOKE_SPREAD (--smoke-blocks-shots)
+        let in_smoke = utility::smoke_between(
             eye_pos,
-            Dir3::new(ray_dir).unwrap_or(Dir3::NEG_Z),
-            multiplayer::world::SHOOT_RANGE,
-            true,
-            &mut filter,
-        ) {
+            trace.end,
+            smoke_query.iter().map(|(pos, cloud)| (pos.0, cloud.radius)),
+        );
+        if in_smoke {
+            match smoke_settings.shots {
+                SmokeShots::Block => {
+                    info!("[SHOOT-SERVER] Shot lost in smoke");
+                    continue;
+                }
+                SmokeShots::Degrade => {
+                    let jitter = || (rand::random::<f32>() * 2.0 - 1.0) * utility::SMOKE_SPREAD;
+                    let blind_dir = Dir3::new(
+                        Quat::from_euler(EulerRot::YXZ, yaw.0 + spread_yaw + jitter(), pitch.0 + spread_pitch + jitter(), 0.0)
+                            * Vec3::NEG_Z,
+                    )
+                    .unwrap_or(ray_dir);
+                    trace = trace_along(blind_dir);
+                }
+            }
+        }
+
+        let mut training = training_query.get_mut(shooter).ok().filter(|_| ruleset.headshot_only);
+        if let Some(stats) = training.as_mut() {
+            stats.shots += 1;
+        }
+
+        if let Some(hit) = trace.hit {
             info!(
-                "[SHOOT-SERVER] Lag-comp hit entity {:?} at distance {:.1}",
-                hit.entity, hit.distance
+                "[SHOOT-SERVER] Lag-comp hit entity {:?} at distance {:.1} ({} surface(s) penetrated, {} ricochet(s))",
+                hit, trace.distance, trace.penetrated, trace.ricochets.len()
             );
-            if let Ok((mut health, last_damaged)) = health_query.get_mut(hit.entity) {
-                health.0 -= multiplayer::world::SHOOT_DAMAGE;
+            // Headshot-only training: body hits are counted but do no damage.
+            // The head zone is checked against the victim's current height,
+            // which matches the rewound one unless they were mid-jump.
+            if ruleset.headshot_only && health_query.contains(hit) {
+                let hit_y = trace.end.y;
+                let victim_y = victim_query.get(hit).map(|p| p.0.y).unwrap_or(f32::MIN);
+                let victim_eye = stance_query
+                    .get(hit)
+                    .map(|s| s.eye_height())
+                    .unwrap_or(multiplayer::player::EYE_HEIGHT);
+                let headshot = multiplayer::world::is_headshot(hit_y, victim_y, victim_eye);
+                if let Some(stats) = training.as_mut() {
+                    if headshot {
+                        stats.headshots += 1;
+                    } else {
+                        stats.body_hits += 1;
+                    }
+                }
+                if !headshot {
+                    info!("[SHOOT-SERVER] Body hit ignored (headshot-only)");
+                    continue;
+                }
+            }
+            if let Ok((mut health, last_damaged)) = health_query.get_mut(hit) {
+                let damage = if ruleset.hardcore {
+                    health.0.max(1)
+                } else {
+                    let damage = multiplayer::weapons::penetrated_damage(multiplayer::world::SHOOT_DAMAGE, trace.penetrated);
+                    multiplayer::weapons::ricochet_damage(damage, trace.ricochets.len() as u32)
+                };
+                health.0 -= damage;
                 if let Some(mut last) = last_damaged {
                     last.0 = attacker_id.0;
                 }
                 info!(
                     "[SHOOT-SERVER] Player hit! {} damage applied, health now: {}",
-                    multiplayer::world::SHOOT_DAMAGE, health.0
+                    damage, health.0
                 );
             }
         }
@@ -324,6 +696,37 @@ struct PendingRespawns {
     timers: Vec<(Entity, f32)>,
 }
 
+/// Server-only: who damaged each player recently, for assists.
+/// Victim entity -> (attacker client id, last hit time) per attacker.
+#[derive(Resource, Default)]
+struct DamageContributors {
+    by_victim: std::collections::HashMap<Entity, Vec<(u64, f32)>>,
+}
+
+/// Server-only: records every hit (a write to LastDamagedBy) so
+/// check_player_death can credit assists. Runs first in the death chain.
+fn record_damage_contributors(
+    query: Query<(Entity, &LastDamagedBy), Changed<LastDamagedBy>>,
+    players: Query<(), With<PlayerId>>,
+    mut contributors: ResMut<DamageContributors>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (victim, attacker) in query.iter() {
+        // 0 = never damaged (the default on spawn)
+        if attacker.0 == 0 {
+            continue;
+        }
+        let hits = contributors.by_victim.entry(victim).or_default();
+        match hits.iter_mut().find(|(id, _)| *id == attacker.0) {
+            Some(hit) => hit.1 = now,
+            None => hits.push((attacker.0, now)),
+        }
+    }
+    // Forget players who disconnected
+    contributors.by_victim.retain(|victim, _| players.contains(*victim));
+}
+
 /// Server-only: kill plane — any player below this Y is instantly killed.
 /// Prevents players from falling forever if they clip through geometry.
 const KILL_PLANE_Y: f32 = -60.0;
@@ -339,28 +742,186 @@ fn kill_plane(
     }
 }
 
+/// Server-only: fractional hazard damage not yet applied, per player.
+/// A resource rather than a Local so check_player_death can clear it.
+#[derive(Resource, Default)]
+struct HazardDamage {
+    pending: std::collections::HashMap<Entity, f32>,
+}
+
+/// Server-only: damage over time for players inside a DamageVolume.
+/// Damage accumulates fractionally per player so low dps values still tick
+/// down integer health smoothly. Tags players with InHazard for client effects.
+/// Hazard damage is self-inflicted as far as kills go: it sets LastDamagedBy
+/// to the victim, so whoever shot them earlier gets no credit for the death.
+fn apply_hazard_damage(
+    mut players: Query<
+        (Entity, &Position, &mut PlayerHealth, &PlayerId, &mut LastDamagedBy, Has<InHazard>),
+        Without<PlayerDead>,
+    >,
+    volumes: Query<(&Position, &DamageVolume), Without<PlayerId>>,
+    mut hazard_damage: ResMut<HazardDamage>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    // Forget players who disconnected
+    hazard_damage.pending.retain(|entity, _| players.contains(*entity));
+    let pending_damage = &mut hazard_damage.pending;
+    for (entity, pos, mut health, id, mut last_damaged_by, in_hazard) in players.iter_mut() {
+        let dps: f32 = volumes
+            .iter()
+            .filter(|(center, volume)| volume.contains(center.0, pos.0))
+            .map(|(_, volume)| volume.dps)
+            .sum();
+
+        if dps <= 0.0 {
+            if in_hazard {
+                commands.entity(entity).remove::<InHazard>();
+                pending_damage.remove(&entity);
+            }
+            continue;
+        }
+        if !in_hazard {
+            info!("[HAZARD] Player {} entered a hazard ({} dps)", id.0, dps);
+            commands.entity(entity).insert(InHazard);
+        }
+
+        let pending = pending_damage.entry(entity).or_insert(0.0);
+        *pending += dps * dt;
+        let whole = pending.floor();
+        if whole >= 1.0 && health.0 > 0 {
+            *pending -= whole;
+            health.0 -= whole as i32;
+            last_damaged_by.set_if_neq(LastDamagedBy(id.0));
+        }
+    }
+}
+
+/// Server-only: finishes revives and bleeds out downed players.
+/// A revive completes once someone has held Interact next to the player for
+/// REVIVE_TIME (progress is tracked by the shared `shared_revive_system`).
+/// Bleeding out marks the player and re-flags their health as changed so
+/// `check_player_death` runs the normal death path next.
+fn process_downed(
+    mut query: Query<(Entity, &PlayerId, &mut Downed, &mut PlayerHealth)>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, id, mut downed, mut health) in query.iter_mut() {
+        if downed.bled_out {
+            continue;
+        }
+        if downed.revive_progress(now) >= rules::REVIVE_TIME {
+            info!("[REVIVE] Player {} revived", id.0);
+            health.0 = rules::REVIVE_HEALTH;
+            commands.entity(entity).remove::<Downed>();
+            continue;
+        }
+        if now - downed.since >= downed.window {
+            info!("[REVIVE] Player {} bled out", id.0);
+            downed.bled_out = true;
+            health.set_changed();
+        }
+    }
+}
+
+/// Moves the world Equippable entity for each item to `at`, fanned out in a
+/// small circle so they don't stack on the exact same spot.
+fn scatter_items(
+    items: &[String],
+    at: Vec3,
+    equippable_query: &mut Query<(&Equippable, &mut Position), Without<PlayerHealth>>,
+) {
+    for (drop_index, item_name) in items.iter().enumerate() {
+        let angle = drop_index as f32 * std::f32::consts::TAU / items.len().max(1) as f32;
+        let offset = if items.len() > 1 {
+            Vec3::new(angle.cos() * 0.5, 0.0, angle.sin() * 0.5)
+        } else {
+            Vec3::ZERO
+        };
+
+        let Some((_, mut eq_pos)) = equippable_query.iter_mut().find(|(equippable, _)| equippable.name == *item_name) else {
+            info!("[DROP] No world entity found for '{}' — skipping", item_name);
+            continue;
+        };
+        eq_pos.0 = at + offset;
+        info!("[DROP] Moved {} to {:?}", item_name, eq_pos.0);
+    }
+}
+
+/// Server-only: with `--drop-on-disconnect`, a player who leaves (or a bot
+/// that is removed) drops what they carry where they stood, like on death.
+/// Runs as their entity is despawned, while its components are still there.
+fn drop_items_on_leave(
+    trigger: On<Remove, PlayerId>,
+    player_query: Query<(&PlayerDisplayId, &Position, &PlayerEquipped, &PlayerInventory, Option<&LoadoutItems>), With<PlayerHealth>>,
+    mut equippable_query: Query<(&Equippable, &mut Position), Without<PlayerHealth>>,
+    ruleset: Res<Ruleset>,
+) {
+    if !ruleset.drop_on_disconnect {
+        return;
+    }
+    let Ok((display_id, position, equipped, inventory, loadout)) = player_query.get(trigger.entity) else {
+        return;
+    };
+    let mut items: Vec<String> = equipped.0.iter().chain(inventory.items.iter()).cloned().collect();
+    if let Some(loadout) = loadout {
+        items.retain(|item| !loadout.contains(item));
+    }
+    if items.is_empty() {
+        return;
+    }
+    scatter_items(&items, position.0, &mut equippable_query);
+    info!("[DISCONNECT] Player {} left {} item(s) behind: {:?}", display_id.0, items.len(), items);
+}
+
 /// Server-only: when health drops to 0, mark the player as dead and drop all items.
 /// Equipped item + inventory items are dropped as world Equippable entities at
 /// the death position. This is the core loot loop — die, lose your stuff.
 fn check_player_death(
     mut death_query: Query<
         (Entity, &PlayerHealth, &PlayerId, &PlayerDisplayId, &LastDamagedBy,
-         &Position, &mut PlayerEquipped, &mut PlayerInventory),
+         &Position, &mut PlayerEquipped, &mut PlayerInventory, Option<&Downed>, Option<&LoadoutItems>),
         (Changed<PlayerHealth>, Without<PlayerDead>),
     >,
     all_players: Query<(&PlayerId, &PlayerDisplayId)>,
+    mut stats_query: Query<(&PlayerId, &mut PlayerStats)>,
+    mut streak_query: Query<(&PlayerId, &mut KillStreak)>,
     mut equippable_query: Query<(&Equippable, &mut Position), Without<PlayerHealth>>,
     mut commands: Commands,
     mut pending: ResMut<PendingRespawns>,
+    mut contributors: ResMut<DamageContributors>,
+    mut hazard_damage: ResMut<HazardDamage>,
+    ruleset: Res<Ruleset>,
     time: Res<Time>,
 ) {
+    let now = time.elapsed_secs();
     for (entity, health, player_id, victim_display, last_damaged_by,
-         death_pos, mut equipped, mut inventory) in death_query.iter_mut()
+         death_pos, mut equipped, mut inventory, downed, loadout) in death_query.iter_mut()
     {
         if health.0 > 0 {
             continue;
         }
 
+        // Downed ruleset: zero health downs the player first; they only die
+        // here once process_downed reports they bled out.
+        match (downed, ruleset.downed_bleedout) {
+            (Some(downed), _) if !downed.bled_out => continue,
+            (None, Some(window)) => {
+                info!("[DOWNED] Player {} is down ({}s to revive)", victim_display.0, window);
+                commands.entity(entity).insert(Downed {
+                    since: time.elapsed_secs(),
+                    window,
+                    ..default()
+                });
+                continue;
+            }
+            _ => {}
+        }
+        commands.entity(entity).remove::<Downed>();
+
         let killer_display = all_players.iter()
             .find(|(pid, _)| pid.0 == last_damaged_by.0)
             .map(|(_, d)| d.0)
@@ -373,36 +934,14 @@ fn check_player_death(
             items_to_drop.push(equipped_name);
         }
         items_to_drop.append(&mut inventory.items);
-
-        // Move matching world Equippable entities to the death position.
-        // Spread items slightly so they don't stack on the exact same spot.
-        let drop_pos = death_pos.0;
-        let mut drop_index = 0u32;
-        for item_name in &items_to_drop {
-            // Small offset so items fan out in a circle around the death spot
-            let angle = drop_index as f32 * std::f32::consts::TAU / items_to_drop.len().max(1) as f32;
-            let offset = if items_to_drop.len() > 1 {
-                Vec3::new(angle.cos() * 0.5, 0.0, angle.sin() * 0.5)
-            } else {
-                Vec3::ZERO
-            };
-
-            let mut found = false;
-            for (equippable, mut eq_pos) in equippable_query.iter_mut() {
-                if equippable.name == *item_name {
-                    eq_pos.0 = drop_pos + offset;
-                    found = true;
-                    info!("[DEATH DROP] Moved {} to {:?}", item_name, eq_pos.0);
-                    break;
-                }
-            }
-
-            if !found {
-                info!("[DEATH DROP] No world entity found for '{}' — skipping", item_name);
-            }
-            drop_index += 1;
+        // Loadout items are issued again on respawn, not dropped
+        if let Some(loadout) = loadout {
+            items_to_drop.retain(|item| !loadout.contains(item));
         }
 
+        // Move matching world Equippable entities to the death position
+        scatter_items(&items_to_drop, death_pos.0, &mut equippable_query);
+
         if !items_to_drop.is_empty() {
             info!(
                 "[DEATH] Player {} dropped {} item(s): {:?}",
@@ -410,16 +949,59 @@ fn check_player_death(
             );
         }
 
-        info!(
-            "[DEATH] Player {} killed by Player {}! Respawn in {}s",
-            victim_display.0, killer_display, RESPAWN_DELAY
-        );
-
-        commands.entity(entity).insert(PlayerDead);
+        commands.entity(entity).insert(PlayerDead).remove::<InHazard>();
+        hazard_damage.pending.remove(&entity);
         commands.entity(entity).insert(avian3d::prelude::Rotation(
             Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
         ));
-        pending.timers.push((entity, time.elapsed_secs() + RESPAWN_DELAY));
+        if ruleset.elimination {
+            // Out until the round ends — run_elimination_rounds revives everyone
+            info!(
+                "[DEATH] Player {} killed by Player {}! Eliminated for this round",
+                victim_display.0, killer_display
+            );
+            commands.entity(entity).insert(Eliminated);
+        } else {
+            let respawn_at = match ruleset.respawn_wave {
+                Some(interval) => rules::next_respawn_wave(now, interval),
+                None => now + ruleset.respawn_delay(),
+            };
+            info!(
+                "[DEATH] Player {} killed by Player {}! Respawn in {:.1}s",
+                victim_display.0, killer_display, respawn_at - now
+            );
+            pending.timers.push((entity, respawn_at));
+        }
+
+        // Assists: everyone else who hurt the victim within the assist window
+        let assisters: Vec<u64> = contributors
+            .by_victim
+            .remove(&entity)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(id, at)| *id != player_id.0 && *id != last_damaged_by.0 && now - at <= ASSIST_WINDOW_SECS)
+            .map(|(id, _)| id)
+            .collect();
+
+        // Lifetime stats: death for the victim, kill for the attacker (not for
+        // suicides/falls), assist for the other recent attackers
+        for (pid, mut stats) in stats_query.iter_mut() {
+            if pid.0 == player_id.0 {
+                stats.deaths += 1;
+            } else if pid.0 == last_damaged_by.0 {
+                stats.kills += 1;
+            } else if assisters.contains(&pid.0) {
+                stats.assists += 1;
+            }
+        }
+        // Kill streaks: the victim's ends, the attacker's grows
+        for (pid, mut streak) in streak_query.iter_mut() {
+            if pid.0 == player_id.0 {
+                streak.0 = 0;
+            } else if pid.0 == last_damaged_by.0 {
+                streak.0 += 1;
+            }
+        }
 
         // Spawn kill feed entry — replicated to all clients
         let now = time.elapsed_secs();
@@ -434,6 +1016,76 @@ fn check_player_death(
     }
 }
 
+/// Server-only: grants the configured reward when a player's kill streak
+/// reaches a threshold, and announces it in the kill feed.
+fn grant_streak_rewards(
+    mut query: Query<(Entity, &PlayerId, &PlayerDisplayId, &KillStreak, &mut PlayerHealth), (Changed<KillStreak>, Without<PlayerDead>)>,
+    rewards: Res<KillStreakRewards>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, player_id, display_id, streak, mut health) in query.iter_mut() {
+        for reward in rewards.at(streak.0) {
+            match *reward {
+                StreakReward::Health(amount) => {
+                    health.0 = (health.0 + amount).min(PlayerHealth::default().0);
+                }
+                StreakReward::Speed { multiplier, secs } => {
+                    commands.entity(entity).insert(SpeedBoost { multiplier, expires_at: now + secs });
+                }
+            }
+            info!("[STREAK] Player {} on a {}-kill streak: {}", display_id.0, streak.0, reward.describe());
+            commands.spawn((
+                KillStreakEntry {
+                    player_name: multiplayer::auth::client_id_to_base58(player_id.0),
+                    streak: streak.0,
+                    reward: reward.describe(),
+                    timestamp: now,
+                },
+                Replicate::to_clients(NetworkTarget::All),
+            ));
+        }
+    }
+}
+
+/// Seconds a kill feed or streak entry stays replicated. Clients stop
+/// showing them well before this.
+const FEED_ENTRY_SECS: f32 = 30.0;
+
+/// Server-only: despawns old kill feed and streak entries, which are
+/// otherwise spawned per kill and never cleaned up.
+fn expire_feed_entries(
+    feed_query: Query<(Entity, &KillFeedEntry)>,
+    streak_query: Query<(Entity, &KillStreakEntry)>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    let expired = feed_query
+        .iter()
+        .map(|(entity, entry)| (entity, entry.timestamp))
+        .chain(streak_query.iter().map(|(entity, entry)| (entity, entry.timestamp)))
+        .filter(|(_, timestamp)| now - timestamp > FEED_ENTRY_SECS);
+    for (entity, _) in expired {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Server-only: ends kill-streak speed boosts once they run out, or on death.
+fn expire_speed_boosts(
+    query: Query<(Entity, &SpeedBoost, Has<PlayerDead>)>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, boost, is_dead) in query.iter() {
+        if is_dead || now >= boost.expires_at {
+            commands.entity(entity).remove::<SpeedBoost>();
+        }
+    }
+}
+
 /// Server-only: processes respawn timers. Revives players after delay.
 /// Picks the spawn point furthest from living players to avoid spawn-camping.
 ///
@@ -444,24 +1096,38 @@ fn check_player_death(
 ///   ANIMA_RESPAWN token balance or SOL balance via Solana RPC.
 fn process_respawns(
     mut pending: ResMut<PendingRespawns>,
-    mut query: Query<(&mut PlayerHealth, &mut Position, &mut avian3d::prelude::Rotation, &PlayerId, &mut PlayerEquipped, &mut PlayerInventory), With<PlayerDead>>,
+    mut query: Query<(&mut PlayerHealth, &mut Position, &mut avian3d::prelude::Rotation, &PlayerId, Option<&Team>, Has<Bot>), With<PlayerDead>>,
     living_query: Query<&Position, (With<PlayerId>, Without<PlayerDead>)>,
     mut commands: Commands,
+    loadouts: Res<LoadoutConfig>,
+    utility_loadout: Res<UtilityLoadout>,
     time: Res<Time>,
     respawn_config: Res<RespawnConfig>,
     verified_wallets: Res<VerifiedWallets>,
+    ruleset: Res<Ruleset>,
 ) {
     let now = time.elapsed_secs();
+    // Denied respawns are retried later — in wave mode, with the next wave
+    let retry_at = match ruleset.respawn_wave {
+        Some(interval) => rules::next_respawn_wave(now, interval),
+        None => now + 5.0,
+    };
     let mut i = 0;
     while i < pending.timers.len() {
         if now >= pending.timers[i].1 {
             let (entity, _) = pending.timers.remove(i);
 
-            let Ok((mut health, mut position, mut rotation, player_id, mut equipped, mut inventory)) = query.get_mut(entity) else {
+            let Ok((mut health, mut position, mut rotation, player_id, team, is_bot)) = query.get_mut(entity) else {
                 continue;
             };
 
-            match solana::check_respawn_authorization(&respawn_config, player_id.0, &verified_wallets) {
+            // Bots have no wallet — they always respawn
+            let auth = if is_bot {
+                RespawnAuth::Authorized
+            } else {
+                solana::check_respawn_authorization(&respawn_config, player_id.0, &verified_wallets)
+            };
+            match auth {
                 RespawnAuth::Authorized => {
                     let living_positions: Vec<Vec3> = living_query
                         .iter()
@@ -473,10 +1139,11 @@ fn process_respawns(
                     health.0 = 100;
                     position.0 = spawn_pos;
                     rotation.0 = Quat::IDENTITY;
-                    // Ensure inventory is clean on respawn (should already be empty from death drop)
-                    equipped.0 = None;
-                    inventory.items.clear();
-                    commands.entity(entity).remove::<PlayerDead>();
+                    // Start from the spawn loadout (everything else dropped on death)
+                    commands
+                        .entity(entity)
+                        .insert((loadouts.for_team(team.copied()).grant(), utility_loadout.pouch(), Stamina::default()))
+                        .remove::<PlayerDead>();
                 }
                 RespawnAuth::InsufficientFunds { required_lamports, available_lamports } => {
                     warn!(
@@ -484,7 +1151,7 @@ fn process_respawns(
                         player_id.0, available_lamports, required_lamports
                     );
                     // Re-queue with a retry delay — player may fund wallet
-                    pending.timers.push((entity, now + 5.0));
+                    pending.timers.push((entity, retry_at));
                 }
                 RespawnAuth::WalletNotVerified => {
                     warn!(
@@ -492,7 +1159,7 @@ fn process_respawns(
                         player_id.0
                     );
                     // Re-queue — wallet auth may still be in flight
-                    pending.timers.push((entity, now + 5.0));
+                    pending.timers.push((entity, retry_at));
                 }
             }
         } else {
@@ -501,30 +1168,1181 @@ fn process_respawns(
     }
 }
 
+/// Server-only: keeps the replicated wave countdown current. Only writes when
+/// the whole-second value changes, so it replicates once per second.
+fn update_respawn_wave(mut query: Query<&mut RespawnWave>, time: Res<Time>) {
+    let now = time.elapsed_secs();
+    for mut wave in query.iter_mut() {
+        let next_in = (rules::next_respawn_wave(now, wave.interval_secs) - now).ceil() as u32;
+        if wave.next_in_secs != next_in {
+            wave.next_in_secs = next_in;
+        }
+    }
+}
+
 // ========================================
-// Wallet Auth Verification
+// Elimination rounds
 // ========================================
 
-/// Process incoming wallet auth messages from clients.
-/// Reads WalletAuthMessage from each client's MessageReceiver, verifies the
-/// Ed25519 signature, and maps the pubkey -> Solana wallet address on the player entity.
-fn process_wallet_auth(
-    mut client_query: Query<(&RemoteId, &mut MessageReceiver<WalletAuthMessage>), With<ClientOf>>,
-    mut player_query: Query<(&PlayerId, &mut WalletAddress)>,
-    mut verified_wallets: ResMut<VerifiedWallets>,
+/// Server-only: when the current round's intermission ends (None between
+/// intermissions). A resource rather than a Local so a referee restart can clear it.
+#[derive(Resource, Default)]
+struct NextRoundAt(Option<f32>);
+
+/// Spawns the replicated entities that tell clients about the active rules:
+/// the movement, ADS and lean configs, Hardcore / HeadshotOnly / Ricochets markers, the RespawnWave countdown or a custom
+/// RespawnDelay, CTF flags +
+/// score, and MatchState for round-based rulesets.
+fn spawn_match_state(
+    ruleset: Res<Ruleset>,
+    movement: Res<multiplayer::player::MovementConfig>,
+    ads: Res<multiplayer::player::AdsConfig>,
+    lean: Res<multiplayer::player::LeanConfig>,
+    mut commands: Commands,
 ) {
-    for (remote_id, mut receiver) in client_query.iter_mut() {
-        let client_id_bits = remote_id.0.to_bits();
-
-        // Skip if already verified
-        if verified_wallets.is_verified(client_id_bits) {
-            // Drain any remaining messages
-            for _ in receiver.receive() {}
-            continue;
+    commands.spawn((
+        multiplayer::protocol::ServerMovementConfig(movement.clone()),
+        Name::new("Movement Config"),
+        Replicate::to_clients(NetworkTarget::All),
+    ));
+    commands.spawn((
+        multiplayer::protocol::ServerAdsConfig(*ads),
+        Name::new("ADS Config"),
+        Replicate::to_clients(NetworkTarget::All),
+    ));
+    commands.spawn((
+        multiplayer::protocol::ServerLeanConfig(*lean),
+        Name::new("Lean Config"),
+        Replicate::to_clients(NetworkTarget::All),
+    ));
+    if let Some(interval) = ruleset.respawn_wave {
+        commands.spawn((
+            RespawnWave {
+                interval_secs: interval,
+                next_in_secs: interval.ceil() as u32,
+            },
+            Name::new("Respawn Wave"),
+            Replicate::to_clients(NetworkTarget::All),
+        ));
+    } else if let Some(secs) = ruleset.respawn_delay {
+        commands.spawn((
+            RespawnDelay { secs },
+            Name::new("Respawn Delay"),
+            Replicate::to_clients(NetworkTarget::All),
+        ));
+    }
+    if ruleset.headshot_only {
+        commands.spawn((
+            HeadshotOnly,
+            Name::new("Headshot-Only Training"),
+            Replicate::to_clients(NetworkTarget::All),
+        ));
+    }
+    if ruleset.ricochets {
+        commands.spawn((
+            multiplayer::protocol::Ricochets,
+            Name::new("Ricochet Rules"),
+            Replicate::to_clients(NetworkTarget::All),
+        ));
+    }
+    if ruleset.hardcore {
+        commands.spawn((
+            Hardcore,
+            Name::new("Hardcore Rules"),
+            Replicate::to_clients(NetworkTarget::All),
+        ));
+    }
+    if ruleset.ctf {
+        for team in Team::ALL {
+            let home = Flag::base(team);
+            commands.spawn((
+                Position(home),
+                Flag { team, home, carrier: None, dropped_at: None },
+                Name::new(format!("{} Flag", team.name())),
+                Replicate::to_clients(NetworkTarget::All),
+            ));
         }
+        commands.spawn((
+            CtfScore::default(),
+            Name::new("CTF Score"),
+            Replicate::to_clients(NetworkTarget::All),
+        ));
+    }
+    if !ruleset.elimination {
+        return;
+    }
+    commands.spawn((
+        MatchState { round: 1, ..default() },
+        Name::new("Match State"),
+        Replicate::to_clients(NetworkTarget::All),
+    ));
+}
 
-        for auth_msg in receiver.receive() {
-            info!(
+/// Server-only: last man standing (last team standing under CTF). A round is
+/// decided once someone has been eliminated and at most one player (or team)
+/// still has anyone alive; the survivors are awarded a win. After
+/// ROUND_INTERMISSION every player — survivors included — goes back through
+/// the normal respawn path, so each round starts from full health, a spawn
+/// point and the spawn loadout.
+fn run_elimination_rounds(
+    mut players: Query<
+        (Entity, &PlayerId, Option<&Team>, &Position, &mut PlayerEquipped, &mut PlayerInventory,
+         Option<&LoadoutItems>, Has<PlayerDead>, Has<Eliminated>),
+        With<PlayerHealth>,
+    >,
+    mut equippable_query: Query<(&Equippable, &mut Position), Without<PlayerHealth>>,
+    mut stats_query: Query<&mut PlayerStats>,
+    mut match_query: Query<&mut MatchState>,
+    mut pending: ResMut<PendingRespawns>,
+    mut commands: Commands,
+    ruleset: Res<Ruleset>,
+    time: Res<Time>,
+    mut next_round_at: ResMut<NextRoundAt>,
+) {
+    let Ok(mut match_state) = match_query.single_mut() else { return; };
+    let now = time.elapsed_secs();
+
+    if let Some(start_at) = next_round_at.0 {
+        if now < start_at {
+            return;
+        }
+        next_round_at.0 = None;
+        for (entity, _, _, position, mut equipped, mut inventory, loadout, dead, _) in players.iter_mut() {
+            if !dead {
+                // Survivors drop what they picked up, like on death; the
+                // loadout is issued again on respawn
+                let mut items: Vec<String> = equipped.0.take().into_iter().collect();
+                items.append(&mut inventory.items);
+                if let Some(loadout) = loadout {
+                    items.retain(|item| !loadout.contains(item));
+                }
+                scatter_items(&items, position.0, &mut equippable_query);
+                commands.entity(entity).remove::<Downed>().insert(PlayerDead);
+            }
+            commands.entity(entity).remove::<Eliminated>();
+            pending.timers.retain(|(queued, _)| *queued != entity);
+            pending.timers.push((entity, now));
+        }
+        match_state.round += 1;
+        match_state.intermission = false;
+        info!("[ROUND] Round {} started", match_state.round);
+        return;
+    }
+
+    let any_eliminated = players.iter().any(|(.., eliminated)| eliminated);
+    if !any_eliminated {
+        return;
+    }
+    let alive: Vec<(Entity, u64, Option<Team>)> = players
+        .iter()
+        .filter(|(.., dead, _)| !dead)
+        .map(|(entity, id, team, ..)| (entity, id.0, team.copied()))
+        .collect();
+
+    // Round decided?
+    let winners: Vec<Entity> = if ruleset.ctf {
+        let mut teams: Vec<Team> = Vec::new();
+        for team in alive.iter().filter_map(|(_, _, team)| *team) {
+            if !teams.contains(&team) {
+                teams.push(team);
+            }
+        }
+        if teams.len() > 1 {
+            return;
+        }
+        match_state.last_winner = teams.first().map(|team| team.name().to_string());
+        // The whole team wins, not just whoever is still standing
+        players
+            .iter()
+            .filter(|(_, _, team, ..)| team.is_some() && team.copied() == teams.first().copied())
+            .map(|(entity, ..)| entity)
+            .collect()
+    } else {
+        if alive.len() > 1 {
+            return;
+        }
+        match_state.last_winner = alive.first().map(|(_, id, _)| multiplayer::auth::client_id_to_base58(*id));
+        alive.iter().map(|(entity, ..)| *entity).collect()
+    };
+    match_state.intermission = true;
+    for winner in &winners {
+        if let Ok(mut stats) = stats_query.get_mut(*winner) {
+            stats.wins += 1;
+        }
+    }
+    match match_state.last_winner {
+        Some(ref winner) => info!("[ROUND] Round {} won by {}", match_state.round, winner),
+        None => info!("[ROUND] Round {} ended in a draw", match_state.round),
+    }
+    next_round_at.0 = Some(now + rules::ROUND_INTERMISSION);
+}
+
+// ========================================
+// Capture the flag
+// ========================================
+
+/// Server-only: CTF flag logic.
+/// - Touching the enemy flag picks it up; the server keeps it on the carrier.
+/// - The carrier scores by reaching their own base while their flag is home.
+/// - A carrier who dies, goes down or leaves drops the flag where they were.
+/// - Touching your own dropped flag returns it; untouched, it returns after
+///   FLAG_RETURN_SECS.
+fn run_capture_the_flag(
+    mut flags: Query<(&mut Flag, &mut Position)>,
+    players: Query<(&PlayerId, &Team, &Position, Has<PlayerDead>, Has<Downed>), Without<Flag>>,
+    mut score_query: Query<&mut CtfScore>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    let flag_home: Vec<Team> = flags
+        .iter()
+        .filter(|(flag, pos)| flag.carrier.is_none() && pos.0 == flag.home)
+        .map(|(flag, _)| flag.team)
+        .collect();
+    let mut captures: Vec<Team> = Vec::new();
+
+    for (mut flag, mut pos) in flags.iter_mut() {
+        if let Some(carrier_id) = flag.carrier {
+            let carrier = players
+                .iter()
+                .find(|(id, _, _, dead, downed)| id.0 == carrier_id && !dead && !downed);
+            let Some((_, team, carrier_pos, ..)) = carrier else {
+                info!("[CTF] {} flag dropped at {:?}", flag.team.name(), pos.0);
+                flag.carrier = None;
+                flag.dropped_at = Some(now);
+                continue;
+            };
+            pos.0 = carrier_pos.0;
+            if flag_home.contains(team) && carrier_pos.0.distance(Flag::base(*team)) <= rules::FLAG_CAPTURE_RADIUS {
+                info!("[CTF] Player {} captured the {} flag for {}", carrier_id, flag.team.name(), team.name());
+                captures.push(*team);
+                flag.carrier = None;
+                pos.0 = flag.home;
+            }
+            continue;
+        }
+
+        let at_home = pos.0 == flag.home;
+        if !at_home && flag.dropped_at.is_some_and(|dropped| now - dropped >= rules::FLAG_RETURN_SECS) {
+            info!("[CTF] {} flag returned to base (timeout)", flag.team.name());
+            flag.dropped_at = None;
+            pos.0 = flag.home;
+            continue;
+        }
+
+        // An enemy in reach takes the flag even if a defender stands on it
+        let mut toucher = None;
+        for (id, team, player_pos, dead, downed) in players.iter() {
+            if dead || downed || player_pos.0.distance(pos.0) > rules::FLAG_TOUCH_RADIUS {
+                continue;
+            }
+            if *team != flag.team {
+                toucher = Some((id, team));
+                break;
+            }
+            toucher.get_or_insert((id, team));
+        }
+        let Some((id, team)) = toucher else { continue; };
+        if *team != flag.team {
+            info!("[CTF] Player {} took the {} flag", id.0, flag.team.name());
+            flag.carrier = Some(id.0);
+            flag.dropped_at = None;
+        } else if !at_home {
+            info!("[CTF] Player {} returned the {} flag", id.0, flag.team.name());
+            flag.dropped_at = None;
+            pos.0 = flag.home;
+        }
+    }
+
+    if let Ok(mut score) = score_query.single_mut() {
+        for team in captures {
+            score.add(team);
+        }
+    }
+}
+
+// ========================================
+// Observers & Admin Commands
+// ========================================
+
+/// Checks observer tokens. An accepted client loses the player body spawned
+/// for it on connect and is marked `Observer`; the reply tells the client to
+/// switch to its spectator camera.
+fn process_observer_login(
+    mut client_query: Query<
+        (Entity, &RemoteId, &mut MessageReceiver<ObserverLoginMessage>, &mut MessageSender<ObserverStatusMessage>, Has<Observer>),
+        With<ClientOf>,
+    >,
+    player_query: Query<(Entity, &PlayerId)>,
+    tokens: Res<ObserverTokens>,
+    mut commands: Commands,
+) {
+    for (link, remote_id, mut receiver, mut sender, is_observer) in client_query.iter_mut() {
+        let client_id_bits = remote_id.0.to_bits();
+        for login in receiver.receive() {
+            if is_observer {
+                continue;
+            }
+            let granted = tokens.accepts(&login.token);
+            sender.send::<ObserverChannel>(ObserverStatusMessage { granted });
+            if !granted {
+                warn!("[OBSERVER] Client {} presented an invalid observer token", client_id_bits);
+                continue;
+            }
+
+            info!("[OBSERVER] Client {} joined as observer", client_id_bits);
+            commands.entity(link).insert(Observer);
+            for (player, player_id) in player_query.iter() {
+                if player_id.0 == client_id_bits {
+                    commands.entity(player).despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Runs referee commands. Only links marked `Observer` may issue them.
+fn process_admin_commands(
+    mut client_query: Query<(&RemoteId, &mut MessageReceiver<AdminCommandMessage>, Has<Observer>), With<ClientOf>>,
+    paused_query: Query<Entity, With<MatchPaused>>,
+    mut players: Query<
+        (Entity, &mut PlayerHealth, &mut Position, &mut avian3d::prelude::Rotation, &mut CharacterVelocity, Has<PlayerDead>),
+        With<PlayerId>,
+    >,
+    mut match_query: Query<&mut MatchState>,
+    mut pending: ResMut<PendingRespawns>,
+    mut next_round_at: ResMut<NextRoundAt>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (remote_id, mut receiver, is_observer) in client_query.iter_mut() {
+        let client_id_bits = remote_id.0.to_bits();
+        for AdminCommandMessage(command) in receiver.receive() {
+            if !is_observer {
+                warn!("[ADMIN] Client {} is not an observer — ignoring {:?}", client_id_bits, command);
+                continue;
+            }
+            info!("[ADMIN] Observer {} issued {:?}", client_id_bits, command);
+
+            match command {
+                AdminCommand::Pause => {
+                    if paused_query.is_empty() {
+                        commands.spawn((
+                            MatchPaused,
+                            Name::new("Match Paused"),
+                            Replicate::to_clients(NetworkTarget::All),
+                        ));
+                    }
+                }
+                AdminCommand::Resume => {
+                    for entity in paused_query.iter() {
+                        commands.entity(entity).despawn();
+                    }
+                }
+                AdminCommand::RestartRound => {
+                    let now = time.elapsed_secs();
+                    next_round_at.0 = None;
+                    if let Ok(mut match_state) = match_query.single_mut() {
+                        match_state.intermission = false;
+                    }
+
+                    // Living players go back to (spread out) spawn points at full
+                    // health; dead players respawn through the normal path.
+                    let mut taken: Vec<Vec3> = Vec::new();
+                    for (entity, mut health, mut position, mut rotation, mut velocity, is_dead) in players.iter_mut() {
+                        commands.entity(entity).remove::<Downed>();
+                        if is_dead {
+                            commands.entity(entity).remove::<Eliminated>();
+                            pending.timers.retain(|(e, _)| *e != entity);
+                            pending.timers.push((entity, now));
+                            continue;
+                        }
+                        let spawn_pos = select_spawn_point(&taken);
+                        taken.push(spawn_pos);
+                        health.0 = 100;
+                        position.0 = spawn_pos;
+                        rotation.0 = Quat::IDENTITY;
+                        velocity.0 = Vec3::ZERO;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ========================================
+// Player Preferences
+// ========================================
+
+/// Applies PlayerPreferencesMessage from each client to their player entity.
+fn process_player_preferences(
+    mut client_query: Query<(&RemoteId, &mut MessageReceiver<PlayerPreferencesMessage>), With<ClientOf>>,
+    player_query: Query<(Entity, &PlayerId)>,
+    mut commands: Commands,
+) {
+    for (remote_id, mut receiver) in client_query.iter_mut() {
+        let client_id_bits = remote_id.0.to_bits();
+        for prefs in receiver.receive() {
+            let Some((player, _)) = player_query.iter().find(|(_, pid)| pid.0 == client_id_bits) else {
+                warn!("[PREFS] No player entity for client {} — ignoring preferences", client_id_bits);
+                continue;
+            };
+            info!(
+                "[PREFS] Client {} auto_equip={} auto_crouch_ads={}",
+                client_id_bits, prefs.auto_equip, prefs.auto_crouch_ads
+            );
+            if prefs.auto_equip {
+                commands.entity(player).insert(AutoEquip);
+            } else {
+                commands.entity(player).remove::<AutoEquip>();
+            }
+            if prefs.auto_crouch_ads {
+                commands.entity(player).insert(AutoCrouchAds);
+            } else {
+                commands.entity(player).remove::<AutoCrouchAds>();
+            }
+        }
+    }
+}
+
+// ========================================
+// Message of the day
+// ========================================
+
+/// Sends the MOTD and rule summary to each client as it connects.
+fn send_motd(
+    mut client_query: Query<(&RemoteId, &mut MessageSender<MotdMessage>), (With<ClientOf>, Added<Connected>)>,
+    motd: Res<ServerMotd>,
+    ruleset: Res<Ruleset>,
+) {
+    for (remote_id, mut sender) in client_query.iter_mut() {
+        sender.send::<ServerInfoChannel>(MotdMessage {
+            text: motd.0.clone(),
+            rules: ruleset.summary(),
+        });
+        info!("[MOTD] Sent to client {}", remote_id.0.to_bits());
+    }
+}
+
+// ========================================
+// Anti-AFK
+// ========================================
+
+/// Server-only: when a player last sent input, what they were holding on
+/// Move then, and whether they have been warned since.
+struct AfkState {
+    last_active: f32,
+    last_move: Vec2,
+    warned: bool,
+}
+
+/// Kicks players whose input hasn't changed for AfkConfig::timeout seconds,
+/// with an AfkWarningMessage AfkConfig::warning seconds before. Looking
+/// around, pressing something or changing direction counts as activity; a
+/// key held down the whole time doesn't. Dead players are waiting to
+/// respawn, not idle, and bots are never kicked. While a referee has the
+/// match paused everyone counts as active, so the clock starts over on
+/// resume. Typing in chat releases every action (gate_input_on_chat), so
+/// the server sees it as idle time; the warning leaves time to send the line.
+fn kick_afk_players(
+    config: Res<AfkConfig>,
+    players: Query<(Entity, &PlayerId, &ControlledBy, &ActionState<PlayerActions>, Has<PlayerDead>)>,
+    paused: Query<(), With<MatchPaused>>,
+    mut senders: Query<&mut MessageSender<AfkWarningMessage>, With<Connected>>,
+    mut idle: Local<std::collections::HashMap<Entity, AfkState>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let now = time.elapsed_secs();
+    let running = paused.is_empty();
+    idle.retain(|entity, _| players.contains(*entity));
+    for (entity, id, controlled, action, dead) in players.iter() {
+        let movement = action.axis_pair(&PlayerActions::Move);
+        let state = idle.entry(entity).or_insert(AfkState { last_active: now, last_move: movement, warned: false });
+        let active = !running
+            || dead
+            || movement != state.last_move
+            || action.axis_pair(&PlayerActions::Look) != Vec2::ZERO
+            || !action.get_just_pressed().is_empty();
+        state.last_move = movement;
+        if active {
+            state.last_active = now;
+            state.warned = false;
+            continue;
+        }
+
+        let idle_for = now - state.last_active;
+        if idle_for >= config.timeout {
+            info!("[AFK] Kicking player {} after {:.0}s without input", id.0, idle_for);
+            commands.trigger(Disconnect { entity: controlled.owner });
+            // Don't ask again every tick while the disconnect goes through
+            state.last_active = now;
+        } else if !state.warned && idle_for >= config.timeout - config.warning {
+            state.warned = true;
+            let secs_left = (config.timeout - idle_for).ceil() as u32;
+            if let Ok(mut sender) = senders.get_mut(controlled.owner) {
+                sender.send::<ServerInfoChannel>(AfkWarningMessage { secs_left });
+            }
+            info!("[AFK] Warned player {}: kick in {}s", id.0, secs_left);
+        }
+    }
+}
+
+// ========================================
+// Trading
+// ========================================
+
+/// Handles TradeRequestMessage: the buyer must be alive, within range of a
+/// trading station and able to pay. The price is taken from their Resources
+/// and the item added to their inventory as an issued copy (LoadoutItems), so
+/// it never touches the map entity of the same name. Items the buyer already
+/// carries are refused.
+fn process_trade_requests(
+    mut client_query: Query<(&RemoteId, &mut MessageReceiver<TradeRequestMessage>), With<ClientOf>>,
+    mut players: Query<
+        (&PlayerId, &Position, &mut Resources, &PlayerEquipped, &mut PlayerInventory, &mut LoadoutItems),
+        Without<PlayerDead>,
+    >,
+    stations: Query<(&TradeStation, &Position)>,
+) {
+    for (remote_id, mut receiver) in client_query.iter_mut() {
+        let client_id_bits = remote_id.0.to_bits();
+        for request in receiver.receive() {
+            let Some(offer) = TRADE_OFFERS.get(request.offer as usize) else {
+                warn!("[TRADE] Client {} asked for unknown offer {}", client_id_bits, request.offer);
+                continue;
+            };
+            let Some((_, position, mut resources, equipped, mut inventory, mut issued)) =
+                players.iter_mut().find(|(pid, ..)| pid.0 == client_id_bits)
+            else {
+                warn!("[TRADE] No living player for client {} — ignoring trade", client_id_bits);
+                continue;
+            };
+            let in_range = stations
+                .iter()
+                .any(|(station, station_pos)| position.0.distance(station_pos.0) <= station.interaction_distance);
+            if !in_range {
+                warn!("[TRADE] Client {} is not at a trading station", client_id_bits);
+                continue;
+            }
+            if equipped.0.as_deref() == Some(offer.item) || inventory.items.iter().any(|item| item == offer.item) {
+                info!("[TRADE] Client {} already carries {}", client_id_bits, offer.item);
+                continue;
+            }
+            if !offer.affordable(&resources) {
+                info!("[TRADE] Client {} can't afford {} ({} ore, has {})", client_id_bits, offer.item, offer.ore_cost, resources.ore);
+                continue;
+            }
+            resources.ore -= offer.ore_cost;
+            // An issued copy like the spawn loadout: it doesn't claim the map
+            // pickup of the same name and is gone after the next respawn
+            inventory.items.push(offer.item.to_string());
+            issued.0.push(offer.item.to_string());
+            info!("[TRADE] Client {} bought {} for {} ore", client_id_bits, offer.item, offer.ore_cost);
+        }
+    }
+}
+
+// ========================================
+// Inventory
+// ========================================
+
+/// Pressing Interact next to an ore chunk picks it up into Resources. Chunks
+/// all share one name, so they stack here instead of going through the
+/// Equippable flow (shared_equip_interact_system skips them).
+fn collect_ore_chunks(
+    mut players: Query<(&ActionState<PlayerActions>, &PlayerId, &Position, &mut Resources), Without<PlayerDead>>,
+    chunks: Query<(Entity, &Position, &Equippable, Option<&multiplayer::world::OreStack>)>,
+    mut commands: Commands,
+) {
+    let mut taken = Vec::new();
+    for (action, id, player_pos, mut resources) in players.iter_mut() {
+        if !action.just_pressed(&PlayerActions::Interact) {
+            continue;
+        }
+        let nearest = chunks
+            .iter()
+            .filter(|(entity, _, equippable, _)| equippable.name == multiplayer::world::ORE_CHUNK && !taken.contains(entity))
+            .map(|(entity, pos, equippable, stack)| {
+                (entity, pos.0.distance(player_pos.0), equippable.interaction_distance, stack.map_or(1, |s| s.0))
+            })
+            .filter(|(_, distance, reach, _)| distance <= reach)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((chunk, _, _, count)) = nearest else { continue; };
+        taken.push(chunk);
+        commands.entity(chunk).despawn();
+        resources.ore += count;
+        info!("[INVENTORY] Player {} picked up ore ({} now)", id.0, resources.ore);
+    }
+}
+
+/// Handles DropOreMessage: takes up to `count` ore from a living player's
+/// Resources and drops it in front of them as a single chunk carrying the
+/// whole stack, so a big drop can't flood the physics world with bodies.
+fn process_ore_drops(
+    mut client_query: Query<(&RemoteId, &mut MessageReceiver<DropOreMessage>), With<ClientOf>>,
+    mut players: Query<(&PlayerId, &Position, &PlayerYaw, &mut Resources), Without<PlayerDead>>,
+    mut commands: Commands,
+) {
+    for (remote_id, mut receiver) in client_query.iter_mut() {
+        let client_id_bits = remote_id.0.to_bits();
+        for request in receiver.receive() {
+            let Some((_, position, yaw, mut resources)) = players.iter_mut().find(|(pid, ..)| pid.0 == client_id_bits) else {
+                warn!("[INVENTORY] No living player for client {} — ignoring drop", client_id_bits);
+                continue;
+            };
+            let count = request.count.min(resources.ore);
+            if count == 0 {
+                continue;
+            }
+            resources.ore -= count;
+            let forward = Quat::from_rotation_y(yaw.0) * Vec3::NEG_Z;
+            let at = position.0 + forward + Vec3::Y * 0.3;
+            commands.spawn(multiplayer::world::ore_chunk_bundle(at, count));
+            info!("[INVENTORY] Client {} dropped {} ore ({} left)", client_id_bits, count, resources.ore);
+        }
+    }
+}
+
+// ========================================
+// Chat
+// ========================================
+
+/// Relays ChatMessage lines to every client, tagged with the sender's display
+/// id. Lines are trimmed and cut to MAX_CHAT_LEN; empty ones are dropped.
+fn relay_chat(
+    mut receivers: Query<(&RemoteId, &mut MessageReceiver<ChatMessage>), With<ClientOf>>,
+    mut senders: Query<&mut MessageSender<ChatBroadcast>, (With<ClientOf>, With<Connected>)>,
+    players: Query<(&PlayerId, &PlayerDisplayId)>,
+) {
+    let mut lines = Vec::new();
+    for (remote_id, mut receiver) in receivers.iter_mut() {
+        let client_id_bits = remote_id.0.to_bits();
+        for message in receiver.receive() {
+            let text: String = message.text.trim().chars().take(MAX_CHAT_LEN).collect();
+            if text.is_empty() {
+                continue;
+            }
+            let Some((_, display_id)) = players.iter().find(|(pid, _)| pid.0 == client_id_bits) else {
+                continue;
+            };
+            info!("[CHAT] Player {}: {}", display_id.0, text);
+            lines.push(ChatBroadcast { sender: display_id.0, text });
+        }
+    }
+    for line in lines {
+        for mut sender in senders.iter_mut() {
+            sender.send::<ChatChannel>(line.clone());
+        }
+    }
+}
+
+// ========================================
+// Team swap
+// ========================================
+
+/// Handles TeamSwapRequestMessage: in a team ruleset, moves the player to the
+/// other team if that keeps the team sizes within the allowed imbalance and
+/// they aren't carrying a flag. A living player has to be unhurt (so a swap
+/// can't heal or teleport anyone out of a fight) and is moved to a spawn
+/// point with the new team's loadout; a dead one just respawns on the new
+/// team after the normal delay. Every request gets a reply.
+fn process_team_swaps(
+    mut client_query: Query<
+        (&RemoteId, &mut MessageReceiver<TeamSwapRequestMessage>, &mut MessageSender<TeamSwapReplyMessage>),
+        With<ClientOf>,
+    >,
+    mut players: Query<
+        (Entity, &PlayerId, &mut Team, &PlayerHealth, &mut Position, &mut avian3d::prelude::Rotation, &mut CharacterVelocity, Has<PlayerDead>, Has<Downed>),
+    >,
+    flags: Query<&Flag>,
+    ruleset: Res<Ruleset>,
+    loadouts: Res<LoadoutConfig>,
+    utility_loadout: Res<UtilityLoadout>,
+    mut commands: Commands,
+) {
+    for (remote_id, mut receiver, mut sender) in client_query.iter_mut() {
+        let client_id_bits = remote_id.0.to_bits();
+        for _ in receiver.receive() {
+            let refuse = |sender: &mut MessageSender<TeamSwapReplyMessage>, text: &str| {
+                info!("[TEAM] Client {} swap refused: {}", client_id_bits, text);
+                sender.send::<TeamChannel>(TeamSwapReplyMessage { accepted: false, text: text.to_string() });
+            };
+            if !ruleset.ctf {
+                refuse(&mut sender, "There are no teams in this match");
+                continue;
+            }
+            let Some((from, hurt)) = players
+                .iter()
+                .find(|(_, pid, ..)| pid.0 == client_id_bits)
+                .map(|(_, _, team, health, .., dead, downed)| (*team, !dead && (downed || health.0 < PlayerHealth::default().0)))
+            else {
+                warn!("[TEAM] No player for client {} — ignoring swap", client_id_bits);
+                continue;
+            };
+            if hurt {
+                refuse(&mut sender, "You can't switch teams while hurt");
+                continue;
+            }
+            if flags.iter().any(|flag| flag.carrier == Some(client_id_bits)) {
+                refuse(&mut sender, "Drop the flag before switching teams");
+                continue;
+            }
+            let max_imbalance = ruleset.team_swap_imbalance();
+            if !rules::swap_keeps_balance(players.iter().map(|(_, _, team, ..)| *team), from, max_imbalance) {
+                refuse(&mut sender, "Switching would unbalance the teams");
+                continue;
+            }
+
+            let to = if from == Team::Red { Team::Blue } else { Team::Red };
+            let taken: Vec<Vec3> = players
+                .iter()
+                .filter(|(_, pid, .., dead, _)| pid.0 != client_id_bits && !*dead)
+                .map(|(_, _, _, _, position, ..)| position.0)
+                .collect();
+            let Some((entity, _, mut team, _, mut position, mut rotation, mut velocity, dead, _)) =
+                players.iter_mut().find(|(_, pid, ..)| pid.0 == client_id_bits)
+            else {
+                continue;
+            };
+            *team = to;
+            if !dead {
+                // Back to a spawn point with the new team's loadout
+                position.0 = select_spawn_point(&taken);
+                rotation.0 = Quat::IDENTITY;
+                velocity.0 = Vec3::ZERO;
+                commands
+                    .entity(entity)
+                    .insert((loadouts.for_team(Some(to)).grant(), utility_loadout.pouch(), Stamina::default()));
+            }
+            info!("[TEAM] Client {} switched from {} to {}", client_id_bits, from.name(), to.name());
+            sender.send::<TeamChannel>(TeamSwapReplyMessage {
+                accepted: true,
+                text: format!("You joined {}", to.name()),
+            });
+        }
+    }
+}
+
+// ========================================
+// Utility (grenades)
+// ========================================
+
+/// Server-only: when a thrown utility goes off (elapsed secs).
+#[derive(Component)]
+struct UtilityFuse(f32);
+
+/// Server-only: when a player's `Flashed` or a `SmokeCloud` wears off.
+#[derive(Component)]
+struct UtilityExpiry(f32);
+
+/// Throws the selected utility on `just_pressed(Throw)` and cycles the
+/// selection on `just_pressed(CycleUtility)`. Each kind has its own cooldown
+/// per player; a throw with none left in the pouch does nothing.
+fn throw_utility(
+    mut players: Query<
+        (Entity, &ActionState<PlayerActions>, &Position, &PlayerYaw, &PlayerPitch, &PlayerId, &mut UtilityPouch),
+        (Without<PlayerDead>, Without<Downed>),
+    >,
+    stance_query: Query<&multiplayer::protocol::PlayerStance>,
+    lean_config: Res<multiplayer::player::LeanConfig>,
+    mut last_throw: Local<std::collections::HashMap<(Entity, UtilityKind), f32>>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, action, pos, yaw, pitch, id, mut pouch) in players.iter_mut() {
+        if action.just_pressed(&PlayerActions::CycleUtility) {
+            pouch.cycle();
+        }
+        if !action.just_pressed(&PlayerActions::Throw) {
+            continue;
+        }
+        let kind = pouch.selected;
+        let last = last_throw.get(&(entity, kind)).copied().unwrap_or(f32::MIN);
+        if now - last < kind.cooldown_secs() || !pouch.take(kind) {
+            continue;
+        }
+        last_throw.insert((entity, kind), now);
+
+        let eye = pos.0
+            + stance_query
+                .get(entity)
+                .map(|s| s.eye_offset(yaw.0, &lean_config))
+                .unwrap_or(Vec3::Y * multiplayer::player::EYE_HEIGHT);
+        let aim = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0) * Vec3::NEG_Z;
+        info!("[UTILITY] Player {} threw a {} ({} left)", id.0, kind.name(), pouch.count(kind));
+        commands.spawn((
+            // Start clear of the thrower's capsule
+            Position(eye + aim * 0.6),
+            avian3d::prelude::Rotation::default(),
+            avian3d::prelude::RigidBody::Dynamic,
+            avian3d::prelude::Collider::sphere(utility::UTILITY_RADIUS),
+            // Small and fast: sweep so a throw can't tunnel through a thin
+            // wall or roof slab between two ticks
+            avian3d::prelude::SweptCcd::default(),
+            avian3d::prelude::LinearVelocity(aim * utility::THROW_SPEED + Vec3::Y * utility::THROW_LOB),
+            ThrownUtility { kind, thrower: id.0 },
+            UtilityFuse(now + kind.fuse_secs()),
+            Name::new(kind.name()),
+            Replicate::to_clients(NetworkTarget::All),
+        ));
+        // Fall back to whatever is still in the pouch
+        if pouch.count(kind) == 0 {
+            pouch.cycle();
+        }
+    }
+}
+
+/// Whether nothing but `target` (or nothing at all) lies between `from` and `to`.
+/// `utility` is the grenade the ray starts inside; its despawn is deferred, so
+/// its collider is still in the pipeline and has to be excluded.
+fn utility_line_of_sight(spatial_query: &SpatialQuery, from: Vec3, to: Vec3, target: Entity, utility: Entity) -> bool {
+    let Ok((dir, distance)) = Dir3::new_and_length(to - from) else {
+        return true;
+    };
+    let filter = SpatialQueryFilter::from_excluded_entities([utility]);
+    match spatial_query.cast_ray(from, dir, distance, true, &filter) {
+        Some(hit) => hit.entity == target,
+        None => true,
+    }
+}
+
+/// Sets off utilities whose fuse has run out:
+/// - Frag: damage falling off with distance to every player in the open
+///   within its radius, credited to the thrower. None under --headshot-only,
+///   where only headshots deal damage.
+/// - Flashbang: blinds (`Flashed`) players who can see it (not through walls
+///   or smoke) and face it.
+/// - Smoke: leaves a `SmokeCloud` for SmokeSettings::secs.
+fn detonate_utilities(
+    utilities: Query<(Entity, &ThrownUtility, &Position, &UtilityFuse)>,
+    mut players: Query<
+        (Entity, &Position, &PlayerYaw, &PlayerPitch, &PlayerId, &mut PlayerHealth, Option<&mut LastDamagedBy>),
+        Without<PlayerDead>,
+    >,
+    smoke_query: Query<(&Position, &SmokeCloud), Without<PlayerId>>,
+    stance_query: Query<&multiplayer::protocol::PlayerStance>,
+    lean_config: Res<multiplayer::player::LeanConfig>,
+    spatial_query: SpatialQuery,
+    ruleset: Res<Ruleset>,
+    smoke_settings: Res<SmokeSettings>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    let clouds: Vec<(Vec3, f32)> = smoke_query.iter().map(|(pos, cloud)| (pos.0, cloud.radius)).collect();
+    for (entity, utility, pos, fuse) in utilities.iter() {
+        if now < fuse.0 {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        let center = pos.0;
+        let radius = utility.kind.radius();
+        info!("[UTILITY] {} went off at {:?}", utility.kind.name(), center);
+
+        match utility.kind {
+            UtilityKind::Frag if ruleset.headshot_only => {}
+            UtilityKind::Frag => {
+                for (player, player_pos, _, _, id, mut health, last_damaged) in players.iter_mut() {
+                    let chest = player_pos.0 + Vec3::Y * (multiplayer::player::EYE_HEIGHT * 0.5);
+                    let distance = chest.distance(center);
+                    if distance > radius || health.0 <= 0 {
+                        continue;
+                    }
+                    if !utility_line_of_sight(&spatial_query, center, chest, player, entity) {
+                        continue;
+                    }
+                    let damage = if ruleset.hardcore {
+                        health.0
+                    } else {
+                        (utility::FRAG_DAMAGE * (1.0 - distance / radius)).round() as i32
+                    };
+                    if damage <= 0 {
+                        continue;
+                    }
+                    health.0 -= damage;
+                    if let Some(mut last) = last_damaged {
+                        last.0 = utility.thrower;
+                    }
+                    info!("[UTILITY] Frag hit Player {} for {} (health {})", id.0, damage, health.0);
+                }
+            }
+            UtilityKind::Flashbang => {
+                for (player, player_pos, yaw, pitch, id, _, _) in players.iter() {
+                    let eye = player_pos.0
+                        + stance_query
+                            .get(player)
+                            .map(|s| s.eye_offset(yaw.0, &lean_config))
+                            .unwrap_or(Vec3::Y * multiplayer::player::EYE_HEIGHT);
+                    let view = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0) * Vec3::NEG_Z;
+                    let secs = utility::flash_duration(eye, view, center);
+                    if secs <= 0.0
+                        || utility::smoke_between(center, eye, clouds.iter().copied())
+                        || !utility_line_of_sight(&spatial_query, center, eye, player, entity)
+                    {
+                        continue;
+                    }
+                    info!("[UTILITY] Player {} flashed for {:.1}s", id.0, secs);
+                    commands.entity(player).insert((Flashed { secs }, UtilityExpiry(now + secs)));
+                }
+            }
+            UtilityKind::Smoke => {
+                commands.spawn((
+                    Position(center),
+                    SmokeCloud { radius, secs: smoke_settings.secs, remaining_secs: smoke_settings.secs.ceil() as u32 },
+                    UtilityExpiry(now + smoke_settings.secs),
+                    Name::new("Smoke Cloud"),
+                    Replicate::to_clients(NetworkTarget::All),
+                ));
+            }
+        }
+    }
+}
+
+/// Turns homing utilities in flight toward the nearest enemy of their
+/// thrower (anyone else, or the other team in CTF) within HOMING_RANGE and
+/// HomingSettings::cone of their flight direction, by at most the turn rate.
+/// Only the velocity changes; the physics step and Position replication do
+/// the rest, so clients see the curve.
+fn steer_homing_utilities(
+    homing: Res<HomingSettings>,
+    mut utilities: Query<(&ThrownUtility, &Position, &mut avian3d::prelude::LinearVelocity)>,
+    players: Query<(&PlayerId, &Position, Option<&Team>), Without<PlayerDead>>,
+    time: Res<Time>,
+) {
+    let max_turn = homing.turn_rate * time.delta_secs();
+    for (thrown, pos, mut velocity) in utilities.iter_mut() {
+        if !homing.kinds.contains(&thrown.kind) {
+            continue;
+        }
+        let thrower_team = players.iter().find(|(id, ..)| id.0 == thrown.thrower).and_then(|(_, _, team)| team);
+        let heading = velocity.0.normalize_or_zero();
+        let target = players
+            .iter()
+            .filter(|(id, _, team)| id.0 != thrown.thrower && (thrower_team.is_none() || *team != thrower_team))
+            // Aim at the chest rather than the feet
+            .map(|(_, target_pos, _)| target_pos.0 + Vec3::Y * (multiplayer::player::EYE_HEIGHT * 0.5) - pos.0)
+            .filter(|to_target| {
+                to_target.length() <= utility::HOMING_RANGE && heading.angle_between(to_target.normalize_or_zero()) <= homing.cone
+            })
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()));
+        if let Some(to_target) = target {
+            velocity.0 = homing.steer(velocity.0, to_target, max_turn);
+        }
+    }
+}
+
+/// Clears `Flashed` from players and despawns smoke clouds once they expire.
+/// Until then, keeps each cloud's replicated `remaining_secs` current (only
+/// writing when the whole-second value changes, like the respawn wave).
+fn expire_utility_effects(
+    mut query: Query<(Entity, &UtilityExpiry, Option<&mut SmokeCloud>)>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, expiry, smoke) in query.iter_mut() {
+        if now < expiry.0 {
+            if let Some(mut cloud) = smoke {
+                let remaining = (expiry.0 - now).ceil() as u32;
+                if cloud.remaining_secs != remaining {
+                    cloud.remaining_secs = remaining;
+                }
+            }
+            continue;
+        }
+        if smoke.is_some() {
+            commands.entity(entity).despawn();
+        } else {
+            commands.entity(entity).remove::<(Flashed, UtilityExpiry)>();
+        }
+    }
+}
+
+// ========================================
+// Area of Interest
+// ========================================
+
+// Entities with NetworkVisibility are only replicated to the clients they
+// have been made visible to; the observers below opt players, loose items
+// and thrown utilities into that as they spawn.
+
+fn cull_players(trigger: On<Add, PlayerId>, mut commands: Commands) {
+    commands.entity(trigger.entity).insert(NetworkVisibility::default());
+}
+
+fn cull_items(trigger: On<Add, Equippable>, mut commands: Commands) {
+    commands.entity(trigger.entity).insert(NetworkVisibility::default());
+}
+
+fn cull_utilities(trigger: On<Add, ThrownUtility>, mut commands: Commands) {
+    commands.entity(trigger.entity).insert(NetworkVisibility::default());
+}
+
+/// Shows each client the culled entities within AoiConfig::radius of its
+/// player and hides the rest. A client always sees its own player and its
+/// teammates; observers (and clients without a player) see everything.
+fn update_interest(
+    config: Res<AoiConfig>,
+    clients: Query<(Entity, Has<Observer>), (With<ClientOf>, With<ReplicationSender>)>,
+    viewers: Query<(&ControlledBy, &Position, Option<&Team>), With<PlayerId>>,
+    mut culled: Query<(Entity, &Position, &mut NetworkVisibility, Option<&ControlledBy>, Option<&Team>)>,
+    mut visible: ResMut<InterestVisibility>,
+) {
+    let mut now_visible = std::collections::HashSet::new();
+    for (client, is_observer) in clients.iter() {
+        let viewer = viewers.iter().find(|(controlled, ..)| controlled.owner == client);
+        for (entity, pos, _, controlled, team) in culled.iter() {
+            let seen = match viewer {
+                _ if is_observer => true,
+                None => true,
+                Some(_) if controlled.is_some_and(|c| c.owner == client) => true,
+                Some((_, _, Some(own_team))) if team == Some(own_team) => true,
+                Some((_, viewer_pos, _)) => config.relevant(viewer_pos.0, pos.0, visible.contains(&(client, entity))),
+            };
+            if seen {
+                now_visible.insert((client, entity));
+            }
+        }
+    }
+
+    for &(client, entity) in now_visible.difference(&visible.0) {
+        if let Ok((_, _, mut network_visibility, ..)) = culled.get_mut(entity) {
+            network_visibility.gain_visibility(client);
+        }
+    }
+    for &(client, entity) in visible.0.difference(&now_visible) {
+        if let Ok((_, _, mut network_visibility, ..)) = culled.get_mut(entity) {
+            network_visibility.lose_visibility(client);
+        }
+    }
+    visible.0 = now_visible;
+}
+
+/// Which (client, entity) pairs update_interest currently has visible.
+#[derive(Resource, Default)]
+struct InterestVisibility(std::collections::HashSet<(Entity, Entity)>);
+
+/// Disables dynamic bodies (ore chunks, thrown utilities) that are out of
+/// PhysicsCullConfig::radius of every living player and re-enables them once
+/// one comes back into range. Disabled bodies keep their velocity, so a chunk
+/// frozen mid-fall carries on falling when woken.
+fn cull_far_physics(
+    config: Res<PhysicsCullConfig>,
+    players: Query<&Position, (With<PlayerId>, Without<PlayerDead>)>,
+    bodies: Query<(Entity, &Position, &avian3d::prelude::RigidBody, Has<avian3d::prelude::RigidBodyDisabled>), Without<PlayerId>>,
+    mut commands: Commands,
+) {
+    for (entity, pos, body, disabled) in bodies.iter() {
+        if !body.is_dynamic() {
+            continue;
+        }
+        let simulate = config.simulate(players.iter().map(|p| p.0), pos.0, !disabled);
+        if simulate && disabled {
+            commands.entity(entity).remove::<avian3d::prelude::RigidBodyDisabled>();
+            info!("[AOI] Woke {:?} at {:?}", entity, pos.0);
+        } else if !simulate && !disabled {
+            commands.entity(entity).insert(avian3d::prelude::RigidBodyDisabled);
+            info!("[AOI] Put {:?} at {:?} to sleep", entity, pos.0);
+        }
+    }
+}
+
+/// Inspector only: interest overlay settings. `client` is the client link
+/// whose view is drawn.
+#[derive(Resource, Default)]
+struct InterestDebug {
+    enabled: bool,
+    client: Option<Entity>,
+}
+
+/// Inspector only: picks the client whose interest is drawn.
+fn interest_debug_panel(
+    mut contexts: EguiContexts,
+    mut debug: ResMut<InterestDebug>,
+    clients: Query<Entity, With<ClientOf>>,
+    players: Query<(&ControlledBy, &PlayerId)>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+    egui::Window::new("Interest")
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-16.0, 16.0))
+        .show(ctx, |ui| {
+            ui.checkbox(&mut debug.enabled, "Show interest radius");
+            for client in clients.iter() {
+                let player = players
+                    .iter()
+                    .find(|(controlled, _)| controlled.owner == client)
+                    .map(|(_, id)| format!("player #{}", id.0))
+                    .unwrap_or_else(|| "no player".to_string());
+                let selected = debug.client == Some(client);
+                if ui.selectable_label(selected, format!("{:?}  {}", client, player)).clicked() {
+                    debug.client = Some(client);
+                }
+            }
+        });
+}
+
+/// Inspector only: draws the selected client's interest sphere (inner: gain
+/// radius, outer: where visible entities are dropped) and marks every culled
+/// entity green if that client sees it, red if not.
+fn draw_interest_debug(
+    debug: Res<InterestDebug>,
+    config: Res<AoiConfig>,
+    visible: Res<InterestVisibility>,
+    viewers: Query<(&ControlledBy, &Position), With<PlayerId>>,
+    culled: Query<(Entity, &Position), With<NetworkVisibility>>,
+    mut gizmos: Gizmos,
+) {
+    let Some(client) = debug.client.filter(|_| debug.enabled) else { return; };
+    if let Some((_, center)) = viewers.iter().find(|(controlled, _)| controlled.owner == client) {
+        gizmos.sphere(Isometry3d::from_translation(center.0), config.radius, Color::srgb(0.3, 0.7, 1.0));
+        gizmos.sphere(
+            Isometry3d::from_translation(center.0),
+            config.radius * interest::AOI_HYSTERESIS,
+            Color::srgba(0.3, 0.7, 1.0, 0.3),
+        );
+    }
+    for (entity, pos) in culled.iter() {
+        let color = if visible.0.contains(&(client, entity)) {
+            Color::srgb(0.2, 1.0, 0.3)
+        } else {
+            Color::srgb(1.0, 0.2, 0.2)
+        };
+        gizmos.sphere(Isometry3d::from_translation(pos.0 + Vec3::Y * 2.0), 0.3, color);
+    }
+}
+
+// ========================================
+// Wallet Auth Verification
+// ========================================
+
+/// Process incoming wallet auth messages from clients.
+/// Reads WalletAuthMessage from each client's MessageReceiver, verifies the
+/// Ed25519 signature, and maps the pubkey -> Solana wallet address on the player entity.
+/// A verified client gets back its lifetime stats from the StatsStore and,
+/// after a server restart, what it carried from the match autosave.
+fn process_wallet_auth(
+    mut client_query: Query<(&RemoteId, &mut MessageReceiver<WalletAuthMessage>), With<ClientOf>>,
+    mut player_query: Query<(Entity, &PlayerId, &mut WalletAddress)>,
+    mut verified_wallets: ResMut<VerifiedWallets>,
+    stats_store: Res<StatsStore>,
+    mut autosave: Option<ResMut<MatchAutosave>>,
+    mut commands: Commands,
+) {
+    for (remote_id, mut receiver) in client_query.iter_mut() {
+        let client_id_bits = remote_id.0.to_bits();
+
+        // Skip if already verified
+        if verified_wallets.is_verified(client_id_bits) {
+            // Drain any remaining messages
+            for _ in receiver.receive() {}
+            continue;
+        }
+
+        for auth_msg in receiver.receive() {
+            info!(
+                target: "network",
                 "[AUTH] Received wallet auth from client {} (pubkey: {})",
                 client_id_bits,
                 auth::pubkey_address(&auth_msg.pubkey)
@@ -537,6 +2355,7 @@ fn process_wallet_auth(
             ) {
                 Ok(wallet_address) => {
                     info!(
+                        target: "network",
                         "[AUTH] Wallet VERIFIED for client {}: {}",
                         client_id_bits, wallet_address
                     );
@@ -545,19 +2364,33 @@ fn process_wallet_auth(
                     verified_wallets.wallets.insert(client_id_bits, wallet_address.clone());
 
                     // Update the player entity's WalletAddress component (replicated to all)
-                    for (player_id, mut wallet) in player_query.iter_mut() {
+                    for (player, player_id, mut wallet) in player_query.iter_mut() {
                         if player_id.0 == client_id_bits {
                             wallet.0 = wallet_address.clone();
                             info!(
+                                target: "network",
                                 "[AUTH] WalletAddress set on player entity for client {}",
                                 client_id_bits
                             );
+                            // Lifetime stats carried over from previous sessions with this keypair
+                            commands.entity(player).insert(stats_store.get(client_id_bits));
+                            // Hand back what this player carried before a server restart
+                            if let Some(saved) = autosave.as_mut().and_then(|a| a.pending_players.remove(&client_id_bits)) {
+                                info!("[AUTOSAVE] Restoring client {}: {:?} + {:?}", client_id_bits, saved.equipped, saved.inventory);
+                                commands.entity(player).insert((
+                                    PlayerEquipped(saved.equipped),
+                                    PlayerInventory { items: saved.inventory },
+                                    KillStreak(saved.kill_streak),
+                                    saved.resources,
+                                ));
+                            }
                             break;
                         }
                     }
                 }
                 Err(e) => {
                     warn!(
+                        target: "network",
                         "[AUTH] Wallet auth FAILED for client {}: {}",
                         client_id_bits, e
                     );
@@ -566,3 +2399,753 @@ fn process_wallet_auth(
         }
     }
 }
+
+// ========================================
+// Bots
+// ========================================
+
+/// Bot PlayerIds start here so they never collide with keypair-derived client ids
+/// in practice and are easy to spot in logs.
+const BOT_ID_BASE: u64 = 0xB07 << 48;
+
+/// Server-only: adds or removes bots so humans + bots == BotFill::target.
+/// Dead bots are removed first. Bots are regular player entities without a
+/// client: no ControlledBy, an idle ActionState, and interpolated on every client.
+/// Holds off while a referee has the match paused, like the other match systems.
+fn balance_bots(
+    fill: Res<BotFill>,
+    humans: Query<(), (With<PlayerId>, Without<Bot>)>,
+    bots: Query<(Entity, &PlayerId, Has<PlayerDead>), With<Bot>>,
+    living_query: Query<&Position, (With<PlayerId>, Without<PlayerDead>)>,
+    team_query: Query<&Team>,
+    ruleset: Res<Ruleset>,
+    loadouts: Res<LoadoutConfig>,
+    utility_loadout: Res<UtilityLoadout>,
+    mut counter: ResMut<PlayerCounter>,
+    mut next_bot: Local<u64>,
+    mut commands: Commands,
+) {
+    let wanted = fill.target.saturating_sub(humans.iter().count());
+    let current = bots.iter().count();
+
+    if current > wanted {
+        let mut removable: Vec<_> = bots.iter().collect();
+        // Dead bots first, then the newest
+        removable.sort_by_key(|(_, id, dead)| (!*dead, std::cmp::Reverse(id.0)));
+        for (entity, id, _) in removable.into_iter().take(current - wanted) {
+            info!("[BOTS] Removing bot {}", id.0 - BOT_ID_BASE);
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let mut taken: Vec<Vec3> = living_query.iter().map(|p| p.0).collect();
+    let mut teams: Vec<Team> = team_query.iter().copied().collect();
+    for _ in current..wanted {
+        *next_bot += 1;
+        counter.0 += 1;
+        let bot_id = BOT_ID_BASE + *next_bot;
+        let spawn_pos = select_spawn_point(&taken);
+        taken.push(spawn_pos);
+
+        let bot = commands.spawn((
+            player_replicated_bundle(bot_id),
+            player_physics_bundle(),
+            PlayerDisplayId(counter.0),
+            Bot,
+            BotPatrol::default(),
+            Name::new(format!("Bot {}", *next_bot)),
+            Replicate::to_clients(NetworkTarget::All),
+            InterpolationTarget::to_clients(NetworkTarget::All),
+            LagCompensationHistory::default(),
+        ))
+        .insert(Position(spawn_pos))
+        .id();
+        let team = ruleset.ctf.then(|| rules::smaller_team(teams.iter().copied()));
+        if let Some(team) = team {
+            teams.push(team);
+            commands.entity(bot).insert(team);
+        }
+        commands.entity(bot).insert((loadouts.for_team(team).grant(), utility_loadout.pouch()));
+        info!("[BOTS] Added bot {} (Player {}) at {:?}", *next_bot, counter.0, spawn_pos);
+    }
+}
+
+/// Server-only: headshot-only training targets. Bots with an even id stand
+/// still; odd ones strafe left and right, turning around every
+/// TRAINING_STRAFE_SECS.
+fn drive_training_bots(
+    mut bots: Query<(&PlayerId, &mut ActionState<PlayerActions>), With<Bot>>,
+    time: Res<Time>,
+) {
+    let leg = (time.elapsed_secs() / TRAINING_STRAFE_SECS) as u64;
+    for (id, mut action) in bots.iter_mut() {
+        let strafe = if id.0 % 2 == 0 {
+            0.0
+        } else if (leg + id.0) % 2 == 0 {
+            1.0
+        } else {
+            -1.0
+        };
+        action.set_axis_pair(&PlayerActions::Move, Vec2::new(strafe, 0.0));
+    }
+}
+
+/// Patrolling bots walk at this fraction of PLAYER_MOVE_SPEED.
+const BOT_PATROL_SPEED: f32 = 0.5;
+/// A waypoint counts as reached within this many meters (horizontally).
+const BOT_WAYPOINT_RADIUS: f32 = 1.5;
+/// A bot that hasn't reached its waypoint after this long (stuck on a wall)
+/// picks another.
+const BOT_WAYPOINT_SECS: f32 = 10.0;
+
+/// Server-only: where a bot with nobody to chase is walking to, and when it
+/// gives up on getting there.
+#[derive(Component, Default)]
+struct BotPatrol {
+    waypoint: Option<Vec3>,
+    give_up_at: f32,
+}
+
+/// Fastest a bot turns, in radians per tick.
+const BOT_TURN_RATE: f32 = 0.15;
+/// Bots stop closing in once they're this near their target (meters).
+const BOT_KEEP_DISTANCE: f32 = 8.0;
+/// Bots only pull the trigger when aimed within this many radians.
+const BOT_FIRE_CONE: f32 = 0.05;
+
+/// Server-only: bots pick the nearest living human they can see within
+/// BotFill::aggro_range (not teammates in CTF), turn toward them, walk in to
+/// BOT_KEEP_DISTANCE and fire once on target. With nobody in range they
+/// hold fire and patrol between spawn points (see patrol_bot). Input goes
+/// through the bot's ActionState, so movement, look and shooting run through
+/// the same systems as players'.
+fn drive_bots(
+    mut bots: Query<
+        (
+            Entity,
+            &Position,
+            &PlayerYaw,
+            &PlayerPitch,
+            Option<&Team>,
+            &mut ActionState<PlayerActions>,
+            &mut BotPatrol,
+        ),
+        (With<Bot>, Without<PlayerDead>),
+    >,
+    targets: Query<(Entity, &Position, Option<&Team>), (With<PlayerId>, Without<Bot>, Without<PlayerDead>)>,
+    spatial_query: SpatialQuery,
+    fill: Res<BotFill>,
+    time: Res<Time>,
+) {
+    for (bot, pos, yaw, pitch, team, mut action, mut patrol) in bots.iter_mut() {
+        let eye = pos.0 + Vec3::Y * multiplayer::player::EYE_HEIGHT;
+        let filter = SpatialQueryFilter::from_excluded_entities([bot]);
+        let target = targets
+            .iter()
+            .filter(|(_, _, other_team)| team.is_none() || team != *other_team)
+            .map(|(entity, target_pos, _)| (entity, target_pos.0 + Vec3::Y * 0.3))
+            .filter(|(_, aim)| aim.distance(eye) <= fill.aggro_range)
+            .filter(|(entity, aim)| {
+                let Ok((dir, distance)) = Dir3::new_and_length(*aim - eye) else {
+                    return true;
+                };
+                spatial_query
+                    .cast_ray(eye, dir, distance, true, &filter)
+                    .is_none_or(|hit| hit.entity == *entity)
+            })
+            .min_by(|(_, a), (_, b)| a.distance_squared(eye).total_cmp(&b.distance_squared(eye)));
+
+        let Some((_, aim)) = target else {
+            action.release(&PlayerActions::Primary);
+            patrol_bot(bot, eye, yaw.0, pitch.0, &mut action, &mut patrol, &spatial_query, time.elapsed_secs());
+            continue;
+        };
+        // Chasing: forget the patrol route, pick a fresh one afterwards
+        patrol.waypoint = None;
+
+        let to_target = aim - eye;
+        let (yaw_error, pitch_error) = bot_aim_error(to_target, yaw.0, pitch.0);
+        bot_turn(&mut action, yaw_error, pitch_error);
+
+        let flat = Vec2::new(to_target.x, to_target.z);
+        let chase = if flat.length() > BOT_KEEP_DISTANCE { flat.normalize_or_zero() } else { Vec2::ZERO };
+        action.set_axis_pair(&PlayerActions::Move, chase);
+
+        // Alternate press / release so every press is a fresh just_pressed;
+        // the weapon cooldown decides the actual rate of fire.
+        let on_target = yaw_error.abs().max(pitch_error.abs()) < BOT_FIRE_CONE;
+        if on_target && !action.pressed(&PlayerActions::Primary) {
+            action.press(&PlayerActions::Primary);
+        } else {
+            action.release(&PlayerActions::Primary);
+        }
+    }
+}
+
+/// Yaw and pitch a bot looking along (yaw, pitch) has to turn to face `dir`.
+fn bot_aim_error(dir: Vec3, yaw: f32, pitch: f32) -> (f32, f32) {
+    let dir = dir.normalize_or_zero();
+    let wanted_yaw = (-dir.x).atan2(-dir.z);
+    let wanted_pitch = dir.y.clamp(-1.0, 1.0).asin();
+    let yaw_error = (wanted_yaw - yaw + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+    (yaw_error, wanted_pitch - pitch)
+}
+
+/// Turns a bot by up to BOT_TURN_RATE toward the given error.
+fn bot_turn(action: &mut ActionState<PlayerActions>, yaw_error: f32, pitch_error: f32) {
+    // shared_look_system applies the negated Look delta
+    let turn = Vec2::new(yaw_error, pitch_error).clamp_length_max(BOT_TURN_RATE);
+    action.set_axis_pair(&PlayerActions::Look, -turn);
+}
+
+/// Walks a bot toward its waypoint at BOT_PATROL_SPEED, looking where it
+/// goes. Waypoints are spawn points (all on open ground inside the map),
+/// preferring ones the bot can see so it doesn't walk into a wall; a bot
+/// that still gets stuck gives up after BOT_WAYPOINT_SECS.
+#[allow(clippy::too_many_arguments)]
+fn patrol_bot(
+    bot: Entity,
+    eye: Vec3,
+    yaw: f32,
+    pitch: f32,
+    action: &mut ActionState<PlayerActions>,
+    patrol: &mut BotPatrol,
+    spatial_query: &SpatialQuery,
+    now: f32,
+) {
+    let reached = patrol
+        .waypoint
+        .is_some_and(|w| Vec2::new(w.x - eye.x, w.z - eye.z).length() < BOT_WAYPOINT_RADIUS);
+    if patrol.waypoint.is_none() || reached || now >= patrol.give_up_at {
+        let filter = SpatialQueryFilter::from_excluded_entities([bot]);
+        let in_sight = |point: &&Vec3| {
+            let Ok((dir, distance)) = Dir3::new_and_length(**point - eye) else {
+                return false;
+            };
+            distance >= BOT_WAYPOINT_RADIUS && spatial_query.cast_ray(eye, dir, distance, true, &filter).is_none()
+        };
+        let visible: Vec<&Vec3> = SPAWN_POINTS.iter().filter(in_sight).collect();
+        let choices: Vec<&Vec3> = if visible.is_empty() { SPAWN_POINTS.iter().collect() } else { visible };
+        patrol.waypoint = Some(*choices[rand::random::<usize>() % choices.len()]);
+        patrol.give_up_at = now + BOT_WAYPOINT_SECS;
+    }
+    let Some(waypoint) = patrol.waypoint else { return; };
+
+    let flat = Vec2::new(waypoint.x - eye.x, waypoint.z - eye.z).normalize_or_zero();
+    action.set_axis_pair(&PlayerActions::Move, flat * BOT_PATROL_SPEED);
+    let (yaw_error, _) = bot_aim_error(Vec3::new(flat.x, 0.0, flat.y), yaw, pitch);
+    bot_turn(action, yaw_error, -pitch);
+}
+
+// ========================================
+// Lifetime stats
+// ========================================
+
+/// Seconds between stats file writes (disconnects also flush immediately).
+const STATS_SAVE_INTERVAL: f32 = 10.0;
+
+/// Per-tick movement above this is a teleport (respawn), not travel.
+const MAX_TRACKED_STEP: f32 = 2.0;
+
+/// Server-only: accumulates sub-meter movement so `PlayerStats` (replicated)
+/// only changes once per whole meter traveled.
+#[derive(Component)]
+struct DistanceTracker {
+    last: Vec3,
+    pending: f32,
+}
+
+/// Server-only: copies each client link's measured round-trip time into its
+/// player's replicated PlayerPing, once a second (bots keep 0).
+fn update_player_ping(
+    mut players: Query<(&ControlledBy, &mut PlayerPing)>,
+    links: Query<&Link>,
+    time: Res<Time>,
+    mut next_update: Local<f32>,
+) {
+    let now = time.elapsed_secs();
+    if now < *next_update {
+        return;
+    }
+    *next_update = now + 1.0;
+    for (controlled_by, mut ping) in players.iter_mut() {
+        let Ok(link) = links.get(controlled_by.owner) else { continue; };
+        let rtt_ms = (link.stats.rtt.as_secs_f64() * 1000.0).round() as u32;
+        if ping.0 != rtt_ms {
+            ping.0 = rtt_ms;
+        }
+    }
+}
+
+/// Server-only: adds position deltas of living players to distance traveled.
+fn track_distance_traveled(
+    mut query: Query<(&Position, &mut DistanceTracker, &mut PlayerStats), Without<PlayerDead>>,
+) {
+    for (pos, mut tracker, mut stats) in query.iter_mut() {
+        let step = (pos.0 - tracker.last).length();
+        tracker.last = pos.0;
+        if step > MAX_TRACKED_STEP {
+            continue;
+        }
+        tracker.pending += step;
+        if tracker.pending >= 1.0 {
+            stats.distance_traveled += tracker.pending;
+            tracker.pending = 0.0;
+        }
+    }
+}
+
+/// Server-only: pays out an ore vein that was mined out — one ore in the
+/// miner's `Resources` and/or a loose chunk at the vein (Ruleset::ore_drop) —
+/// and counts it in their lifetime stats.
+fn count_ore_mined(
+    trigger: On<OreMined>,
+    mut query: Query<(Option<&mut PlayerStats>, &mut Resources)>,
+    ruleset: Res<Ruleset>,
+    mut commands: Commands,
+) {
+    let event = trigger.event();
+    if ruleset.ore_drop.drops_chunk() {
+        commands.spawn(multiplayer::world::ore_chunk_bundle(event.at + Vec3::Y * 0.3, 1));
+    }
+    let Ok((stats, mut resources)) = query.get_mut(event.miner) else { return; };
+    if ruleset.ore_drop.credits_miner() {
+        resources.ore += 1;
+        info!("[MINE] Player {:?} now has {} ore", event.miner, resources.ore);
+    }
+    if let Some(mut stats) = stats {
+        stats.ore_mined += 1;
+    }
+}
+
+/// Server-only: copies changed PlayerStats of verified players into the
+/// StatsStore and writes the stats file at most every STATS_SAVE_INTERVAL
+/// seconds. Unverified players' fresh stats would overwrite the stored ones.
+fn persist_player_stats(
+    query: Query<(&PlayerId, &PlayerStats), Changed<PlayerStats>>,
+    verified_wallets: Res<VerifiedWallets>,
+    mut stats_store: ResMut<StatsStore>,
+    time: Res<Time>,
+    mut last_save: Local<f32>,
+) {
+    for (player_id, stats) in query.iter() {
+        if verified_wallets.is_verified(player_id.0) {
+            stats_store.record(player_id.0, stats);
+        }
+    }
+
+    let now = time.elapsed_secs();
+    if now - *last_save >= STATS_SAVE_INTERVAL {
+        *last_save = now;
+        stats_store.save_if_dirty();
+    }
+}
+
+// ========================================
+// Match autosave
+// ========================================
+
+/// Server-only (PostStartup): restores the last autosave — world item
+/// positions, round and CTF score. Player inventories wait in
+/// `MatchAutosave::pending_players` until each player reconnects and passes
+/// wallet auth, for at most PENDING_RESTORE_SECS.
+fn restore_match_autosave(
+    mut autosave: ResMut<MatchAutosave>,
+    mut items: Query<(&Equippable, &mut Position, Option<&mut WeaponSpawn>)>,
+    mut match_state: Query<&mut MatchState>,
+    mut ctf_score: Query<&mut CtfScore>,
+    time: Res<Time>,
+) {
+    let Some(snapshot) = autosave.load() else { return; };
+    info!(
+        "[AUTOSAVE] Restoring {} item(s) and {} player(s) from {}",
+        snapshot.items.len(),
+        snapshot.players.len(),
+        autosave.path.display()
+    );
+
+    for saved in &snapshot.items {
+        let Some((_, mut pos, spawn)) = items.iter_mut().find(|(e, ..)| e.name == saved.name) else { continue; };
+        pos.0 = saved.position;
+        // A map weapon lying away from its spawn counts as taken, so it
+        // returns home on the usual timer if nobody picks it up
+        if let Some(mut spawn) = spawn {
+            if saved.position != spawn.home {
+                spawn.available = false;
+                spawn.taken_at = Some(time.elapsed_secs());
+            }
+        }
+    }
+    if let (Some(saved), Ok(mut state)) = (snapshot.match_state, match_state.single_mut()) {
+        *state = saved;
+    }
+    if let (Some(saved), Ok(mut score)) = (snapshot.ctf_score, ctf_score.single_mut()) {
+        *score = saved;
+    }
+    autosave.pending_players = snapshot.players;
+    autosave.pending_until = time.elapsed_secs() + autosave::PENDING_RESTORE_SECS;
+}
+
+/// Server-only: writes the match snapshot every `interval_secs`. Players who
+/// haven't reconnected since a restore keep their saved entry until it
+/// expires; only verified players are saved, so an unverified connection
+/// can't overwrite the entry of the id it claims.
+fn autosave_match(
+    mut autosave: ResMut<MatchAutosave>,
+    players: Query<(&PlayerId, &PlayerEquipped, &PlayerInventory, &KillStreak, &Resources), Without<Bot>>,
+    verified_wallets: Res<VerifiedWallets>,
+    items: Query<(&Equippable, &Position)>,
+    match_state: Query<&MatchState>,
+    ctf_score: Query<&CtfScore>,
+    time: Res<Time>,
+    mut last_save: Local<f32>,
+) {
+    let now = time.elapsed_secs();
+    if now - *last_save < autosave.interval_secs {
+        return;
+    }
+    *last_save = now;
+    if now >= autosave.pending_until && !autosave.pending_players.is_empty() {
+        info!("[AUTOSAVE] Dropping {} unclaimed saved player(s)", autosave.pending_players.len());
+        autosave.pending_players.clear();
+    }
+
+    let mut snapshot = MatchSnapshot {
+        match_state: match_state.single().ok().cloned(),
+        ctf_score: ctf_score.single().ok().cloned(),
+        players: autosave.pending_players.clone(),
+        items: items
+            .iter()
+            .map(|(equippable, pos)| ItemSnapshot { name: equippable.name.clone(), position: pos.0 })
+            .collect(),
+    };
+    for (player_id, equipped, inventory, streak, resources) in players.iter() {
+        if !verified_wallets.is_verified(player_id.0) {
+            continue;
+        }
+        snapshot.players.insert(player_id.0, PlayerSnapshot {
+            equipped: equipped.0.clone(),
+            inventory: inventory.items.clone(),
+            kill_streak: streak.0,
+            resources: *resources,
+        });
+    }
+    autosave.save(&snapshot);
+}
+
+// ========================================
+// Entity inspector
+// ========================================
+
+/// Where the inspector camera sits relative to the entity it jumps to.
+const INSPECTOR_CAMERA_OFFSET: Vec3 = Vec3::new(0.0, 4.0, 6.0);
+
+/// Marker for the --inspector debug camera.
+#[derive(Component)]
+struct InspectorCamera;
+
+/// --inspector: open a window with a live entity inspector (debugging only).
+fn inspector_mode() -> bool {
+    std::env::args().any(|a| a == "--inspector")
+}
+
+/// Inspector only: debug camera, starting with an overview of the map.
+fn spawn_inspector_camera(mut commands: Commands) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 30.0, 30.0).looking_at(Vec3::ZERO, Vec3::Y),
+        InspectorCamera,
+    ));
+}
+
+/// Inspector only: live list of players and world items with their position,
+/// health and owner. Clicking a row moves the debug camera to that entity.
+/// Rounds are hitscan, so there are no projectile entities to list.
+fn entity_inspector_panel(
+    mut contexts: EguiContexts,
+    players: Query<(Entity, &PlayerId, &Position, &PlayerHealth, Option<&ControlledBy>, Option<&Team>, Has<Bot>, Has<PlayerDead>)>,
+    items: Query<(Entity, &Equippable, &Position)>,
+    carriers: Query<(&PlayerId, &PlayerEquipped, &PlayerInventory)>,
+    mut camera: Query<&mut Transform, With<InspectorCamera>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else { return; };
+
+    let mut rows: Vec<_> = players.iter().collect();
+    rows.sort_by_key(|(_, id, ..)| id.0);
+
+    let mut jump_to = None;
+    egui::Window::new("Entities")
+        .default_width(460.0)
+        .anchor(egui::Align2::LEFT_TOP, egui::vec2(16.0, 16.0))
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.heading(format!("Players ({})", rows.len()));
+                for (entity, id, pos, health, controlled_by, team, is_bot, is_dead) in &rows {
+                    let owner = match controlled_by {
+                        Some(controlled) => format!("{:?}", controlled.owner),
+                        None if *is_bot => "bot".to_string(),
+                        None => "server".to_string(),
+                    };
+                    let label = format!(
+                        "{:?}  #{}  hp {}{}  team {}  owner {}  ({:.1}, {:.1}, {:.1})",
+                        entity,
+                        id.0,
+                        health.0,
+                        if *is_dead { " (dead)" } else { "" },
+                        team.map(|t| t.name()).unwrap_or("-"),
+                        owner,
+                        pos.x, pos.y, pos.z,
+                    );
+                    if ui.selectable_label(false, label).clicked() {
+                        jump_to = Some(pos.0);
+                    }
+                }
+
+                ui.separator();
+                ui.heading(format!("Items ({})", items.iter().count()));
+                for (entity, equippable, pos) in items.iter() {
+                    // Held items stay in the world (hidden), so look the owner up by name
+                    let owner = carriers
+                        .iter()
+                        .find(|(_, equipped, inventory)| {
+                            equipped.0.as_deref() == Some(equippable.name.as_str())
+                                || inventory.items.contains(&equippable.name)
+                        })
+                        .map(|(id, ..)| format!("player #{}", id.0))
+                        .unwrap_or_else(|| "world".to_string());
+                    let label = format!(
+                        "{:?}  {}  owner {}  ({:.1}, {:.1}, {:.1})",
+                        entity, equippable.name, owner, pos.x, pos.y, pos.z,
+                    );
+                    if ui.selectable_label(false, label).clicked() {
+                        jump_to = Some(pos.0);
+                    }
+                }
+            });
+        });
+
+    if let Some(target) = jump_to {
+        for mut transform in camera.iter_mut() {
+            *transform = Transform::from_translation(target + INSPECTOR_CAMERA_OFFSET)
+                .looking_at(target, Vec3::Y);
+        }
+    }
+}
+
+// ========================================
+// Deterministic stepping
+// ========================================
+
+/// Ticks between state checksum log lines in deterministic mode.
+const CHECKSUM_INTERVAL_TICKS: u32 = 64;
+
+/// --deterministic: step the simulation with manual fixed-duration time.
+fn deterministic_mode() -> bool {
+    std::env::args().any(|a| a == "--deterministic")
+}
+
+/// Deterministic mode only: logs a checksum of every player's physics state so
+/// two runs fed the same inputs can be diffed tick-for-tick.
+/// Players are hashed in PlayerId order; floats are hashed by bit pattern.
+fn log_state_checksum(
+    query: Query<(&PlayerId, &Position, &multiplayer::protocol::CharacterVelocity, &PlayerYaw, &PlayerPitch)>,
+    mut tick: Local<u32>,
+) {
+    use std::hash::{Hash, Hasher};
+
+    *tick += 1;
+    if *tick % CHECKSUM_INTERVAL_TICKS != 0 {
+        return;
+    }
+
+    let mut players: Vec<_> = query.iter().collect();
+    players.sort_by_key(|(id, ..)| id.0);
+
+    let mut hasher = std::hash::DefaultHasher::new();
+    for (id, pos, vel, yaw, pitch) in &players {
+        id.0.hash(&mut hasher);
+        for v in pos.0.to_array().into_iter().chain(vel.0.to_array()).chain([yaw.0, pitch.0]) {
+            v.to_bits().hash(&mut hasher);
+        }
+    }
+    info!(
+        "[DETERMINISM] tick={} players={} checksum={:016x}",
+        *tick, players.len(), hasher.finish()
+    );
+}
+
+// ========================================
+// Input recording & replay
+// ========================================
+
+/// Seconds between flushes of the input recording to disk.
+const RECORDING_FLUSH_SECS: f32 = 10.0;
+
+/// --record-inputs: the recording file, appended to every tick.
+#[derive(Resource)]
+struct InputRecorder {
+    writer: RecordingWriter,
+}
+
+/// --replay-inputs: the recording being played back and the next tick to apply.
+#[derive(Resource)]
+struct InputReplay {
+    path: std::path::PathBuf,
+    recording: InputRecording,
+    tick: usize,
+}
+
+/// Records every player's (and bot's) input for this tick, appending it to
+/// the recording file, which is flushed every RECORDING_FLUSH_SECS. Runs last
+/// in the tick, so a player first seen here is recorded with the state this
+/// tick left them in.
+fn record_inputs(
+    mut recorder: ResMut<InputRecorder>,
+    players: Query<(&PlayerId, &ActionState<PlayerActions>, &Position, &PlayerYaw, &PlayerPitch)>,
+    time: Res<Time>,
+    mut last_flush: Local<f32>,
+) {
+    let mut inputs = Vec::new();
+    for (id, action, pos, yaw, pitch) in players.iter() {
+        if let Err(e) = recorder.writer.see_player(id.0, pos.0, yaw.0, pitch.0) {
+            warn!("[REPLAY] {}", e);
+        }
+        inputs.push(RecordedInput::capture(id.0, action));
+    }
+    inputs.sort_by_key(|input| input.player);
+    if let Err(e) = recorder.writer.push_tick(inputs) {
+        warn!("[REPLAY] {}", e);
+    }
+
+    let now = time.elapsed_secs();
+    if now - *last_flush >= RECORDING_FLUSH_SECS {
+        *last_flush = now;
+        if let Err(e) = recorder.writer.flush() {
+            warn!("[REPLAY] {}", e);
+        }
+    }
+}
+
+/// Flushes the input recording when the server shuts down (Ctrl-C included),
+/// so the last seconds of the session — usually the part being debugged —
+/// make it to disk.
+fn flush_recording_on_exit(mut exit: MessageReader<AppExit>, mut recorder: ResMut<InputRecorder>) {
+    if exit.read().count() == 0 {
+        return;
+    }
+    match recorder.writer.flush() {
+        Ok(()) => info!("[REPLAY] Recording flushed on exit"),
+        Err(e) => warn!("[REPLAY] {}", e),
+    }
+}
+
+/// Plays a recording back on a headless server: spawns each recorded player
+/// the tick after they were first seen and puts their recorded input on
+/// their ActionState every tick, ahead of the simulation. After the last
+/// tick the players' final state is compared byte for byte with the first
+/// replay of the same recording (stored next to it) and the server exits —
+/// with an error if the two runs diverged.
+fn replay_inputs(
+    mut replay: ResMut<InputReplay>,
+    mut players: Query<(&PlayerId, &mut ActionState<PlayerActions>)>,
+    state: Query<(&PlayerId, &Position, &CharacterVelocity, &PlayerYaw, &PlayerPitch)>,
+    mut commands: Commands,
+    mut exit: MessageWriter<AppExit>,
+) {
+    let tick = replay.tick;
+    replay.tick += 1;
+    let Some(inputs) = replay.recording.ticks.get(tick) else {
+        if tick == replay.recording.ticks.len() {
+            finish_replay(&replay.path, tick, &state, &mut exit);
+        }
+        return;
+    };
+
+    for joining in replay.recording.players.iter().filter(|p| p.first_tick as usize + 1 == tick) {
+        let mut action = ActionState::<PlayerActions>::default();
+        if let Some(input) = inputs.iter().find(|input| input.player == joining.player) {
+            input.apply_to(&mut action);
+        }
+        commands
+            .spawn((player_replicated_bundle(joining.player), player_physics_bundle(), Name::new(format!("Replay {}", joining.player))))
+            .insert((Position(joining.position), PlayerYaw(joining.yaw), PlayerPitch(joining.pitch), action));
+    }
+    for (id, mut action) in players.iter_mut() {
+        if let Some(input) = inputs.iter().find(|input| input.player == id.0) {
+            input.apply_to(&mut action);
+        }
+    }
+}
+
+fn finish_replay(
+    path: &std::path::Path,
+    ticks: usize,
+    state: &Query<(&PlayerId, &Position, &CharacterVelocity, &PlayerYaw, &PlayerPitch)>,
+    exit: &mut MessageWriter<AppExit>,
+) {
+    let bytes = replay::state_bytes(state.iter().map(|(id, pos, vel, yaw, pitch)| (id.0, pos.0, vel.0, yaw.0, pitch.0)));
+    match replay::check_determinism(path, &bytes) {
+        Ok(DeterminismCheck::Recorded) => {
+            info!(
+                "[REPLAY] Replayed {} ticks; final state ({} bytes) saved to {} — replay again to compare",
+                ticks,
+                bytes.len(),
+                replay::state_path(path).display()
+            );
+            exit.write(AppExit::Success);
+        }
+        Ok(DeterminismCheck::Identical) => {
+            info!("[REPLAY] Replayed {} ticks; final state is byte-identical to the first replay", ticks);
+            exit.write(AppExit::Success);
+        }
+        Ok(DeterminismCheck::Diverged { at }) => {
+            error!("[REPLAY] Replayed {} ticks; final state differs from the first replay at byte {}", ticks, at);
+            exit.write(AppExit::error());
+        }
+        Err(e) => {
+            error!("[REPLAY] {}", e);
+            exit.write(AppExit::error());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avian3d::prelude::{Collider, PhysicsPlugins, RigidBody};
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_frag_in_the_open_sees_player_in_range() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            bevy::mesh::MeshPlugin,
+            PhysicsPlugins::new(PostUpdate),
+        ));
+
+        // A live grenade in the open and a player a couple of meters away
+        let center = Vec3::new(0.0, 1.0, 0.0);
+        let grenade = app
+            .world_mut()
+            .spawn((Position(center), RigidBody::Dynamic, Collider::sphere(utility::UTILITY_RADIUS)))
+            .id();
+        let player_pos = Vec3::new(3.0, 1.0, 0.0);
+        let player = app.world_mut().spawn((Position(player_pos), player_physics_bundle())).id();
+        assert!(player_pos.distance(center) < UtilityKind::Frag.radius());
+
+        // Step once so both colliders are in the spatial query pipeline
+        app.update();
+
+        let seen = app
+            .world_mut()
+            .run_system_once(move |spatial_query: SpatialQuery| {
+                utility_line_of_sight(&spatial_query, center, player_pos, player, grenade)
+            })
+            .unwrap();
+        assert!(seen, "the grenade's own collider must not block its blast");
+    }
+}
@@ -0,0 +1,235 @@
+//! Highlight clips — the client keeps the last few seconds of what it saw
+//! (every player's position, view and life state, plus its own input in the
+//! replay recording's format) and writes them to a clip file on CLIP_KEY, or
+//! by itself after a multi-kill with `--auto-clip`.
+//!
+//! Clips are JSON under `~/.anima/clips/` (localStorage on the web), named
+//! `clip-<n>.json` with the first free n. `--play-clip <file>` plays one
+//! back in game as ghost players looping over the map.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::replay::RecordedInput;
+
+/// Snapshots per second kept in the buffer.
+pub const CLIP_SAMPLE_HZ: f32 = 20.0;
+/// Seconds of history a clip covers, unless `--clip-secs` says otherwise.
+pub const DEFAULT_CLIP_SECS: f32 = 15.0;
+/// Longest clip a player can ask for (the buffer lives in memory).
+pub const MAX_CLIP_SECS: f32 = 120.0;
+/// Kills this close together count as a multi-kill for `--auto-clip`.
+pub const MULTI_KILL_WINDOW: f32 = 4.0;
+pub const MULTI_KILL_COUNT: usize = 2;
+/// Key that saves the buffer as a clip.
+pub const CLIP_KEY: KeyCode = KeyCode::F8;
+
+/// One player in a clip frame.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClipPlayer {
+    pub id: u64,
+    pub position: [f32; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    pub dead: bool,
+}
+
+/// Everyone's state at `t` seconds into the clip, and the recorder's own
+/// input at that moment.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClipFrame {
+    pub t: f32,
+    pub players: Vec<ClipPlayer>,
+    #[serde(default)]
+    pub input: Option<RecordedInput>,
+}
+
+/// A saved clip. `recorded_by` is the PlayerId of whoever saved it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Clip {
+    pub recorded_by: u64,
+    pub frames: Vec<ClipFrame>,
+}
+
+impl Clip {
+    /// Length in seconds.
+    pub fn duration(&self) -> f32 {
+        self.frames.last().map_or(0.0, |f| f.t)
+    }
+
+    /// The two frames around `t` and how far between them `t` is.
+    pub fn frames_at(&self, t: f32) -> Option<(&ClipFrame, &ClipFrame, f32)> {
+        let next = self.frames.iter().position(|f| f.t >= t)?;
+        let prev = next.saturating_sub(1);
+        let (a, b) = (&self.frames[prev], &self.frames[next]);
+        let span = b.t - a.t;
+        let blend = if span > 0.0 { ((t - a.t) / span).clamp(0.0, 1.0) } else { 0.0 };
+        Some((a, b, blend))
+    }
+}
+
+/// Client-only clip options (`--clip-secs <secs>`, `--auto-clip`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClipSettings {
+    pub secs: f32,
+    /// Save a clip by itself after a multi-kill.
+    pub auto_multi_kill: bool,
+}
+
+impl Default for ClipSettings {
+    fn default() -> Self {
+        Self { secs: DEFAULT_CLIP_SECS, auto_multi_kill: false }
+    }
+}
+
+/// Parse --clip-secs <secs> and --auto-clip from CLI args.
+pub fn parse_clip_settings() -> ClipSettings {
+    let args: Vec<String> = std::env::args().collect();
+    let mut settings = ClipSettings::default();
+    if let Some(secs) = args
+        .iter()
+        .position(|a| a == "--clip-secs")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|s| *s > 0.0)
+    {
+        settings.secs = secs.min(MAX_CLIP_SECS);
+    }
+    settings.auto_multi_kill = args.iter().any(|a| a == "--auto-clip");
+    settings
+}
+
+/// Client-only rolling buffer of the last `settings.secs` of frames.
+/// Frame times are elapsed secs until a clip is cut, then rebased to 0.
+#[derive(Resource, Default)]
+pub struct ClipRecorder {
+    pub settings: ClipSettings,
+    frames: VecDeque<ClipFrame>,
+    /// Elapsed secs of our recent kills, for multi-kill detection.
+    pub kill_times: Vec<f32>,
+    /// Storage key and time of the last saved clip, for the HUD.
+    pub last_saved: Option<(String, f32)>,
+    /// Lowest clip number that may still be free.
+    next_clip: u32,
+}
+
+impl ClipRecorder {
+    pub fn new(settings: ClipSettings) -> Self {
+        Self { settings, ..default() }
+    }
+
+    /// Whether a frame is due at `now`.
+    pub fn wants_sample(&self, now: f32) -> bool {
+        self.frames.back().is_none_or(|f| now - f.t >= 1.0 / CLIP_SAMPLE_HZ)
+    }
+
+    /// Append a frame and drop those older than the clip length.
+    pub fn push(&mut self, frame: ClipFrame) {
+        let cutoff = frame.t - self.settings.secs;
+        self.frames.push_back(frame);
+        while self.frames.front().is_some_and(|f| f.t < cutoff) {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Note a kill at `now`. True when it completes a multi-kill.
+    pub fn note_kill(&mut self, now: f32) -> bool {
+        self.kill_times.retain(|t| now - *t <= MULTI_KILL_WINDOW);
+        self.kill_times.push(now);
+        self.kill_times.len() >= MULTI_KILL_COUNT
+    }
+
+    /// The buffer as a clip starting at t = 0. None while it's empty.
+    pub fn clip(&self, recorded_by: u64) -> Option<Clip> {
+        let start = self.frames.front()?.t;
+        let frames = self
+            .frames
+            .iter()
+            .map(|f| ClipFrame { t: f.t - start, ..f.clone() })
+            .collect();
+        Some(Clip { recorded_by, frames })
+    }
+
+    /// Write the buffer to `clips/clip-<n>.json` with the first n not taken
+    /// by an earlier clip, returning the key.
+    pub fn save(&mut self, recorded_by: u64, now: f32) -> Result<String, String> {
+        let clip = self.clip(recorded_by).ok_or("nothing recorded yet")?;
+        let json = serde_json::to_string(&clip).map_err(|e| e.to_string())?;
+        while crate::storage::read(&clip_key(self.next_clip)).is_some() {
+            self.next_clip += 1;
+        }
+        let key = clip_key(self.next_clip);
+        self.next_clip += 1;
+        crate::storage::write(&key, &json)?;
+        info!("[CLIP] Saved {:.1}s clip to {}", clip.duration(), crate::storage::location(&key));
+        self.last_saved = Some((key.clone(), now));
+        Ok(key)
+    }
+}
+
+/// Storage key of clip number `n`.
+fn clip_key(n: u32) -> String {
+    format!("clips/clip-{}.json", n)
+}
+
+/// Load the clip named by --play-clip <file> (a path, or a key under
+/// `~/.anima/`).
+pub fn parse_play_clip() -> Option<Clip> {
+    let args: Vec<String> = std::env::args().collect();
+    let file = args
+        .iter()
+        .position(|a| a == "--play-clip")
+        .and_then(|pos| args.get(pos + 1))?;
+    let json = std::fs::read_to_string(file).ok().or_else(|| crate::storage::read(file));
+    match json.map(|j| serde_json::from_str::<Clip>(&j)) {
+        Some(Ok(clip)) => {
+            info!("[CLIP] Playing {} ({:.1}s, {} frames)", file, clip.duration(), clip.frames.len());
+            Some(clip)
+        }
+        Some(Err(e)) => {
+            warn!("[CLIP] {} is not a clip: {}", file, e);
+            None
+        }
+        None => {
+            warn!("[CLIP] Could not read {}", file);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(t: f32) -> ClipFrame {
+        ClipFrame { t, players: Vec::new(), input: None }
+    }
+
+    #[test]
+    fn test_push_keeps_clip_length() {
+        let mut recorder = ClipRecorder::new(ClipSettings { secs: 2.0, auto_multi_kill: false });
+        for i in 0..=10 {
+            recorder.push(frame(i as f32 * 0.5));
+        }
+        // Frames from t = 3.0 to 5.0 survive, rebased to start at 0
+        let clip = recorder.clip(1).unwrap();
+        assert_eq!(clip.frames.len(), 5);
+        assert_eq!(clip.frames[0].t, 0.0);
+        assert_eq!(clip.duration(), 2.0);
+    }
+
+    #[test]
+    fn test_frames_at_blends_between_neighbours() {
+        let clip = Clip { recorded_by: 1, frames: vec![frame(0.0), frame(1.0), frame(2.0)] };
+        let (a, b, blend) = clip.frames_at(1.25).unwrap();
+        assert_eq!((a.t, b.t), (1.0, 2.0));
+        assert!((blend - 0.25).abs() < 1e-6);
+
+        // Start lands on the first frame; past the end there is nothing
+        let (a, b, blend) = clip.frames_at(0.0).unwrap();
+        assert_eq!((a.t, b.t, blend), (0.0, 0.0, 0.0));
+        assert!(clip.frames_at(2.5).is_none());
+    }
+}
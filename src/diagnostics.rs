@@ -0,0 +1,315 @@
+//! Network diagnostics export — appends periodic per-link metrics to a CSV
+//! file for offline analysis of a session. Used by both client and server.
+//!
+//! Enabled with `--net-diagnostics <file.csv>`; one row per connected link
+//! every `--net-diagnostics-interval <secs>` (default 1s). Loss is measured
+//! with small unreliable probes the other side echoes back; bytes/sec by
+//! weighing each link's packets as they pass the IO layer.
+//!
+//! Also holds the entity-count watchdog (`EntityCountWarning`), which flags
+//! runaway spawning before it shows up only as a frame-rate drop.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use lightyear::prelude::*;
+
+use crate::protocol::{NetProbe, NetProbeChannel, NetProbeEcho};
+
+/// Default seconds between samples.
+const DEFAULT_INTERVAL_SECS: f32 = 1.0;
+
+/// Default entity count above which `EntityCountWarning` trips. A normal
+/// match (map, players, items, tracers) stays well below this.
+const DEFAULT_ENTITY_WARNING: usize = 5000;
+
+/// Seconds between loss probes on each link while exporting.
+const PROBE_INTERVAL_SECS: f32 = 0.1;
+/// A probe whose echo hasn't come back after this long counts as lost.
+const PROBE_TIMEOUT_SECS: f32 = 1.0;
+
+const CSV_HEADER: &str = "time_secs,link,rtt_ms,jitter_ms,loss_pct,bytes_in_per_sec,bytes_out_per_sec,entities";
+
+/// Where and how often to write network metrics. Absent when export is off.
+#[derive(Resource, Clone, Debug)]
+pub struct NetDiagnosticsExport {
+    pub path: PathBuf,
+    pub interval_secs: f32,
+}
+
+/// Parse --net-diagnostics <path> and --net-diagnostics-interval <secs> from
+/// CLI args. None when export is not requested.
+pub fn parse_net_diagnostics() -> Option<NetDiagnosticsExport> {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+    };
+    let path = PathBuf::from(value("--net-diagnostics")?);
+    let interval_secs = value("--net-diagnostics-interval")
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|v| *v > 0.0)
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    info!("[NETDIAG] Exporting network metrics to {} every {}s", path.display(), interval_secs);
+    Some(NetDiagnosticsExport { path, interval_secs })
+}
+
+/// Turn on the CSV export on the client or server.
+pub fn add_net_diagnostics_export(app: &mut App, export: NetDiagnosticsExport) {
+    app.insert_resource(export);
+    app.init_resource::<LinkTraffic>();
+    // Weigh packets between the IO layer and lightyear's transport, in both directions
+    app.add_systems(PreUpdate, count_bytes_in.after(LinkSystems::Receive).before(TransportSystems::Receive));
+    app.add_systems(PostUpdate, count_bytes_out.after(TransportSystems::Send).before(LinkSystems::Send));
+    app.add_systems(Update, (send_net_probes, export_net_diagnostics).chain());
+}
+
+/// What one link did since the last CSV row.
+#[derive(Default, Debug)]
+struct LinkCounters {
+    next_probe: u32,
+    /// Probes sent and not settled yet, with the time they were sent.
+    in_flight: VecDeque<(u32, f32)>,
+    /// In-flight probes whose echo came back.
+    echoed: Vec<u32>,
+    probes_settled: u32,
+    probes_lost: u32,
+    bytes_in: u64,
+    bytes_out: u64,
+}
+
+impl LinkCounters {
+    /// Settle probes sent at least PROBE_TIMEOUT_SECS before `now`: each
+    /// one was either echoed or lost.
+    fn settle(&mut self, now: f32) {
+        while let Some(&(seq, sent_at)) = self.in_flight.front() {
+            if now - sent_at < PROBE_TIMEOUT_SECS {
+                break;
+            }
+            self.in_flight.pop_front();
+            self.probes_settled += 1;
+            match self.echoed.iter().position(|s| *s == seq) {
+                Some(index) => {
+                    self.echoed.swap_remove(index);
+                }
+                None => self.probes_lost += 1,
+            }
+        }
+    }
+
+    /// Percentage of the probes settled since the last row that were lost.
+    fn loss_pct(&self) -> f32 {
+        if self.probes_settled == 0 {
+            return 0.0;
+        }
+        self.probes_lost as f32 / self.probes_settled as f32 * 100.0
+    }
+}
+
+/// Per-link counters behind the loss and bytes/sec columns.
+#[derive(Resource, Default)]
+pub struct LinkTraffic(HashMap<Entity, LinkCounters>);
+
+/// Answers the other side's loss probes. Runs whether or not this side
+/// exports diagnostics itself.
+pub fn echo_net_probes(mut links: Query<(&mut MessageReceiver<NetProbe>, &mut MessageSender<NetProbeEcho>)>) {
+    for (mut receiver, mut sender) in links.iter_mut() {
+        for NetProbe(seq) in receiver.receive() {
+            sender.send::<NetProbeChannel>(NetProbeEcho(seq));
+        }
+    }
+}
+
+/// Sends a loss probe on every connected link each PROBE_INTERVAL_SECS and
+/// collects the echoes.
+fn send_net_probes(
+    mut traffic: ResMut<LinkTraffic>,
+    mut links: Query<(Entity, &mut MessageSender<NetProbe>, &mut MessageReceiver<NetProbeEcho>), With<Connected>>,
+    time: Res<Time>,
+    mut next_probe: Local<f32>,
+) {
+    let now = time.elapsed_secs();
+    let probe_due = now >= *next_probe;
+    if probe_due {
+        *next_probe = now + PROBE_INTERVAL_SECS;
+    }
+    for (entity, mut sender, mut receiver) in links.iter_mut() {
+        let counters = traffic.0.entry(entity).or_default();
+        for NetProbeEcho(seq) in receiver.receive() {
+            if counters.in_flight.iter().any(|(s, _)| *s == seq) {
+                counters.echoed.push(seq);
+            }
+        }
+        if probe_due {
+            let seq = counters.next_probe;
+            counters.next_probe = seq.wrapping_add(1);
+            counters.in_flight.push_back((seq, now));
+            sender.send::<NetProbeChannel>(NetProbe(seq));
+        }
+    }
+}
+
+/// Weighs the packets the IO layer just received, before the transport
+/// takes them.
+fn count_bytes_in(mut traffic: ResMut<LinkTraffic>, mut links: Query<(Entity, &mut Link)>) {
+    for (entity, mut link) in links.iter_mut() {
+        let packets: Vec<_> = link.recv.drain().collect();
+        traffic.0.entry(entity).or_default().bytes_in += packets.iter().map(|p| p.len() as u64).sum::<u64>();
+        for packet in packets {
+            link.recv.push_raw(packet);
+        }
+    }
+}
+
+/// Weighs the packets the transport just queued, before the IO layer sends them.
+fn count_bytes_out(mut traffic: ResMut<LinkTraffic>, mut links: Query<(Entity, &mut Link)>) {
+    for (entity, mut link) in links.iter_mut() {
+        let packets: Vec<_> = link.send.drain().collect();
+        traffic.0.entry(entity).or_default().bytes_out += packets.iter().map(|p| p.len() as u64).sum::<u64>();
+        for packet in packets {
+            link.send.push(packet);
+        }
+    }
+}
+
+/// Appends one CSV row per link (client: the server connection; server: each
+/// client) every `interval_secs`. Writes the header when the file is new.
+fn export_net_diagnostics(
+    export: Res<NetDiagnosticsExport>,
+    mut traffic: ResMut<LinkTraffic>,
+    links: Query<(Entity, &Link)>,
+    entities: Query<()>,
+    time: Res<Time>,
+    mut last_sample: Local<Option<f32>>,
+    mut failed: Local<bool>,
+) {
+    let now = time.elapsed_secs();
+    let Some(last) = *last_sample else {
+        // Start counting from the first run
+        *last_sample = Some(now);
+        return;
+    };
+    if now - last < export.interval_secs || *failed {
+        return;
+    }
+    *last_sample = Some(now);
+    let elapsed = (now - last) as f64;
+
+    traffic.0.retain(|entity, _| links.contains(*entity));
+    let entity_count = entities.iter().count();
+    let mut rows = String::new();
+    for (entity, link) in links.iter() {
+        let counters = traffic.0.entry(entity).or_default();
+        counters.settle(now);
+        rows.push_str(&format!(
+            "{:.3},{},{:.1},{:.1},{:.1},{:.0},{:.0},{}\n",
+            now,
+            entity,
+            link.stats.rtt.as_secs_f64() * 1000.0,
+            link.stats.jitter.as_secs_f64() * 1000.0,
+            counters.loss_pct(),
+            counters.bytes_in as f64 / elapsed,
+            counters.bytes_out as f64 / elapsed,
+            entity_count,
+        ));
+        counters.probes_settled = 0;
+        counters.probes_lost = 0;
+        counters.bytes_in = 0;
+        counters.bytes_out = 0;
+    }
+    if rows.is_empty() {
+        return;
+    }
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&export.path)
+        .and_then(|mut file| {
+            if file.metadata()?.len() == 0 {
+                writeln!(file, "{}", CSV_HEADER)?;
+            }
+            file.write_all(rows.as_bytes())
+        });
+    if let Err(e) = result {
+        // Don't retry every interval — one warning is enough
+        warn!("[NETDIAG] Failed to write {}: {} — export disabled", export.path.display(), e);
+        *failed = true;
+    }
+}
+
+/// Entity-count watchdog: `count` is refreshed by `check_entity_count`, and
+/// `tripped` is set while it's above `threshold` (0 disables the check).
+#[derive(Resource, Clone, Debug)]
+pub struct EntityCountWarning {
+    pub threshold: usize,
+    pub count: usize,
+    pub tripped: bool,
+}
+
+/// Parse --entity-warning <count> from CLI args (0 turns the warning off).
+pub fn parse_entity_warning() -> EntityCountWarning {
+    let args: Vec<String> = std::env::args().collect();
+    let threshold = args
+        .iter()
+        .position(|a| a == "--entity-warning")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_ENTITY_WARNING);
+    EntityCountWarning {
+        threshold,
+        count: 0,
+        tripped: false,
+    }
+}
+
+/// Counts entities once per second and logs when the count crosses the
+/// warning threshold (either way), so the log shows when a flood started.
+pub fn check_entity_count(
+    mut warning: ResMut<EntityCountWarning>,
+    entities: Query<()>,
+    time: Res<Time>,
+    mut next_sample: Local<f32>,
+) {
+    let now = time.elapsed_secs();
+    if warning.threshold == 0 || now < *next_sample {
+        return;
+    }
+    *next_sample = now + 1.0;
+
+    warning.count = entities.iter().count();
+    let over = warning.count > warning.threshold;
+    if over && !warning.tripped {
+        warn!("[ENTITIES] {} entities exceeds the warning threshold of {}", warning.count, warning.threshold);
+    } else if !over && warning.tripped {
+        info!("[ENTITIES] Entity count back to {}", warning.count);
+    }
+    warning.tripped = over;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unechoed_probes_count_as_lost_after_timeout() {
+        let mut counters = LinkCounters::default();
+        for seq in 0..4 {
+            counters.in_flight.push_back((seq, seq as f32 * PROBE_INTERVAL_SECS));
+        }
+        counters.echoed = vec![0, 2, 3];
+
+        // Nothing is old enough to judge yet
+        counters.settle(0.5);
+        assert_eq!(counters.probes_settled, 0);
+
+        counters.settle(PROBE_TIMEOUT_SECS + 1.0);
+        assert_eq!((counters.probes_settled, counters.probes_lost), (4, 1));
+        assert_eq!(counters.loss_pct(), 25.0);
+        assert!(counters.echoed.is_empty());
+    }
+}
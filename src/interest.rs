@@ -0,0 +1,77 @@
+//! Area-of-interest culling — with `--aoi-radius <meters>` each client is
+//! only sent the players and loose items near its own player, instead of
+//! every entity in the match.
+//!
+//! The server drives lightyear's per-client `NetworkVisibility` from this:
+//! an entity entering a client's radius is spawned on that client, one
+//! leaving it is despawned there (lightyear sends the despawn). Culled
+//! players also drop off that client's scoreboard until they come back
+//! into range, so this is meant for large servers.
+//!
+//! `--physics-cull-radius <meters>` is the simulation-side counterpart: loose
+//! dynamic bodies (ore chunks, thrown utilities) further than that from every
+//! living player are taken out of the physics step until someone comes near.
+
+use bevy::prelude::*;
+
+/// An entity stays visible until it is this much further out than the
+/// radius, so something standing on the edge doesn't spawn and despawn
+/// every tick.
+pub const AOI_HYSTERESIS: f32 = 1.15;
+
+/// Server-only: relevancy radius. Absent when culling is off (the default).
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct AoiConfig {
+    pub radius: f32,
+}
+
+impl AoiConfig {
+    /// Whether a viewer at `viewer` should see something at `target`, given
+    /// whether it sees it now.
+    pub fn relevant(&self, viewer: Vec3, target: Vec3, visible: bool) -> bool {
+        let reach = if visible { self.radius * AOI_HYSTERESIS } else { self.radius };
+        viewer.distance_squared(target) <= reach * reach
+    }
+}
+
+/// Parse --aoi-radius <meters> from CLI args.
+pub fn parse_aoi_config() -> Option<AoiConfig> {
+    let args: Vec<String> = std::env::args().collect();
+    let radius = args
+        .iter()
+        .position(|a| a == "--aoi-radius")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|r| *r > 0.0)?;
+    info!("[AOI] Clients only receive players and items within {}m", radius);
+    Some(AoiConfig { radius })
+}
+
+/// Server-only: physics culling radius. Absent when culling is off (the default).
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct PhysicsCullConfig {
+    pub radius: f32,
+}
+
+impl PhysicsCullConfig {
+    /// Whether a body at `target` should be simulated, given the living
+    /// players' positions and whether it is simulated now. Uses the same
+    /// hysteresis as AoiConfig::relevant.
+    pub fn simulate(&self, players: impl IntoIterator<Item = Vec3>, target: Vec3, simulated: bool) -> bool {
+        let reach = if simulated { self.radius * AOI_HYSTERESIS } else { self.radius };
+        players.into_iter().any(|p| p.distance_squared(target) <= reach * reach)
+    }
+}
+
+/// Parse --physics-cull-radius <meters> from CLI args.
+pub fn parse_physics_cull_config() -> Option<PhysicsCullConfig> {
+    let args: Vec<String> = std::env::args().collect();
+    let radius = args
+        .iter()
+        .position(|a| a == "--physics-cull-radius")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|r| *r > 0.0)?;
+    info!("[AOI] Loose bodies further than {}m from every player stop simulating", radius);
+    Some(PhysicsCullConfig { radius })
+}
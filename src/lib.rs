@@ -4,17 +4,74 @@ use lightyear::avian3d::plugin::AvianReplicationMode;
 use lightyear::avian3d::prelude::*;
 
 pub mod auth;
+pub mod autosave;
+pub mod clips;
+pub mod diagnostics;
+pub mod interest;
+pub mod logging;
+pub mod observer;
 pub mod player;
 pub mod protocol;
+pub mod replay;
+pub mod rules;
+pub mod settings;
 pub mod solana;
+pub mod stats;
+pub mod storage;
+pub mod trade;
+pub mod transport;
+pub mod utility;
+pub mod weapons;
 pub mod world;
 
 pub const PROTOCOL_ID: u64 = 7;
 pub const SERVER_PORT: u16 = 5000;
 pub const FIXED_TIMESTEP_HZ: f64 = 64.0;
 
+/// Default cap on simulated time per frame (seconds).
+pub const DEFAULT_MAX_FRAME_DELTA: f32 = 0.1;
+
+/// Upper bound on how much simulated time a single frame may advance.
+///
+/// Applied to `Time<Virtual>` so a stall (alt-tab, debugger, slow disk) is
+/// dropped instead of replayed as a burst of fixed ticks. That is the only
+/// place a variable delta occurs: FixedUpdate systems always see the
+/// constant fixed timestep.
+/// Override with `--max-frame-delta <seconds>`.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct MaxFrameDelta(pub f32);
+
+impl Default for MaxFrameDelta {
+    fn default() -> Self {
+        Self(DEFAULT_MAX_FRAME_DELTA)
+    }
+}
+
+/// Parse --max-frame-delta <seconds> from CLI args. Non-positive values are ignored.
+pub fn parse_max_frame_delta() -> MaxFrameDelta {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|a| a == "--max-frame-delta")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|v| *v > 0.0)
+        .map(MaxFrameDelta)
+        .unwrap_or_default()
+}
+
+/// Startup: applies MaxFrameDelta to virtual time.
+fn apply_max_frame_delta(limit: Res<MaxFrameDelta>, mut time: ResMut<Time<Virtual>>) {
+    time.set_max_delta(std::time::Duration::from_secs_f32(limit.0));
+    info!("[TIME] Max frame delta: {:.3}s", limit.0);
+}
+
 /// Shared plugin added by both client and server:
 /// registers protocol, physics, frame interpolation, and shared movement.
+///
+/// To change channel delivery, insert a `protocol::NetworkTuning` resource
+/// *before* adding this plugin — the channels are registered while it builds,
+/// so a tuning inserted afterwards is ignored. Client and server must insert
+/// the same one.
 pub struct SharedPlugin;
 
 impl Plugin for SharedPlugin {
@@ -42,6 +99,21 @@ impl Plugin for SharedPlugin {
         // Other dynamic entities (like ore chunks) still use default gravity.
         app.insert_resource(Gravity(Vec3::new(0.0, -9.81, 0.0)));
 
+        // Clamp frame time so a hitch can't teleport players or skip interaction progress
+        app.insert_resource(parse_max_frame_delta());
+        app.add_systems(Startup, apply_max_frame_delta);
+
+        // Answer the other side's packet-loss probes (--net-diagnostics)
+        app.add_systems(Update, diagnostics::echo_net_probes);
+
+        // Optional strafe-jump air control (off unless --strafe-jump is passed)
+        app.insert_resource(player::parse_movement_config());
+        // ADS across weapon switches: the server parses --ads-switch and
+        // replicates it, clients start from the default until it arrives
+        app.init_resource::<player::AdsConfig>();
+        // Peek lean distance: parsed (--lean-max) and replicated by the server
+        app.init_resource::<player::LeanConfig>();
+
         // Note: FrameInterpolationPlugin is NOT needed — PositionButInterpolateTransform
         // mode handles Position→Transform and Rotation→Transform sync with smooth correction.
 
@@ -52,15 +124,19 @@ impl Plugin for SharedPlugin {
         //
         // Movement reads the world-space Move axis (rotated on the client before
         // BufferClientInputs). With zero input the movement system zeros XZ vel
-        // directly — no separate clear_xz_velocity step required.
+        // directly (or ramps it down with --ground-decel) — no separate
+        // clear_xz_velocity step required.
         app.add_systems(
             FixedUpdate,
             (
                 player::shared_look_system,
+                player::shared_stance_system,
+                player::sync_stance_collider,
                 player::shared_movement_system,
                 player::shared_jump_system,
                 player::character_controller,
                 player::sync_rotation_from_yaw,
+                player::shared_revive_system,
                 world::shared_door_interact_system,
                 world::shared_equip_interact_system,
                 world::shared_drop_system,
@@ -68,7 +144,8 @@ impl Plugin for SharedPlugin {
                 world::shared_primary_action_system,
                 world::reset_stale_mining,
             )
-                .chain(),
+                .chain()
+                .run_if(observer::match_running),
         );
     }
 }
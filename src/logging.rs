@@ -0,0 +1,79 @@
+//! Log filtering by category — lets an operator focus the log on one area
+//! (e.g. only network warnings) without knowing module paths.
+//!
+//! `--log <directives>` takes comma-separated `category=level` entries, where
+//! a category is one of `network`, `physics` or `gameplay` and expands to the
+//! modules behind it. Anything else is passed through as a plain tracing
+//! directive (`lightyear::connection=debug`). `--log-level <level>` sets the
+//! level for everything not named. `RUST_LOG`, when set, overrides both.
+
+use bevy::log::{Level, LogPlugin};
+use bevy::prelude::*;
+
+/// Log targets behind each category. The server binary logs everything
+/// under its own `server` target, so its connect/auth/disconnect lines name
+/// the `network` target explicitly.
+pub const LOG_CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "network",
+        &[
+            "network",
+            "lightyear",
+            "multiplayer::transport",
+            "multiplayer::diagnostics",
+            "multiplayer::auth",
+            "multiplayer::interest",
+        ],
+    ),
+    ("physics", &["avian3d", "multiplayer::player"]),
+    (
+        "gameplay",
+        &[
+            "server",
+            "multiplayer::world",
+            "multiplayer::rules",
+            "multiplayer::stats",
+            "multiplayer::autosave",
+            "multiplayer::observer",
+            "multiplayer::utility",
+        ],
+    ),
+];
+
+/// Bevy's default filter for noisy render crates, kept ahead of ours.
+const BASE_FILTER: &str = "wgpu=error,naga=warn";
+
+/// Expand category names in a `--log` value into tracing directives.
+pub fn expand_log_directives(value: &str) -> String {
+    let mut directives = Vec::new();
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (name, level) = entry.split_once('=').unwrap_or((entry, "trace"));
+        match LOG_CATEGORIES.iter().find(|(category, _)| *category == name) {
+            Some((_, targets)) => directives.extend(targets.iter().map(|t| format!("{}={}", t, level))),
+            None => directives.push(entry.to_string()),
+        }
+    }
+    directives.join(",")
+}
+
+/// LogPlugin configured from --log <directives> and --log-level <level>.
+pub fn log_plugin_from_args() -> LogPlugin {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+    };
+    let level = value("--log-level")
+        .and_then(|v| v.parse::<Level>().ok())
+        .unwrap_or(Level::INFO);
+    let filter = match value("--log") {
+        Some(directives) => format!("{},{}", BASE_FILTER, expand_log_directives(directives)),
+        None => BASE_FILTER.to_string(),
+    };
+    LogPlugin {
+        level,
+        filter,
+        ..default()
+    }
+}
@@ -0,0 +1,75 @@
+//! Observer/referee clients — privileged connections for tournaments.
+//!
+//! An observer connects like any other client, then presents a token from the
+//! server's configured list (`ObserverLoginMessage`). Once accepted the server
+//! removes the body it spawned for that connection, marks the link as an
+//! `Observer`, and accepts `AdminCommandMessage`s (pause, restart round) from
+//! it. The client switches to a free/follow spectator camera.
+
+use bevy::prelude::*;
+
+use crate::protocol::MatchPaused;
+
+/// Server: tokens that grant observer access. Set with
+/// `--observer-tokens <token>[,<token>...]`; empty means no observers allowed.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ObserverTokens(pub Vec<String>);
+
+impl ObserverTokens {
+    /// Whether `token` is one of the configured observer tokens.
+    pub fn accepts(&self, token: &str) -> bool {
+        !token.is_empty() && self.0.iter().any(|t| t == token)
+    }
+}
+
+/// Server-only marker on a client link entity whose observer token was accepted.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Observer;
+
+/// Parse --observer-tokens <comma-separated list> from CLI args.
+pub fn parse_observer_tokens() -> ObserverTokens {
+    let args: Vec<String> = std::env::args().collect();
+    let tokens: Vec<String> = args
+        .iter()
+        .position(|a| a == "--observer-tokens")
+        .and_then(|pos| args.get(pos + 1))
+        .map(|list| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    info!("[OBSERVER] {} observer token(s) configured", tokens.len());
+    ObserverTokens(tokens)
+}
+
+/// Client: parse --observer-token <token> from CLI args. When present the
+/// client asks to join as an observer instead of a player.
+pub fn parse_observer_token() -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|a| a == "--observer-token")
+        .and_then(|pos| args.get(pos + 1))
+        .cloned()
+}
+
+/// Largest observer grid (cells per side).
+pub const MAX_OBSERVER_GRID: u32 = 4;
+
+/// Parse --observer-grid <n> from CLI args: start observing in an n×n grid of
+/// player views (2–MAX_OBSERVER_GRID). None leaves the grid off until toggled.
+pub fn parse_observer_grid() -> Option<u32> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|a| a == "--observer-grid")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<u32>().ok())
+        .map(|n| n.clamp(2, MAX_OBSERVER_GRID))
+}
+
+/// Run condition: false while a referee has the match paused.
+pub fn match_running(paused: Query<(), With<MatchPaused>>) -> bool {
+    paused.is_empty()
+}
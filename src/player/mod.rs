@@ -6,13 +6,14 @@ use bevy::{
     window::{CursorGrabMode, CursorOptions, PrimaryWindow},
 };
 use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use avian3d::prelude::Rotation;
 use lightyear::prelude::{Controlled, Interpolated};
 
 use crate::protocol::{
-    CharacterVelocity, PlayerActions, PlayerDead, PlayerEquipped, PlayerHealth, PlayerId,
-    PlayerPitch, PlayerYaw,
+    CharacterVelocity, Downed, PlayerActions, PlayerDead, PlayerEquipped, PlayerHealth, PlayerId,
+    PlayerPitch, PlayerStance, PlayerYaw,
 };
 
 pub const PLAYER_MOVE_SPEED: f32 = 7.0;
@@ -21,6 +22,8 @@ pub const GRAVITY: f32 = 32.0;
 pub const SKIN_WIDTH: f32 = 0.02;
 pub const STEP_HEIGHT: f32 = 0.1;
 pub const VIEW_MODEL_RENDER_LAYER: usize = 1;
+/// Teammate silhouettes, drawn by their own camera on top of the world.
+pub const TEAMMATE_OUTLINE_RENDER_LAYER: usize = 2;
 pub const PLAYER_SPAWN_POS: Vec3 = Vec3::new(0.0, 1.5, 5.0);
 
 /// Spawn points spread across the Colorado wilderness compound.
@@ -30,7 +33,7 @@ pub const SPAWN_POINTS: &[Vec3] = &[
     Vec3::new(-14.0, 1.2, 2.0),    // Inside the equipment shed
     Vec3::new(19.0, 1.5, -2.0),    // Outside mine entrance
     Vec3::new(-7.5, 4.8, -7.5),    // Watchtower platform
-    Vec3::new(3.0, 1.0, 10.0),     // Campfire area
+    Vec3::new(3.0, 1.0, 13.5),     // Campfire area (south of the fire pit hazard)
     Vec3::new(-10.0, 1.5, -15.0),  // NW boulder cluster
     Vec3::new(12.0, 1.5, -16.0),   // NE rocky ridge
     Vec3::new(10.0, 2.0, 3.0),     // Near the old truck
@@ -58,8 +61,99 @@ pub fn select_spawn_point(living_positions: &[Vec3]) -> Vec3 {
 }
 
 /// Capsule dimensions (must match Collider in physics bundle)
-const CAPSULE_RADIUS: f32 = 0.5;
-const CAPSULE_HEIGHT: f32 = 1.0;
+pub const CAPSULE_RADIUS: f32 = 0.5;
+pub const CAPSULE_HEIGHT: f32 = 1.0;
+
+/// Eye height above the capsule center — shots and melee rays originate here.
+pub const EYE_HEIGHT: f32 = 0.8;
+
+/// How far the eye (and camera) drops while crouching.
+pub const CROUCH_EYE_DROP: f32 = 0.5;
+/// Capsule length while crouched. The crouched capsule keeps its bottom where
+/// the standing one's is, so its center sits CROUCH_CENTER_DROP lower.
+pub const CROUCH_CAPSULE_HEIGHT: f32 = 0.5;
+pub const CROUCH_CENTER_DROP: f32 = (CAPSULE_HEIGHT - CROUCH_CAPSULE_HEIGHT) / 2.0;
+/// Move speed multipliers for crouching / aiming down sights (they stack).
+pub const CROUCH_SPEED_MULT: f32 = 0.5;
+pub const ADS_SPEED_MULT: f32 = 0.7;
+/// Extra move speed multiplier while downed (on top of the forced crouch).
+pub const DOWNED_SPEED_MULT: f32 = 0.5;
+/// Default move speed multiplier while sprinting.
+pub const DEFAULT_SPRINT_MULT: f32 = 1.6;
+/// Sprint only applies when the move input is within ~45° of where we face,
+/// so strafing and backpedaling stay at normal speed.
+const SPRINT_FORWARD_DOT: f32 = 0.7;
+/// Seconds to refill stamina from empty while not sprinting.
+const STAMINA_REGEN_SECS: f32 = 4.0;
+
+impl PlayerStance {
+    /// Eye height above the capsule center for this stance.
+    pub fn eye_height(&self) -> f32 {
+        if self.crouching {
+            EYE_HEIGHT - CROUCH_EYE_DROP
+        } else {
+            EYE_HEIGHT
+        }
+    }
+
+    /// Eye position relative to the capsule center: eye height for the
+    /// stance plus the sideways lean offset at the given yaw.
+    pub fn eye_offset(&self, yaw: f32, lean_config: &LeanConfig) -> Vec3 {
+        Vec3::Y * self.eye_height() + lean_offset(yaw, self.lean, lean_config.max_offset)
+    }
+
+    /// Multiplier applied to ground move speed for this stance.
+    pub fn speed_mult(&self) -> f32 {
+        let mut mult = 1.0;
+        if self.crouching {
+            mult *= CROUCH_SPEED_MULT;
+        }
+        if self.aiming {
+            mult *= ADS_SPEED_MULT;
+        }
+        mult
+    }
+}
+
+/// Gap left between the shooter's collider and a spawned muzzle point.
+pub const MUZZLE_CLEARANCE: f32 = 0.05;
+
+/// Compute a safe spawn point for a shot/projectile fired by a capsule shooter.
+///
+/// `desired` is where the muzzle would normally be (eye + weapon offset). If that
+/// point lies inside the shooter's capsule (e.g. looking straight down, or a
+/// larger shooter), it is pushed along `direction` until it is just outside the
+/// capsule plus `MUZZLE_CLEARANCE`. Points already outside are returned as-is.
+pub fn safe_muzzle_position(
+    shooter_pos: Vec3,
+    desired: Vec3,
+    direction: Dir3,
+    radius: f32,
+    half_height: f32,
+) -> Vec3 {
+    let limit = radius + MUZZLE_CLEARANCE;
+    let inside = |p: Vec3| {
+        let local = p - shooter_pos;
+        let on_axis = Vec3::new(0.0, local.y.clamp(-half_height, half_height), 0.0);
+        local.distance(on_axis) < limit
+    };
+    if !inside(desired) {
+        return desired;
+    }
+
+    // Capsule is convex, so the ray leaves it exactly once — bisect for the exit.
+    let mut lo = 0.0;
+    let mut hi = 2.0 * (half_height + limit);
+    for _ in 0..16 {
+        let mid = (lo + hi) * 0.5;
+        if inside(desired + direction * mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    desired + direction * hi
+}
 
 /// Surface normal must have Y > this to count as walkable ground (~45° max slope)
 const MIN_GROUND_NORMAL_Y: f32 = 0.7;
@@ -73,6 +167,7 @@ pub struct Player {
 
 // --- Client-Only Components ---
 
+/// Look radians per mouse count (x = yaw, y = pitch).
 #[derive(Debug, Component, Deref, DerefMut)]
 pub struct CameraSensitivity(Vec2);
 
@@ -82,6 +177,13 @@ impl Default for CameraSensitivity {
     }
 }
 
+impl CameraSensitivity {
+    /// Build the per-count factors from the player's cm/360 + DPI settings.
+    pub fn from_settings(settings: &crate::settings::PlayerSettings) -> Self {
+        Self(Vec2::new(settings.yaw_per_count(), settings.pitch_per_count()))
+    }
+}
+
 #[derive(Resource)]
 pub struct CursorState {
     pub locked: bool,
@@ -100,13 +202,32 @@ impl Default for CursorState {
 
 /// Physics components for a player entity. Kinematic — we control Position directly
 /// via the character controller. Avian detects collisions but doesn't move us.
+///
+/// The server (`handle_connected`) and the client (`on_predicted_spawn`) both insert
+/// this exact bundle and run the same `character_controller`, so predicted movement
+/// matches the server's simulation by construction. There is no separate
+/// "client physics mode" — don't add a Dynamic body on the client.
 pub fn player_physics_bundle() -> impl Bundle {
     (
-        Collider::capsule(CAPSULE_RADIUS, CAPSULE_HEIGHT),
+        player_collider(false),
         RigidBody::Kinematic,
     )
 }
 
+/// Player collider for a stance, relative to `Position` (always the standing
+/// capsule center). Crouching shortens the capsule from the top.
+pub fn player_collider(crouching: bool) -> Collider {
+    if crouching {
+        Collider::compound(vec![(
+            Vec3::NEG_Y * CROUCH_CENTER_DROP,
+            Quat::IDENTITY,
+            Collider::capsule(CAPSULE_RADIUS, CROUCH_CAPSULE_HEIGHT),
+        )])
+    } else {
+        Collider::capsule(CAPSULE_RADIUS, CAPSULE_HEIGHT)
+    }
+}
+
 /// Replicated gameplay state for a player entity.
 /// Server spawns these; client receives them via lightyear replication.
 ///
@@ -120,10 +241,16 @@ pub fn player_replicated_bundle(client_id: u64) -> impl Bundle {
         PlayerYaw::default(),
         PlayerPitch::default(),
         PlayerEquipped::default(),
+        PlayerStance::default(),
         crate::protocol::PlayerInventory::default(),
         PlayerHealth::default(),
         crate::protocol::LastDamagedBy::default(),
         crate::protocol::LastShot::default(),
+        crate::protocol::KillStreak::default(),
+        crate::protocol::PlayerPing::default(),
+        crate::protocol::Resources::default(),
+        crate::protocol::Stamina::default(),
+        crate::protocol::RoundsFired::default(),
         CharacterVelocity::default(),
         Position(PLAYER_SPAWN_POS),
         Rotation::default(),
@@ -132,6 +259,120 @@ pub fn player_replicated_bundle(client_id: u64) -> impl Bundle {
 
 // --- Shared Movement (FixedUpdate, runs on both client + server) ---
 
+/// Optional movement rules shared by client prediction and the server.
+///
+/// `strafe_jump` enables Quake-style air control: while airborne, input only
+/// adds speed along the wish direction up to `air_wish_speed`, so turning while
+/// strafing keeps accelerating the player (capped at `max_air_speed`). Landing
+/// with jump pressed keeps the carried speed, so chained jumps build momentum.
+///
+/// `sprint_mult` scales ground speed while Sprint is held and moving forward.
+/// With `stamina_secs` set, sprinting drains `Stamina` and stops when it runs
+/// out; it refills over `STAMINA_REGEN_SECS` while not sprinting.
+///
+/// `ground_accel` / `ground_decel` ramp ground velocity toward the input
+/// target instead of snapping to it (0.0 keeps the instant start/stop).
+///
+/// Parsed from CLI flags on the server. The server replicates its config
+/// (`ServerMovementConfig`) and clients adopt it on connect, since a
+/// mismatch would mispredict and be corrected every jump.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MovementConfig {
+    pub strafe_jump: bool,
+    /// Ground acceleration toward the input velocity (m/s²). 0.0 = instant.
+    pub ground_accel: f32,
+    /// Ground deceleration to a stop with no input (m/s²). 0.0 = instant.
+    pub ground_decel: f32,
+    /// Air acceleration, in multiples of PLAYER_MOVE_SPEED per second.
+    pub air_accel: f32,
+    /// Air input only accelerates while speed along the wish direction is below this (m/s).
+    pub air_wish_speed: f32,
+    /// Hard cap on horizontal speed while strafe-jumping (m/s).
+    pub max_air_speed: f32,
+    /// Move speed multiplier while sprinting forward.
+    pub sprint_mult: f32,
+    /// Seconds of sprint on a full stamina bar. 0.0 = unlimited.
+    pub stamina_secs: f32,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            strafe_jump: false,
+            ground_accel: 0.0,
+            ground_decel: 0.0,
+            air_accel: 10.0,
+            air_wish_speed: 1.0,
+            max_air_speed: 14.0,
+            sprint_mult: DEFAULT_SPRINT_MULT,
+            stamina_secs: 0.0,
+        }
+    }
+}
+
+/// Parse --strafe-jump, --air-accel <n>, --max-air-speed <m/s>,
+/// --ground-accel <m/s²>, --ground-decel <m/s²>, --sprint-mult <n> and
+/// --stamina <secs> from CLI args.
+pub fn parse_movement_config() -> MovementConfig {
+    let args: Vec<String> = std::env::args().collect();
+    let mut config = MovementConfig::default();
+
+    let positive_arg = |flag: &str| -> Option<f32> {
+        let pos = args.iter().position(|a| a == flag)?;
+        args.get(pos + 1)?.parse::<f32>().ok().filter(|v| *v > 0.0)
+    };
+
+    config.strafe_jump = args.iter().any(|a| a == "--strafe-jump");
+    if let Some(accel) = positive_arg("--air-accel") {
+        config.air_accel = accel;
+    }
+    if let Some(max) = positive_arg("--max-air-speed") {
+        config.max_air_speed = max;
+    }
+    if let Some(accel) = positive_arg("--ground-accel") {
+        config.ground_accel = accel;
+    }
+    if let Some(decel) = positive_arg("--ground-decel") {
+        config.ground_decel = decel;
+    }
+    if let Some(mult) = positive_arg("--sprint-mult") {
+        config.sprint_mult = mult;
+    }
+    if let Some(secs) = positive_arg("--stamina") {
+        config.stamina_secs = secs;
+    }
+
+    config
+}
+
+/// Quake-style air acceleration: add speed along `wish_dir` only up to
+/// `air_wish_speed` of projected speed, then cap total horizontal speed.
+fn air_accelerate(h_vel: Vec2, wish_dir: Vec2, config: &MovementConfig, dt: f32) -> Vec2 {
+    let current = h_vel.dot(wish_dir);
+    let add = config.air_wish_speed - current;
+    if add <= 0.0 {
+        return h_vel;
+    }
+    let accel = (config.air_accel * PLAYER_MOVE_SPEED * dt).min(add);
+    (h_vel + wish_dir * accel).clamp_length_max(config.max_air_speed)
+}
+
+/// Moves ground velocity toward `target`: `ground_decel` when stopping,
+/// `ground_accel` otherwise. A zero rate snaps straight to the target.
+fn ground_accelerate(h_vel: Vec2, target: Vec2, config: &MovementConfig, dt: f32) -> Vec2 {
+    let rate = if target == Vec2::ZERO { config.ground_decel } else { config.ground_accel };
+    if rate <= 0.0 {
+        return target;
+    }
+    let diff = target - h_vel;
+    let step = rate * dt;
+    if diff.length() <= step {
+        target
+    } else {
+        h_vel + diff.normalize() * step
+    }
+}
+
 /// Reads the Move dual-axis from each player's ActionState and applies it to their
 /// CharacterVelocity. Input is already world-space (pre-rotated by camera yaw on
 /// the client before lightyear buffers the ActionState for replication).
@@ -141,26 +382,323 @@ pub fn player_replicated_bundle(client_id: u64) -> impl Bundle {
 /// system can be called during replay without the rubber-banding that plagued BEI.
 pub fn shared_movement_system(
     mut query: Query<
-        (&ActionState<PlayerActions>, &mut CharacterVelocity, Has<Interpolated>, Has<PlayerDead>),
+        (
+            Entity,
+            &ActionState<PlayerActions>,
+            &mut CharacterVelocity,
+            &Position,
+            &PlayerStance,
+            &PlayerYaw,
+            Option<&mut crate::protocol::Stamina>,
+            Option<&crate::protocol::SpeedBoost>,
+            Has<Interpolated>,
+            Has<PlayerDead>,
+            Has<Downed>,
+        ),
         With<PlayerId>,
     >,
+    config: Res<MovementConfig>,
+    spatial_query: SpatialQuery,
+    time: Res<Time>,
 ) {
-    for (action, mut vel, is_interpolated, is_dead) in query.iter_mut() {
+    let dt = time.delta_secs();
+
+    for (entity, action, mut vel, position, stance, yaw, stamina, boost, is_interpolated, is_dead, is_downed) in query.iter_mut() {
         if is_interpolated || is_dead {
             continue;
         }
 
         let input = action.axis_pair(&PlayerActions::Move);
 
-        if input == Vec2::ZERO {
-            vel.0.x = 0.0;
-            vel.0.z = 0.0;
+        // Input is world-space; compare against our facing (yaw 0 looks down -Z)
+        let forward = Vec2::new(-yaw.0.sin(), -yaw.0.cos());
+        let wants_sprint = action.pressed(&PlayerActions::Sprint)
+            && !is_downed
+            && input.normalize_or_zero().dot(forward) > SPRINT_FORWARD_DOT;
+        let mut sprinting = wants_sprint;
+        if config.stamina_secs > 0.0 {
+            if let Some(mut stamina) = stamina {
+                sprinting = wants_sprint && stamina.0 > 0.0;
+                let next = if sprinting {
+                    (stamina.0 - dt / config.stamina_secs).max(0.0)
+                } else {
+                    (stamina.0 + dt / STAMINA_REGEN_SECS).min(1.0)
+                };
+                if stamina.0 != next {
+                    stamina.0 = next;
+                }
+            }
+        }
+
+        if config.strafe_jump {
+            // A ground cast rather than vel.y: sliding along a slope leaves
+            // vertical velocity on a grounded player
+            let airborne = !is_grounded(&spatial_query, entity, position.0);
+            let h_vel = Vec2::new(vel.0.x, vel.0.z);
+            // Hopping on the landing tick keeps momentum instead of resetting to run speed
+            let hopping = action.pressed(&PlayerActions::Jump) && h_vel.length() > PLAYER_MOVE_SPEED;
+            if airborne || hopping {
+                let h_vel = match input.try_normalize() {
+                    Some(wish_dir) => air_accelerate(h_vel, wish_dir, &config, dt),
+                    None => h_vel,
+                };
+                vel.0.x = h_vel.x;
+                vel.0.z = h_vel.y;
+                continue;
+            }
+        }
+
+        let mut speed = PLAYER_MOVE_SPEED * stance.speed_mult();
+        if is_downed {
+            speed *= DOWNED_SPEED_MULT;
+        }
+        if sprinting {
+            speed *= config.sprint_mult;
+        }
+        if let Some(boost) = boost {
+            speed *= boost.multiplier;
+        }
+        // Partial input (a half-tilted stick, a patrolling bot) moves slower
+        let target = input.clamp_length_max(1.0) * speed;
+        let h_vel = ground_accelerate(Vec2::new(vel.0.x, vel.0.z), target, &config, dt);
+        vel.0.x = h_vel.x;
+        vel.0.z = h_vel.y;
+    }
+}
+
+/// Seconds after a weapon switch before the new weapon is raised and can be aimed.
+pub const WEAPON_RAISE_SECS: f32 = 0.35;
+
+/// What happens to ADS when the held weapon changes while Aim is held.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AdsSwitchMode {
+    /// ADS drops on the switch; release and press Aim again once the new
+    /// weapon is raised.
+    #[default]
+    Cancel,
+    /// ADS comes back by itself once the new weapon is raised, if Aim is
+    /// still held.
+    CarryThrough,
+}
+
+/// ADS rules shared by client prediction and the server. Parsed from the
+/// server's CLI; the server replicates it (`ServerAdsConfig`) and clients
+/// adopt it, since a mismatch would mispredict stance.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct AdsConfig {
+    pub switch_mode: AdsSwitchMode,
+}
+
+/// Parse --ads-switch <cancel|carry> from CLI args (server only).
+pub fn parse_ads_config() -> AdsConfig {
+    let args: Vec<String> = std::env::args().collect();
+    let switch_mode = match args
+        .iter()
+        .position(|a| a == "--ads-switch")
+        .and_then(|pos| args.get(pos + 1))
+        .map(String::as_str)
+    {
+        Some("carry") => AdsSwitchMode::CarryThrough,
+        _ => AdsSwitchMode::Cancel,
+    };
+    AdsConfig { switch_mode }
+}
+
+/// Default sideways eye offset at full lean (meters). Past the capsule edge,
+/// so a full lean clears a corner with the head while the body stays behind it.
+pub const DEFAULT_LEAN_OFFSET: f32 = 0.6;
+/// Camera roll at full lean (radians).
+pub const LEAN_ROLL: f32 = 0.2;
+/// Lean fraction gained/lost per second (0 → full lean in 0.25s).
+const LEAN_SPEED: f32 = 4.0;
+/// Radius of the head hit sphere that moves with the lean. Also the clearance
+/// kept from walls, so a lean can't push the eye through geometry.
+pub const LEAN_HEAD_RADIUS: f32 = 0.2;
+
+/// Lean limits shared by client prediction and the server. Parsed from the
+/// server's CLI; the server replicates it (`ServerLeanConfig`) and clients
+/// adopt it, so the camera and the lean head hitbox agree.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct LeanConfig {
+    /// Sideways eye offset at full lean (meters). 0 disables leaning.
+    pub max_offset: f32,
+}
+
+impl Default for LeanConfig {
+    fn default() -> Self {
+        Self { max_offset: DEFAULT_LEAN_OFFSET }
+    }
+}
+
+/// Parse --lean-max <meters> from CLI args (server only).
+pub fn parse_lean_config() -> LeanConfig {
+    let args: Vec<String> = std::env::args().collect();
+    let max_offset = args
+        .iter()
+        .position(|a| a == "--lean-max")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|v| *v >= 0.0)
+        .unwrap_or(DEFAULT_LEAN_OFFSET);
+    LeanConfig { max_offset }
+}
+
+/// World-space eye offset for a lean fraction (see `PlayerStance::lean`) at
+/// the given yaw. Purely horizontal, along the camera's right axis.
+pub fn lean_offset(yaw: f32, lean: f32, max_offset: f32) -> Vec3 {
+    Quat::from_rotation_y(yaw) * Vec3::X * lean * max_offset
+}
+
+/// Distance along `dir` (unit length) at which a ray from `origin` enters a
+/// sphere, if it does.
+pub fn ray_sphere(origin: Vec3, dir: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let to_center = center - origin;
+    let along = to_center.dot(dir);
+    let miss_sq = to_center.length_squared() - along * along;
+    let radius_sq = radius * radius;
+    if miss_sq > radius_sq {
+        return None;
+    }
+    let entry = along - (radius_sq - miss_sq).sqrt();
+    (entry >= 0.0).then_some(entry)
+}
+
+/// Tracks weapon switches for ADS: when the held item last changed and
+/// whether Aim must be re-pressed. Not replicated — client and server each
+/// derive it from `PlayerEquipped` — and not rolled back, so a mispredicted
+/// switch can at worst delay ADS by one raise.
+#[derive(Component, Clone, Debug, Default)]
+pub struct WeaponRaise {
+    pub item: Option<String>,
+    /// Time the current item finishes raising.
+    pub raised_at: f32,
+    pub needs_reaim: bool,
+}
+
+/// Stance: aiming while Aim is held with a weapon in hand; crouching while
+/// Crouch is held, or while aiming if the player opted into auto-crouch.
+/// The two sources are OR-ed, so releasing ADS never cancels a manual crouch
+/// and releasing crouch keeps the ADS crouch. A crouched player only stands
+/// back up once there is headroom for the full capsule. Shared between client
+/// + server.
+///
+/// After a weapon switch the new weapon can't be aimed for WEAPON_RAISE_SECS;
+/// `AdsConfig` decides whether a held Aim then resumes ADS or must be re-pressed.
+///
+/// Lean eases toward the held LeanLeft/LeanRight direction, limited so the
+/// head keeps LEAN_HEAD_RADIUS of clearance from walls on that side. The
+/// server runs the same check, so a client can't lean its eye through a wall.
+pub fn shared_stance_system(
+    mut query: Query<
+        (Entity, &ActionState<PlayerActions>, &PlayerEquipped, &mut PlayerStance, Option<&mut WeaponRaise>, &Position, &PlayerYaw, Has<crate::protocol::AutoCrouchAds>, Has<Interpolated>, Has<PlayerDead>, Has<Downed>),
+        With<PlayerId>,
+    >,
+    ads_config: Res<AdsConfig>,
+    lean_config: Res<LeanConfig>,
+    spatial_query: SpatialQuery,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let now = time.elapsed_secs();
+    for (entity, action, equipped, mut stance, raise, position, yaw, auto_crouch, is_interpolated, is_dead, is_downed) in query.iter_mut() {
+        if is_interpolated {
             continue;
         }
 
-        let move_dir = input.normalize_or_zero();
-        vel.0.x = move_dir.x * PLAYER_MOVE_SPEED;
-        vel.0.z = move_dir.y * PLAYER_MOVE_SPEED;
+        let aim_pressed = action.pressed(&PlayerActions::Aim);
+        let aim_ready = match raise {
+            // First tick: whatever is in hand is already raised
+            None => {
+                commands.entity(entity).insert(WeaponRaise { item: equipped.0.clone(), ..default() });
+                true
+            }
+            Some(mut raise) => {
+                if raise.item != equipped.0 {
+                    raise.item = equipped.0.clone();
+                    raise.raised_at = now + WEAPON_RAISE_SECS;
+                    raise.needs_reaim = aim_pressed && ads_config.switch_mode == AdsSwitchMode::Cancel;
+                }
+                if !aim_pressed {
+                    raise.needs_reaim = false;
+                }
+                now >= raise.raised_at && !raise.needs_reaim
+            }
+        };
+
+        let has_weapon = equipped
+            .0
+            .as_deref()
+            .is_some_and(|name| crate::weapons::weapon_spec(name).is_some());
+        let aiming = !is_dead && !is_downed && has_weapon && aim_ready && aim_pressed;
+        // Downed players crawl
+        let wants_crouch = is_downed || action.pressed(&PlayerActions::Crouch) || (auto_crouch && aiming);
+        let crouching = !is_dead && (wants_crouch || (stance.crouching && !has_headroom(&spatial_query, entity, position.0)));
+
+        let mut next = PlayerStance { crouching, aiming, lean: 0.0 };
+
+        let lean_dir = if is_dead || is_downed || lean_config.max_offset <= 0.0 {
+            0.0
+        } else {
+            match (action.pressed(&PlayerActions::LeanLeft), action.pressed(&PlayerActions::LeanRight)) {
+                (true, false) => -1.0,
+                (false, true) => 1.0,
+                _ => 0.0,
+            }
+        };
+        // Room on the side we're leaning toward (or currently leaning to)
+        let side = if lean_dir != 0.0 { lean_dir } else { stance.lean.signum() };
+        let mut room = 1.0;
+        if side != 0.0 && lean_config.max_offset > 0.0 {
+            let eye = position.0 + Vec3::Y * next.eye_height();
+            let toward = Dir3::new(lean_offset(yaw.0, side, 1.0)).unwrap_or(Dir3::X);
+            let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+            if let Some(hit) = spatial_query.cast_ray(eye, toward, lean_config.max_offset + LEAN_HEAD_RADIUS, true, &filter) {
+                room = ((hit.distance - LEAN_HEAD_RADIUS) / lean_config.max_offset).clamp(0.0, 1.0);
+            }
+        }
+        let target = lean_dir * room;
+        let step = LEAN_SPEED * time.delta_secs();
+        next.lean = (stance.lean + (target - stance.lean).clamp(-step, step)).clamp(-room, room);
+
+        if *stance != next {
+            *stance = next;
+        }
+    }
+}
+
+/// Whether a crouched player at `position` has room overhead to stand up:
+/// the crouched capsule swept up to the standing height hits nothing.
+fn has_headroom(spatial_query: &SpatialQuery, entity: Entity, position: Vec3) -> bool {
+    let config = ShapeCastConfig {
+        max_distance: CROUCH_CENTER_DROP * 2.0,
+        target_distance: SKIN_WIDTH,
+        compute_contact_on_penetration: true,
+        ignore_origin_penetration: true,
+    };
+    let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+    spatial_query
+        .cast_shape(
+            &Collider::capsule(CAPSULE_RADIUS, CROUCH_CAPSULE_HEIGHT),
+            position + Vec3::NEG_Y * CROUCH_CENTER_DROP,
+            Quat::IDENTITY,
+            Dir3::Y,
+            &config,
+            &filter,
+        )
+        .is_none()
+}
+
+/// Swaps the player collider when the crouch state changes, so a crouched
+/// player fits under low geometry and has a shorter hitbox. Shared between
+/// client + server (and applied to interpolated remote players on the client).
+pub fn sync_stance_collider(
+    mut query: Query<(&PlayerStance, &mut Collider), (Changed<PlayerStance>, With<PlayerId>)>,
+) {
+    for (stance, mut collider) in query.iter_mut() {
+        let is_crouched = collider.shape().as_compound().is_some();
+        if is_crouched != stance.crouching {
+            *collider = player_collider(stance.crouching);
+        }
     }
 }
 
@@ -169,13 +707,13 @@ pub fn shared_movement_system(
 /// though the key may be held across multiple ticks.
 pub fn shared_jump_system(
     mut query: Query<
-        (Entity, &ActionState<PlayerActions>, &mut CharacterVelocity, &Position, Has<Interpolated>, Has<PlayerDead>),
+        (Entity, &ActionState<PlayerActions>, &mut CharacterVelocity, &Position, Has<Interpolated>, Has<PlayerDead>, Has<Downed>),
         With<PlayerId>,
     >,
     spatial_query: SpatialQuery,
 ) {
-    for (entity, action, mut vel, position, is_interpolated, is_dead) in query.iter_mut() {
-        if is_interpolated || is_dead {
+    for (entity, action, mut vel, position, is_interpolated, is_dead, is_downed) in query.iter_mut() {
+        if is_interpolated || is_dead || is_downed {
             continue;
         }
         if !action.just_pressed(&PlayerActions::Jump) {
@@ -185,29 +723,33 @@ pub fn shared_jump_system(
             continue;
         }
 
-        let capsule = Collider::capsule(CAPSULE_RADIUS, CAPSULE_HEIGHT);
-        let config = ShapeCastConfig {
-            max_distance: 0.15,
-            target_distance: SKIN_WIDTH,
-            compute_contact_on_penetration: true,
-            ignore_origin_penetration: true,
-        };
-        let filter = SpatialQueryFilter::from_excluded_entities([entity]);
-
-        if let Some(hit) = spatial_query.cast_shape(
-            &capsule, position.0, Quat::IDENTITY, Dir3::NEG_Y, &config, &filter,
-        ) {
-            if hit.normal1.y > MIN_GROUND_NORMAL_Y {
-                vel.0.y = JUMP_SPEED;
-            }
+        if is_grounded(&spatial_query, entity, position.0) {
+            vel.0.y = JUMP_SPEED;
         }
     }
 }
 
-/// Reads the Look dual-axis (mouse motion) and applies it to yaw/pitch.
+/// Whether the player at `position` stands on walkable ground: a short
+/// downward capsule cast hits a surface flat enough to stand on.
+fn is_grounded(spatial_query: &SpatialQuery, entity: Entity, position: Vec3) -> bool {
+    let capsule = Collider::capsule(CAPSULE_RADIUS, CAPSULE_HEIGHT);
+    let config = ShapeCastConfig {
+        max_distance: 0.15,
+        target_distance: SKIN_WIDTH,
+        compute_contact_on_penetration: true,
+        ignore_origin_penetration: true,
+    };
+    let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+    spatial_query
+        .cast_shape(&capsule, position, Quat::IDENTITY, Dir3::NEG_Y, &config, &filter)
+        .is_some_and(|hit| hit.normal1.y > MIN_GROUND_NORMAL_Y)
+}
+
+/// Reads the Look dual-axis and applies it to yaw/pitch.
 /// Runs on both client (prediction) and server (authority); lightyear's
-/// ActionState replication means the server sees the same mouse deltas the
-/// client buffered.
+/// ActionState replication means the server sees the same deltas the
+/// client buffered. The axis is already in radians — the client scales raw
+/// mouse counts by `CameraSensitivity` in `apply_look_sensitivity`.
 pub fn shared_look_system(
     mut query: Query<
         (&ActionState<PlayerActions>, &mut PlayerYaw, &mut PlayerPitch, Has<Interpolated>, Has<PlayerDead>),
@@ -224,9 +766,9 @@ pub fn shared_look_system(
             continue;
         }
 
-        yaw.0 += -delta.x * 0.003;
+        yaw.0 += -delta.x;
         const PITCH_LIMIT: f32 = FRAC_PI_2 - 0.01;
-        pitch.0 = (pitch.0 + -delta.y * 0.002).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+        pitch.0 = (pitch.0 + -delta.y).clamp(-PITCH_LIMIT, PITCH_LIMIT);
     }
 }
 
@@ -245,7 +787,7 @@ pub fn shared_look_system(
 /// Flow: collect (p0) → shape cast (p1) → write back (p2).
 pub fn character_controller(
     mut params: ParamSet<(
-        Query<(Entity, &Position, &CharacterVelocity), (With<PlayerId>, With<Collider>, Without<Interpolated>)>,
+        Query<(Entity, &Position, &CharacterVelocity, Option<&PlayerStance>), (With<PlayerId>, With<Collider>, Without<Interpolated>)>,
         SpatialQuery,
         Query<(&mut Position, &mut CharacterVelocity), (With<PlayerId>, With<Collider>, Without<Interpolated>)>,
     )>,
@@ -256,20 +798,28 @@ pub fn character_controller(
     // Shorter capsule for horizontal casts — bottom raised by STEP_HEIGHT
     // to prevent scraping the ground and gives basic stair-stepping
     let h_capsule = Collider::capsule(CAPSULE_RADIUS, (CAPSULE_HEIGHT - STEP_HEIGHT * 2.0).max(0.0));
+    // Crouched shapes are cast from CROUCH_CENTER_DROP below Position
+    let crouch_capsule = Collider::capsule(CAPSULE_RADIUS, CROUCH_CAPSULE_HEIGHT);
+    let crouch_h_capsule = Collider::capsule(CAPSULE_RADIUS, (CROUCH_CAPSULE_HEIGHT - STEP_HEIGHT * 2.0).max(0.0));
 
     // 1. Collect current state
-    let players: Vec<(Entity, Vec3, Vec3)> = params
+    let players: Vec<(Entity, Vec3, Vec3, bool)> = params
         .p0()
         .iter()
-        .map(|(e, p, v)| (e, p.0, v.0))
+        .map(|(e, p, v, stance)| (e, p.0, v.0, stance.is_some_and(|s| s.crouching)))
         .collect();
 
     // 2. Compute new positions using SpatialQuery
     let spatial = params.p1();
     let mut results: Vec<(Entity, Vec3, Vec3)> = Vec::with_capacity(players.len());
 
-    for (entity, mut pos, mut vel) in players {
+    for (entity, mut pos, mut vel, crouching) in players {
         let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+        let (capsule, h_capsule, offset) = if crouching {
+            (&crouch_capsule, &crouch_h_capsule, Vec3::NEG_Y * CROUCH_CENTER_DROP)
+        } else {
+            (&capsule, &h_capsule, Vec3::ZERO)
+        };
 
         // Apply gravity
         vel.y -= GRAVITY * dt;
@@ -278,7 +828,7 @@ pub fn character_controller(
         let h_vel = Vec3::new(vel.x, 0.0, vel.z);
         if h_vel.length_squared() > 0.0001 {
             let h_delta = h_vel * dt;
-            pos += move_and_slide(&spatial, &h_capsule, pos, h_delta, &filter);
+            pos += move_and_slide(&spatial, h_capsule, pos + offset, h_delta, &filter);
         }
 
         // --- Vertical movement + ground detection ---
@@ -292,7 +842,7 @@ pub fn character_controller(
             };
 
             match spatial.cast_shape(
-                &capsule, pos, Quat::IDENTITY, Dir3::NEG_Y, &config, &filter,
+                capsule, pos + offset, Quat::IDENTITY, Dir3::NEG_Y, &config, &filter,
             ) {
                 Some(hit) if hit.normal1.y > MIN_GROUND_NORMAL_Y => {
                     // Hit walkable ground — snap and zero vertical velocity
@@ -317,7 +867,7 @@ pub fn character_controller(
             };
 
             match spatial.cast_shape(
-                &capsule, pos, Quat::IDENTITY, Dir3::Y, &config, &filter,
+                capsule, pos + offset, Quat::IDENTITY, Dir3::Y, &config, &filter,
             ) {
                 Some(hit) => {
                     if hit.distance > 0.0 {
@@ -430,6 +980,41 @@ pub fn sync_rotation_from_yaw(
     }
 }
 
+/// Shared system: revive progress on downed players. Holding Interact within
+/// REVIVE_DISTANCE of a downed player starts/continues the revive; letting go
+/// (or walking away) resets it. Same start/last timestamp scheme as mining, so
+/// replayed ticks are idempotent. The server finishes the revive once progress
+/// reaches REVIVE_TIME (see `process_downed`). With teams (CTF), only a
+/// teammate can revive.
+pub fn shared_revive_system(
+    reviver_query: Query<
+        (&ActionState<PlayerActions>, &Position, Option<&crate::protocol::Team>, Has<Interpolated>),
+        (With<PlayerId>, Without<PlayerDead>, Without<Downed>),
+    >,
+    mut downed_query: Query<(&Position, Option<&crate::protocol::Team>, &mut Downed)>,
+    time: Res<Time>,
+) {
+    let current_secs = time.elapsed_secs();
+    for (downed_pos, downed_team, mut downed) in downed_query.iter_mut() {
+        let reviving = reviver_query.iter().any(|(action, pos, team, is_interpolated)| {
+            !is_interpolated
+                && (downed_team.is_none() || team == downed_team)
+                && action.pressed(&PlayerActions::Interact)
+                && pos.0.distance(downed_pos.0) <= crate::rules::REVIVE_DISTANCE
+        });
+
+        if reviving {
+            if downed.revive_start_secs.is_none() {
+                downed.revive_start_secs = Some(current_secs);
+            }
+            downed.last_revive_secs = Some(current_secs);
+        } else if downed.last_revive_secs.is_some_and(|last| current_secs - last > 0.05) {
+            downed.revive_start_secs = None;
+            downed.last_revive_secs = None;
+        }
+    }
+}
+
 // --- Client-Only Systems ---
 
 /// Client-only: rotates the Move axis from player-local (WASD) frame to world frame
@@ -460,6 +1045,44 @@ pub fn pre_rotate_move_input(
     action.set_axis_pair(&PlayerActions::Move, rotated);
 }
 
+/// Client-only: chat UI that currently owns the keyboard or mouse.
+#[derive(Resource, Default)]
+pub struct ChatFocus {
+    /// The chat box has keyboard focus.
+    pub typing: bool,
+    /// The quick-chat wheel is open; the mouse picks a phrase instead of looking.
+    pub wheel: bool,
+}
+
+/// Client-only: releases every action while the chat box is focused, so typing
+/// "w" doesn't walk the player forward, and holds the view still while the
+/// quick-chat wheel is open. First in the ManualControl chain. Hotkeys read
+/// straight from ButtonInput (inspect, panels, scoreboard, spectator keys)
+/// check `ChatFocus::typing` themselves.
+pub fn gate_input_on_chat(
+    focus: Res<ChatFocus>,
+    mut query: Query<&mut ActionState<PlayerActions>, With<Controlled>>,
+) {
+    for mut action in query.iter_mut() {
+        if focus.typing {
+            action.reset_all();
+        } else if focus.wheel {
+            action.set_axis_pair(&PlayerActions::Look, Vec2::ZERO);
+        }
+    }
+}
+
+/// Client-only: releases every action while our player is dead — the mouse
+/// and movement keys drive the spectator camera instead, and nothing is sent
+/// to the server for the body.
+pub fn gate_input_while_dead(
+    mut query: Query<&mut ActionState<PlayerActions>, (With<Controlled>, With<crate::protocol::PlayerDead>)>,
+) {
+    for mut action in query.iter_mut() {
+        action.reset_all();
+    }
+}
+
 /// Client-only: zeros the Look axis when the cursor is unlocked (e.g. Escape pressed).
 /// Prevents mouse deltas from being sent to the server when the player isn't in control.
 /// Runs in FixedPreUpdate in the `InputManagerSystem::ManualControl` set (after leafwing
@@ -476,6 +1099,70 @@ pub fn gate_look_on_cursor(
     }
 }
 
+/// Client-only: converts the raw Look axis (mouse counts) into yaw/pitch radians
+/// using the player's `CameraSensitivity`, so the value replicated to the server
+/// is exactly what `shared_look_system` applies. Same schedule slot as
+/// `gate_look_on_cursor` (ManualControl, before BufferClientInputs).
+///
+/// The counts are device motion (winit's `DeviceEvent::MouseMotion`, summed into
+/// `AccumulatedMouseMotion`), not cursor movement. That is raw, unaccelerated
+/// input on Windows (WM_INPUT), X11 (XInput2 raw events) and Wayland (relative
+/// pointer). macOS only reports accelerated deltas, and winit has no raw hook there.
+///
+/// The player's look curve then scales the result by how fast the mouse moved
+/// this tick (flat by default).
+pub fn apply_look_sensitivity(
+    mut query: Query<(&CameraSensitivity, &mut ActionState<PlayerActions>), With<Controlled>>,
+    settings: Res<crate::settings::PlayerSettings>,
+    time: Res<Time>,
+) {
+    let dt_ms = time.delta_secs() * 1000.0;
+    for (sensitivity, mut action) in query.iter_mut() {
+        let raw = action.axis_pair(&PlayerActions::Look);
+        if raw == Vec2::ZERO {
+            continue;
+        }
+        let speed = if dt_ms > 0.0 { raw.length() / dt_ms / crate::settings::LOOK_CURVE_MOUSE_SPEED } else { 0.0 };
+        let multiplier = settings.look_curve.multiplier(speed);
+        action.set_axis_pair(&PlayerActions::Look, raw * sensitivity.0 * multiplier);
+    }
+}
+
+/// Client-only: adds right-stick look from any connected gamepad to the Look
+/// axis, after `apply_look_sensitivity` (so it's added in radians). Stick up
+/// looks up; pitch uses the same vertical ratio as the mouse sensitivity.
+/// Skipped while the cursor is unlocked, like mouse look. The look curve is
+/// applied by deflection past the deadzone.
+pub fn apply_gamepad_look(
+    gamepads: Query<&Gamepad>,
+    settings: Res<crate::settings::GamepadSettings>,
+    player_settings: Res<crate::settings::PlayerSettings>,
+    cursor_state: Res<CursorState>,
+    mut query: Query<(&CameraSensitivity, &mut ActionState<PlayerActions>), With<Controlled>>,
+    time: Res<Time>,
+) {
+    if !cursor_state.locked {
+        return;
+    }
+    let Some(stick) = gamepads
+        .iter()
+        .map(|gamepad| gamepad.right_stick())
+        .find(|stick| stick.length() > settings.deadzone)
+    else {
+        return;
+    };
+    // Rescale so motion starts from zero at the deadzone edge
+    let magnitude = ((stick.length() - settings.deadzone) / (1.0 - settings.deadzone)).min(1.0);
+    let stick = stick.normalize_or_zero() * magnitude;
+    let multiplier = player_settings.look_curve.multiplier(magnitude);
+    for (sensitivity, mut action) in query.iter_mut() {
+        let vertical_ratio = if sensitivity.x > 0.0 { sensitivity.y / sensitivity.x } else { 1.0 };
+        let turn = settings.look_speed * multiplier * time.delta_secs();
+        let look = action.axis_pair(&PlayerActions::Look) + Vec2::new(stick.x, -stick.y * vertical_ratio) * turn;
+        action.set_axis_pair(&PlayerActions::Look, look);
+    }
+}
+
 /// Client-only: ensures the camera child has identity rotation.
 /// The parent's Rotation now includes both yaw and pitch (via sync_rotation_from_yaw),
 /// so the camera child inherits the correct orientation automatically.
@@ -494,20 +1181,23 @@ pub fn sync_camera_pitch(
     }
 }
 
-/// Grab/release cursor on click/escape
+/// Grab/release cursor on click/escape. Clicks on an egui window (menus,
+/// panels) are left to the window.
 pub fn grab_mouse(
     mut cursor_options: Query<&mut CursorOptions, With<PrimaryWindow>>,
     mouse: Res<ButtonInput<MouseButton>>,
     key: Res<ButtonInput<KeyCode>>,
     mut cursor_state: ResMut<CursorState>,
+    mut contexts: bevy_egui::EguiContexts,
 ) {
     let Ok(mut options) = cursor_options.single_mut() else {
         return;
     };
+    let over_ui = contexts.ctx_mut().is_ok_and(|ctx| ctx.is_pointer_over_area());
 
     if key.just_pressed(KeyCode::Escape) && cursor_state.locked {
         cursor_state.locked = false;
-    } else if mouse.just_pressed(MouseButton::Left) && !cursor_state.locked {
+    } else if mouse.just_pressed(MouseButton::Left) && !cursor_state.locked && !over_ui {
         cursor_state.locked = true;
     }
 
@@ -523,20 +1213,174 @@ pub fn grab_mouse(
 /// Adjust FOV with arrow keys
 pub fn change_fov(
     input: Res<ButtonInput<KeyCode>>,
-    mut camera: Query<&mut Projection, With<crate::world::WorldModelCamera>>,
+    mut base_fov: ResMut<BaseFov>,
 ) {
-    if let Ok(mut projection) = camera.single_mut() {
-        let Projection::Perspective(ref mut perspective) = projection.as_mut() else {
-            return;
-        };
+    if input.pressed(KeyCode::ArrowUp) {
+        base_fov.0 = (base_fov.0 - 1.0_f32.to_radians()).max(20.0_f32.to_radians());
+    }
+    if input.pressed(KeyCode::ArrowDown) {
+        base_fov.0 = (base_fov.0 + 1.0_f32.to_radians()).min(160.0_f32.to_radians());
+    }
+}
+
+/// Client-only clock for cosmetic animation (view model, tracers, recoil,
+/// glow). Follows real frame time except during a hit-stop, when it runs
+/// slower. The networked simulation keeps running on `Time<Fixed>`, so
+/// slowing this clock never affects prediction or what the server sees.
+#[derive(Default)]
+pub struct Presentation;
+
+/// Speed of the presentation clock during a hit-stop.
+pub const HIT_STOP_SCALE: f32 = 0.2;
+
+/// Client-only: brief slow-motion of the presentation clock after the local
+/// player gets a kill. `duration` 0.0 disables it.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct HitStop {
+    pub duration: f32,
+    /// Real time the current hit-stop ends.
+    until: f32,
+}
+
+impl HitStop {
+    pub fn new(duration: f32) -> Self {
+        Self { duration, until: 0.0 }
+    }
+}
+
+/// Client-only: advances `Time<Presentation>` by the real frame delta,
+/// scaled down while a hit-stop is active.
+pub fn advance_presentation_time(
+    mut presentation: ResMut<Time<Presentation>>,
+    real: Res<Time<Real>>,
+    hit_stop: Res<HitStop>,
+) {
+    let scale = if real.elapsed_secs() < hit_stop.until { HIT_STOP_SCALE } else { 1.0 };
+    presentation.advance_by(real.delta().mul_f32(scale));
+}
+
+/// Client-only: starts a hit-stop when our replicated kill count goes up.
+pub fn trigger_hit_stop(
+    player_query: Query<&crate::protocol::PlayerStats, With<Controlled>>,
+    mut hit_stop: ResMut<HitStop>,
+    real: Res<Time<Real>>,
+    mut last_kills: Local<Option<u32>>,
+) {
+    let Ok(stats) = player_query.single() else { return; };
+    let previous = last_kills.replace(stats.kills);
+    if hit_stop.duration > 0.0 && previous.is_some_and(|kills| stats.kills > kills) {
+        hit_stop.until = real.elapsed_secs() + hit_stop.duration;
+    }
+}
 
-        if input.pressed(KeyCode::ArrowUp) {
-            perspective.fov -= 1.0_f32.to_radians();
-            perspective.fov = perspective.fov.max(20.0_f32.to_radians());
+/// Client-only: camera recoil from the local player's shots. The world camera
+/// pitches up by `current` radians; after the last shot it settles back along
+/// the weapon's recovery curve.
+#[derive(Resource, Default)]
+pub struct CameraRecoil {
+    /// Kick when the last shot landed — the curve eases from here to zero.
+    peak: f32,
+    /// Time of the last shot.
+    since: f32,
+    recoil: Option<crate::weapons::RecoilSpec>,
+}
+
+impl CameraRecoil {
+    /// Kick currently applied at time `now`.
+    pub fn current(&self, now: f32) -> f32 {
+        let Some(recoil) = self.recoil else { return 0.0 };
+        if recoil.recovery_secs <= 0.0 {
+            return 0.0;
+        }
+        self.peak * recoil.curve.remaining((now - self.since) / recoil.recovery_secs)
+    }
+}
+
+/// Client-only observer: kicks the camera when the local player fires a
+/// weapon with recoil. Kicks stack during a burst up to the weapon's cap.
+pub fn kick_camera_recoil(
+    _trigger: On<crate::world::ShotFired>,
+    player_query: Query<&PlayerEquipped, With<Controlled>>,
+    mut recoil: ResMut<CameraRecoil>,
+    time: Res<Time<Presentation>>,
+) {
+    let Ok(equipped) = player_query.single() else { return; };
+    let Some(spec) = equipped.0.as_deref().and_then(crate::weapons::weapon_spec).and_then(|w| w.recoil) else {
+        return;
+    };
+    let now = time.elapsed_secs();
+    recoil.peak = (recoil.current(now) + spec.kick).min(spec.max_kick);
+    recoil.since = now;
+    recoil.recoil = Some(spec);
+}
+
+/// Client-only: pitches the world camera up by the current recoil kick.
+/// Runs after `sync_camera_pitch`, which resets the camera's local rotation.
+pub fn apply_camera_recoil(
+    player_query: Query<&Children, With<Controlled>>,
+    mut camera_query: Query<&mut Transform, With<crate::world::WorldModelCamera>>,
+    recoil: Res<CameraRecoil>,
+    time: Res<Time<Presentation>>,
+) {
+    let Ok(children) = player_query.single() else {
+        return;
+    };
+    let kick = recoil.current(time.elapsed_secs());
+    for child in children.iter() {
+        if let Ok(mut cam_transform) = camera_query.get_mut(child) {
+            cam_transform.rotation = Quat::from_rotation_x(kick);
+        }
+    }
+}
+
+/// FOV multiplier while aiming down sights.
+pub const ADS_FOV_SCALE: f32 = 0.6;
+/// How quickly the camera eases between stances (higher = snappier).
+const STANCE_CAMERA_SPEED: f32 = 12.0;
+
+/// Client-only: hip-fire field of view (radians) for the world camera.
+/// `change_fov` edits it; `apply_stance_camera` scales it while aiming.
+#[derive(Resource)]
+pub struct BaseFov(pub f32);
+
+impl Default for BaseFov {
+    fn default() -> Self {
+        Self(90.0_f32.to_radians())
+    }
+}
+
+/// Client-only: eases the world camera down while crouching, sideways and
+/// rolled while leaning, and zooms the FOV while aiming down sights, following
+/// the predicted PlayerStance. Runs after `apply_camera_recoil`, which sets
+/// the camera's local rotation the roll is applied on top of.
+pub fn apply_stance_camera(
+    player_query: Query<(&PlayerStance, &Children), With<Controlled>>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), With<crate::world::WorldModelCamera>>,
+    base_fov: Res<BaseFov>,
+    lean_config: Res<LeanConfig>,
+    time: Res<Time>,
+) {
+    let Ok((stance, children)) = player_query.single() else {
+        return;
+    };
+
+    let t = 1.0 - (-STANCE_CAMERA_SPEED * time.delta_secs()).exp();
+    let target_y = if stance.crouching { -CROUCH_EYE_DROP } else { 0.0 };
+    let target_x = stance.lean * lean_config.max_offset;
+    let target_fov = if stance.aiming { base_fov.0 * ADS_FOV_SCALE } else { base_fov.0 };
+
+    for child in children.iter() {
+        let Ok((mut transform, mut projection)) = camera_query.get_mut(child) else {
+            continue;
+        };
+        transform.translation.y += (target_y - transform.translation.y) * t;
+        transform.translation.x += (target_x - transform.translation.x) * t;
+        if lean_config.max_offset > 0.0 {
+            let roll = -transform.translation.x / lean_config.max_offset * LEAN_ROLL;
+            transform.rotation *= Quat::from_rotation_z(roll);
         }
-        if input.pressed(KeyCode::ArrowDown) {
-            perspective.fov += 1.0_f32.to_radians();
-            perspective.fov = perspective.fov.min(160.0_f32.to_radians());
+        if let Projection::Perspective(ref mut perspective) = projection.as_mut() {
+            perspective.fov += (target_fov - perspective.fov) * t;
         }
     }
 }
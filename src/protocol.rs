@@ -79,10 +79,24 @@ pub enum PlayerActions {
     Interact,
     /// G → drop equipped item
     Drop,
-    /// Q → left-hand jab (melee)
+    /// Q (--melee-key) → left-hand jab: quick-melee that keeps the held item in hand
     Jab,
     /// Left mouse → primary action (shoot / mine depending on equipped item)
     Primary,
+    /// Right mouse (hold) → aim down sights (weapons only)
+    Aim,
+    /// Left Ctrl (hold) → crouch
+    Crouch,
+    /// Z (hold, configurable) → lean left to peek
+    LeanLeft,
+    /// C (hold, configurable) → lean right to peek
+    LeanRight,
+    /// Left Shift (hold) → sprint, forward only
+    Sprint,
+    /// X (configurable) → throw the selected utility
+    Throw,
+    /// B (configurable) → select the next utility kind in the pouch
+    CycleUtility,
 }
 
 impl Actionlike for PlayerActions {
@@ -99,8 +113,38 @@ impl Actionlike for PlayerActions {
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
 pub struct PlayerEquipped(pub Option<String>);
 
+/// Current stance, derived each tick from the Aim/Crouch inputs by
+/// `shared_stance_system`. Predicted for the owner, interpolated-free for
+/// remote players (values snap).
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct PlayerStance {
+    pub crouching: bool,
+    pub aiming: bool,
+    /// Sideways lean for peeking: -1.0 full left .. 1.0 full right, as a
+    /// fraction of `LeanConfig::max_offset`. Eases in/out over a few ticks.
+    pub lean: f32,
+}
+
+/// Rounds this player has fired, for `WeaponSpec::tracer_every`. Predicted
+/// for the owner, so a rolled-back shot isn't counted twice.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct RoundsFired(pub u32);
+
+/// Sprint stamina left, as a fraction (1.0 = full). Only drains when
+/// `MovementConfig::stamina_secs` is set. Predicted for the owner.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Stamina(pub f32);
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
 /// Player inventory — list of carried item names (weapons, resources, etc).
-/// Server-authoritative, replicated to all clients. The equipped item is NOT
+/// Server-authoritative, replicated to all clients, and predicted for the
+/// owner like PlayerEquipped (pickups move items between the two on both
+/// ends, in shared_equip_interact_system). The equipped item is NOT
 /// in this list — it lives in PlayerEquipped. On death, all items (equipped +
 /// inventory) drop as world Equippable entities at the death position.
 /// These will eventually map to SPL tokens on Solana.
@@ -109,6 +153,19 @@ pub struct PlayerInventory {
     pub items: Vec<String>,
 }
 
+/// Items the server issued from the player's spawn loadout or bought at a
+/// trading station. They never drop into the world and don't tie up the map
+/// pickup of the same name; the spawn loadout is granted again on every
+/// respawn, purchases are not. Server-authoritative, replicated.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct LoadoutItems(pub Vec<String>);
+
+impl LoadoutItems {
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|item| item == name)
+    }
+}
+
 /// Player health. Server-authoritative, replicated to all clients.
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct PlayerHealth(pub i32);
@@ -134,6 +191,8 @@ pub struct LastDamagedBy(pub u64);
 pub struct LastShot {
     pub muzzle: Vec3,
     pub hit_point: Vec3,
+    /// Ricochet points between muzzle and hit_point, in order.
+    pub ricochets: Vec<Vec3>,
     pub tick: u32,
 }
 
@@ -143,6 +202,231 @@ pub struct LastShot {
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
 pub struct PlayerDead;
 
+/// Player is downed: at zero health but not dead yet (see `Ruleset::downed_bleedout`).
+/// Crawls with no weapons until another player revives them by holding
+/// Interact nearby, or the window runs out. Server-authoritative, replicated.
+///
+/// Revive progress uses the same start/last timestamps as `Interactable` mining.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Downed {
+    /// Server time the player went down.
+    pub since: f32,
+    /// Seconds from going down to bleeding out.
+    pub window: f32,
+    /// Time the current revive started. None if nobody is reviving.
+    pub revive_start_secs: Option<f32>,
+    /// Last time a reviver held Interact — used to detect interruption.
+    pub last_revive_secs: Option<f32>,
+    /// Set by the server when the window ran out; the death path takes over.
+    pub bled_out: bool,
+}
+
+impl Downed {
+    /// Revive progress in seconds (0.0 when nobody is reviving).
+    pub fn revive_progress(&self, current_secs: f32) -> f32 {
+        self.revive_start_secs.map(|start| current_secs - start).unwrap_or(0.0)
+    }
+}
+
+/// Marker: server-controlled bot player (spawned by bot auto-balance).
+/// Server-authoritative, replicated so clients can label bots.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Bot;
+
+/// Marker: player is standing in a `DamageVolume` and taking damage.
+/// Server-authoritative, replicated so clients can show the hazard effect.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct InHazard;
+
+/// Marker: this player opted into auto-equip (see `PlayerSettings::auto_equip`).
+/// Set by the server from the client's `PlayerPreferencesMessage`; replicated
+/// so the owning client predicts pickups the same way.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct AutoEquip;
+
+/// Marker: this player opted into crouching automatically while aiming down
+/// sights (see `PlayerSettings::auto_crouch_ads`). Set by the server from the
+/// client's `PlayerPreferencesMessage`.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct AutoCrouchAds;
+
+/// Marker: player died during an elimination round and is out (spectating)
+/// until the round ends. Server-authoritative, replicated.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Eliminated;
+
+/// Round state for round-based rulesets (elimination). The server spawns one
+/// replicated entity with this component when such a ruleset is active.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct MatchState {
+    /// Current round number, starting at 1.
+    pub round: u32,
+    /// True between a round being decided and the next one starting.
+    pub intermission: bool,
+    /// Base58 name of the last round's winner, or the winning team's name
+    /// under CTF (None for a draw / no round yet).
+    pub last_winner: Option<String>,
+}
+
+/// Marker: a referee paused the match. The server spawns one replicated entity
+/// with this component while paused; the shared gameplay chain does not run
+/// (on the server or in client prediction) while it exists.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct MatchPaused;
+
+/// Team for team-based rulesets (capture the flag). Assigned by the server on
+/// spawn to whichever team is smaller. Server-authoritative, replicated.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Team {
+    Red,
+    Blue,
+}
+
+impl Team {
+    pub const ALL: [Team; 2] = [Team::Red, Team::Blue];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Team::Red => "RED",
+            Team::Blue => "BLUE",
+        }
+    }
+}
+
+/// Capture-the-flag score. The server spawns one replicated entity with this
+/// component when the CTF ruleset is active.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CtfScore {
+    pub red: u32,
+    pub blue: u32,
+}
+
+impl CtfScore {
+    pub fn get(&self, team: Team) -> u32 {
+        match team {
+            Team::Red => self.red,
+            Team::Blue => self.blue,
+        }
+    }
+
+    pub fn add(&mut self, team: Team) {
+        match team {
+            Team::Red => self.red += 1,
+            Team::Blue => self.blue += 1,
+        }
+    }
+}
+
+/// Marker: the headshot-only training ruleset is active. The server spawns
+/// one replicated entity with this component.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct HeadshotOnly;
+
+/// Per-player aim-training counters for the headshot-only mode. The server
+/// counts every gunshot; clients derive hit/miss feedback from the changes.
+/// Server-authoritative, replicated.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct TrainingStats {
+    pub shots: u32,
+    pub headshots: u32,
+    /// Body hits — registered but ignored (no damage).
+    pub body_hits: u32,
+}
+
+impl TrainingStats {
+    /// Headshots as a percentage of shots fired.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots == 0 {
+            return 0.0;
+        }
+        self.headshots as f32 / self.shots as f32 * 100.0
+    }
+}
+
+/// Marker: the hardcore ruleset is active (any hit kills, minimal HUD).
+/// The server spawns one replicated entity with this component.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Hardcore;
+
+/// The server's movement rules. The server spawns one replicated entity with
+/// this component; clients copy it into their `MovementConfig` so prediction
+/// runs the same rules.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ServerMovementConfig(pub crate::player::MovementConfig);
+
+/// The server's ADS rules, replicated like `ServerMovementConfig`; clients
+/// copy it into their `AdsConfig`.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ServerAdsConfig(pub crate::player::AdsConfig);
+
+/// The server's lean limits, replicated like `ServerMovementConfig`; clients
+/// copy it into their `LeanConfig`.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ServerLeanConfig(pub crate::player::LeanConfig);
+
+/// Marker: the ricochet ruleset is active (rounds bounce off walls, up to
+/// `WeaponSpec::ricochets`). The server spawns one replicated entity with
+/// this component, so predicted tracers bounce the same way.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Ricochets;
+
+/// Wave respawns are active: dead players respawn together every
+/// `interval_secs`. The server spawns one replicated entity with this
+/// component and keeps `next_in_secs` (whole seconds until the next wave)
+/// current, so death screens can count down without a synced clock.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct RespawnWave {
+    pub interval_secs: f32,
+    pub next_in_secs: u32,
+}
+
+/// Non-default per-player respawn delay (`--respawn-delay`). The server spawns
+/// one replicated entity with this component so death screens count down
+/// the right time; without it clients assume `rules::DEFAULT_RESPAWN_DELAY`.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct RespawnDelay {
+    pub secs: f32,
+}
+
+/// Lifetime statistics for this player, loaded from the server's stats file
+/// on connect (keyed by client id, which is derived from the persistent keypair).
+/// Server-authoritative, replicated.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)] // stats files written before a field existed still load
+pub struct PlayerStats {
+    pub kills: u32,
+    pub deaths: u32,
+    /// Kills this player damaged the victim for (within the assist window)
+    /// without landing the final hit.
+    pub assists: u32,
+    /// Elimination rounds won (last player standing).
+    pub wins: u32,
+    pub ore_mined: u32,
+    /// Meters walked/fallen while alive.
+    pub distance_traveled: f32,
+}
+
+impl PlayerStats {
+    /// Scoreboard score: kills and round wins count most, assists half a kill.
+    pub fn score(&self) -> u32 {
+        self.kills * 100 + self.assists * 50 + self.wins * 200
+    }
+}
+
+/// Round-trip time to the server in milliseconds, as measured by the server
+/// on this player's link. Refreshed about once a second; 0 for bots.
+/// Server-authoritative, replicated for the scoreboard.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct PlayerPing(pub u32);
+
+/// Raw materials a player has gathered, for crafting/purchasing later.
+/// Unlike `PlayerInventory` these are counters, and they are kept on death.
+/// Server-authoritative, replicated.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct Resources {
+    pub ore: u32,
+}
+
 /// Kill feed entry. Server-authoritative, replicated to all clients.
 /// Stores truncated base58 addresses for display.
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -152,6 +436,113 @@ pub struct KillFeedEntry {
     pub timestamp: f32,
 }
 
+/// Kill-streak announcement for the kill feed. Spawned by the server when a
+/// player reaches a rewarded streak; replicated to all clients.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct KillStreakEntry {
+    pub player_name: String,
+    pub streak: u32,
+    /// Human-readable reward, e.g. "+50 health".
+    pub reward: String,
+    pub timestamp: f32,
+}
+
+/// Kills in a row without dying. Server-authoritative, replicated.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct KillStreak(pub u32);
+
+/// Kill-streak reward: move speed multiplied while present. The server
+/// removes it at `expires_at` (server time); clients only read `multiplier`.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct SpeedBoost {
+    pub multiplier: f32,
+    pub expires_at: f32,
+}
+
+// --- Channel Priorities ---
+//
+// When more messages are queued than fit in a packet, lightyear sends the
+// highest-priority channels first. Keep gameplay-critical traffic on its own
+// channels above chat/cosmetic ones, so a burst of low-priority messages
+// can only delay other low-priority messages.
+
+/// Auth and referee control — must never wait behind anything else.
+pub const PRIORITY_CRITICAL: f32 = 10.0;
+/// Messages that change how the simulation treats a player (preferences, ...).
+pub const PRIORITY_GAMEPLAY: f32 = 5.0;
+/// Chat and other cosmetic traffic.
+pub const PRIORITY_COSMETIC: f32 = 1.0;
+
+/// Delivery settings for the message channels, in one place. Only discrete
+/// events and requests travel on these; entity state (positions, health, ...)
+/// goes through lightyear replication and never queues behind them.
+///
+/// `ProtocolPlugin` registers the channels with the `NetworkTuning` resource
+/// if one was inserted before it (see `SharedPlugin`), else with the
+/// defaults. Client and server must use the same tuning.
+#[derive(Resource, Clone, Debug)]
+pub struct NetworkTuning {
+    /// Delivery for event and request channels (auth, preferences, chat,
+    /// trades, ...). Reliable and ordered by default: each message arrives
+    /// exactly once, in send order, and unacked packets are resent per the
+    /// `ReliableSettings` inside.
+    pub message_mode: ChannelMode,
+    /// Time between flushes of a channel's queue. Zero flushes every frame.
+    pub send_frequency: Duration,
+}
+
+impl Default for NetworkTuning {
+    fn default() -> Self {
+        Self {
+            message_mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
+            send_frequency: Duration::default(),
+        }
+    }
+}
+
+impl NetworkTuning {
+    /// Settings for a message channel, with `priority` deciding who waits
+    /// when bandwidth runs short.
+    pub fn message_channel(&self, priority: f32) -> ChannelSettings {
+        ChannelSettings {
+            mode: self.message_mode.clone(),
+            send_frequency: self.send_frequency,
+            priority,
+        }
+    }
+
+    /// Settings for `NetProbeChannel`. Always unreliable, whatever the
+    /// message mode: probes that get resent can't measure loss.
+    pub fn probe_channel(&self) -> ChannelSettings {
+        ChannelSettings {
+            mode: ChannelMode::UnorderedUnreliable,
+            send_frequency: self.send_frequency,
+            priority: PRIORITY_COSMETIC,
+        }
+    }
+}
+
+/// Lightyear channel reserved for chat and other cosmetic messages, at the
+/// lowest priority so floods cannot stall gameplay channels.
+pub struct ChatChannel;
+
+/// Longest chat line the server relays (characters); longer lines are cut.
+pub const MAX_CHAT_LEN: usize = 160;
+
+/// Client → Server: one line typed into the chat box.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChatMessage {
+    pub text: String,
+}
+
+/// Server → Client: a chat line relayed to everyone, sender included.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChatBroadcast {
+    /// `PlayerDisplayId` of the sender.
+    pub sender: u32,
+    pub text: String,
+}
+
 // --- Wallet Auth (Solana Challenge-Response) ---
 
 /// Lightyear channel for wallet authentication messages.
@@ -176,12 +567,136 @@ pub struct WalletAuthMessage {
     pub signature: Vec<u8>,
 }
 
+// --- Player Preferences ---
+
+/// Lightyear channel for gameplay preferences the server needs to know about.
+pub struct PreferencesChannel;
+
+/// Client → Server: gameplay preferences, sent on connect and whenever they change.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlayerPreferencesMessage {
+    pub auto_equip: bool,
+    pub auto_crouch_ads: bool,
+}
+
+// --- Server Info ---
+
+/// Lightyear channel for informational server → client messages.
+pub struct ServerInfoChannel;
+
+/// Server → Client: sent once on connect. The client shows it in a
+/// dismissible panel unless both fields are empty.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MotdMessage {
+    /// Message of the day (`--motd`), if the server has one.
+    pub text: Option<String>,
+    /// One line per active rule (see `Ruleset::summary`).
+    pub rules: Vec<String>,
+}
+
+/// Server → Client: we have been idle and will be kicked in `secs_left`
+/// seconds unless we move, look around or press something (`--afk-timeout`).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AfkWarningMessage {
+    pub secs_left: u32,
+}
+
+// --- Trading ---
+
+/// Lightyear channel for purchases at trading stations.
+pub struct TradeChannel;
+
+/// Client → Server: buy `trade::TRADE_OFFERS[offer]` at a nearby station.
+/// The server checks range, price and that the buyer is alive.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TradeRequestMessage {
+    pub offer: u32,
+}
+
+// --- Inventory ---
+
+/// Lightyear channel for inventory requests.
+pub struct InventoryChannel;
+
+/// Client → Server: drop `count` ore from our `Resources` as one loose ore
+/// chunk in front of us. The server drops at most what we have.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DropOreMessage {
+    pub count: u32,
+}
+
+// --- Observer / Referee ---
+
+/// Lightyear channel for observer login and admin commands.
+pub struct ObserverChannel;
+
+/// Client → Server: request observer access with a token from the server's
+/// `--observer-tokens` list. Sent right after connecting.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ObserverLoginMessage {
+    pub token: String,
+}
+
+/// Server → Client: answer to `ObserverLoginMessage`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ObserverStatusMessage {
+    pub granted: bool,
+}
+
+/// Referee actions an observer can trigger.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdminCommand {
+    /// Freeze gameplay for everyone (see `MatchPaused`).
+    Pause,
+    Resume,
+    /// Revive and respawn every player and replay the current round.
+    RestartRound,
+}
+
+/// Client → Server: admin command. Ignored unless the sender is an observer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AdminCommandMessage(pub AdminCommand);
+
+// --- Team swap ---
+
+/// Lightyear channel for team swap requests and the server's answers.
+pub struct TeamChannel;
+
+/// Client → Server: move me to the other team. Only honored in team rulesets
+/// and when the swap keeps the teams balanced (see `rules::swap_keeps_balance`).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TeamSwapRequestMessage;
+
+/// Server → Client: answer to `TeamSwapRequestMessage`, with a line to show.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TeamSwapReplyMessage {
+    pub accepted: bool,
+    pub text: String,
+}
+
+// --- Network probes ---
+
+/// Lightyear channel for packet-loss probes (`--net-diagnostics`). Unreliable
+/// on purpose: a probe that doesn't come back is a lost packet.
+pub struct NetProbeChannel;
+
+/// Either direction: probe number `seq`, sent by a side exporting network
+/// diagnostics. The other side answers with `NetProbeEcho`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct NetProbe(pub u32);
+
+/// Either direction: answer to `NetProbe` with the same number.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct NetProbeEcho(pub u32);
+
 // --- Protocol Plugin ---
 
 pub struct ProtocolPlugin;
 
 impl Plugin for ProtocolPlugin {
     fn build(&self, app: &mut App) {
+        let tuning = app.world().get_resource::<NetworkTuning>().cloned().unwrap_or_default();
+
         // Leafwing input replication via lightyear. ActionState<PlayerActions>
         // is captured each tick on the client, buffered + sent to the server,
         // and restored during rollback — which BEI's Fire<Action> observers
@@ -214,13 +729,48 @@ impl Plugin for ProtocolPlugin {
             .enable_correction();
         app.register_component::<PlayerEquipped>()
             .add_prediction();
-        app.register_component::<PlayerInventory>();
+        app.register_component::<PlayerStance>()
+            .add_prediction();
+        app.register_component::<Stamina>()
+            .add_prediction();
+        app.register_component::<RoundsFired>()
+            .add_prediction();
+        app.register_component::<PlayerInventory>()
+            .add_prediction();
+        app.register_component::<LoadoutItems>();
         app.register_component::<PlayerHealth>();
         app.register_component::<LastShot>();
         app.register_component::<PlayerDisplayId>();
         app.register_component::<LastDamagedBy>();
         app.register_component::<PlayerDead>();
         app.register_component::<KillFeedEntry>();
+        app.register_component::<KillStreakEntry>();
+        app.register_component::<KillStreak>();
+        app.register_component::<PlayerPing>();
+        app.register_component::<Resources>();
+        app.register_component::<SpeedBoost>();
+        app.register_component::<PlayerStats>();
+        app.register_component::<InHazard>();
+        app.register_component::<Downed>();
+        app.register_component::<Bot>();
+        app.register_component::<AutoEquip>();
+        app.register_component::<AutoCrouchAds>();
+        app.register_component::<Eliminated>();
+        app.register_component::<MatchState>();
+        app.register_component::<MatchPaused>();
+        app.register_component::<Hardcore>();
+        app.register_component::<Ricochets>();
+        app.register_component::<ServerMovementConfig>();
+        app.register_component::<ServerAdsConfig>();
+        app.register_component::<ServerLeanConfig>();
+        app.register_component::<RespawnWave>();
+        app.register_component::<RespawnDelay>();
+        app.register_component::<HeadshotOnly>();
+        app.register_component::<TrainingStats>();
+        app.register_component::<Team>();
+        app.register_component::<CtfScore>();
+        app.register_component::<crate::utility::UtilityPouch>();
+        app.register_component::<crate::utility::Flashed>();
 
         // Avian3d physics components with prediction + interpolation.
         // enable_correction() lets lightyear handle smooth corrections on Transform
@@ -247,6 +797,12 @@ impl Plugin for ProtocolPlugin {
         app.register_component::<crate::world::DoorState>();
         app.register_component::<crate::world::Equippable>();
         app.register_component::<crate::world::Interactable>();
+        app.register_component::<crate::world::DamageVolume>();
+        app.register_component::<crate::world::WeaponSpawn>();
+        app.register_component::<crate::world::Flag>();
+        app.register_component::<crate::world::TradeStation>();
+        app.register_component::<crate::utility::ThrownUtility>();
+        app.register_component::<crate::utility::SmokeCloud>();
 
         // Solana wallet address — attached to player entity after auth verification
         app.register_component::<crate::solana::WalletAddress>();
@@ -255,15 +811,81 @@ impl Plugin for ProtocolPlugin {
         // Reliable ordered channel for auth handshake.
         // Client sends WalletAuthMessage immediately after connection.
         // Server verifies and maps pubkey → player entity.
-        app.add_channel::<AuthChannel>(ChannelSettings {
-            mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
-            send_frequency: Duration::default(),
-            priority: 10.0,
-        })
-        .add_direction(NetworkDirection::Bidirectional);
+        app.add_channel::<AuthChannel>(tuning.message_channel(PRIORITY_CRITICAL))
+            .add_direction(NetworkDirection::Bidirectional);
 
         app.register_message::<WalletAuthMessage>()
             .add_direction(NetworkDirection::ClientToServer);
+
+        // --- Preferences Channel + Message ---
+        app.add_channel::<PreferencesChannel>(tuning.message_channel(PRIORITY_GAMEPLAY))
+            .add_direction(NetworkDirection::ClientToServer);
+
+        app.register_message::<PlayerPreferencesMessage>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        // --- Server Info Channel + Message ---
+        app.add_channel::<ServerInfoChannel>(tuning.message_channel(PRIORITY_COSMETIC))
+            .add_direction(NetworkDirection::ServerToClient);
+
+        app.register_message::<MotdMessage>()
+            .add_direction(NetworkDirection::ServerToClient);
+        app.register_message::<AfkWarningMessage>()
+            .add_direction(NetworkDirection::ServerToClient);
+
+        // --- Trade Channel + Message ---
+        app.add_channel::<TradeChannel>(tuning.message_channel(PRIORITY_GAMEPLAY))
+            .add_direction(NetworkDirection::ClientToServer);
+
+        app.register_message::<TradeRequestMessage>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        // --- Inventory Channel + Message ---
+        app.add_channel::<InventoryChannel>(tuning.message_channel(PRIORITY_GAMEPLAY))
+            .add_direction(NetworkDirection::ClientToServer);
+
+        app.register_message::<DropOreMessage>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        // --- Observer Channel + Messages ---
+        app.add_channel::<ObserverChannel>(tuning.message_channel(PRIORITY_CRITICAL))
+            .add_direction(NetworkDirection::Bidirectional);
+
+        app.register_message::<ObserverLoginMessage>()
+            .add_direction(NetworkDirection::ClientToServer);
+        app.register_message::<ObserverStatusMessage>()
+            .add_direction(NetworkDirection::ServerToClient);
+        app.register_message::<AdminCommandMessage>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        // --- Chat Channel ---
+        // Reliable (chat must arrive) but lowest priority.
+        app.add_channel::<ChatChannel>(tuning.message_channel(PRIORITY_COSMETIC))
+            .add_direction(NetworkDirection::Bidirectional);
+
+        app.register_message::<ChatMessage>()
+            .add_direction(NetworkDirection::ClientToServer);
+        app.register_message::<ChatBroadcast>()
+            .add_direction(NetworkDirection::ServerToClient);
+
+        // --- Team Channel ---
+        app.add_channel::<TeamChannel>(tuning.message_channel(PRIORITY_GAMEPLAY))
+            .add_direction(NetworkDirection::Bidirectional);
+
+        app.register_message::<TeamSwapRequestMessage>()
+            .add_direction(NetworkDirection::ClientToServer);
+        app.register_message::<TeamSwapReplyMessage>()
+            .add_direction(NetworkDirection::ServerToClient);
+
+        // --- Network probe Channel ---
+        // Unreliable, unlike every message channel: lost probes measure loss.
+        app.add_channel::<NetProbeChannel>(tuning.probe_channel())
+            .add_direction(NetworkDirection::Bidirectional);
+
+        app.register_message::<NetProbe>()
+            .add_direction(NetworkDirection::Bidirectional);
+        app.register_message::<NetProbeEcho>()
+            .add_direction(NetworkDirection::Bidirectional);
     }
 }
 
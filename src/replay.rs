@@ -0,0 +1,277 @@
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::PlayerActions;
+
+/// One player's input on one tick, as the server applied it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecordedInput {
+    pub player: u64,
+    pub move_axis: Vec2,
+    pub look: Vec2,
+    /// Button actions held this tick.
+    pub pressed: Vec<PlayerActions>,
+}
+
+impl RecordedInput {
+    pub fn capture(player: u64, action: &ActionState<PlayerActions>) -> Self {
+        Self {
+            player,
+            move_axis: action.axis_pair(&PlayerActions::Move),
+            look: action.axis_pair(&PlayerActions::Look),
+            pressed: action.get_pressed(),
+        }
+    }
+
+    /// Put this input on `action`: held buttons pressed, the rest released.
+    pub fn apply_to(&self, action: &mut ActionState<PlayerActions>) {
+        for held in action.get_pressed() {
+            if !self.pressed.contains(&held) {
+                action.release(&held);
+            }
+        }
+        for pressed in &self.pressed {
+            action.press(pressed);
+        }
+        action.set_axis_pair(&PlayerActions::Move, self.move_axis);
+        action.set_axis_pair(&PlayerActions::Look, self.look);
+    }
+}
+
+/// A player as first seen by the recorder, with their state at the end of
+/// `first_tick`. The replay spawns them with it and applies their inputs
+/// from the next tick on.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecordedPlayer {
+    pub player: u64,
+    pub first_tick: u32,
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// Server-side input recording (--record-inputs): every player's input per
+/// fixed tick. Replaying it with --replay-inputs on a headless server in
+/// --deterministic stepping reproduces the match without any clients.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct InputRecording {
+    pub players: Vec<RecordedPlayer>,
+    pub ticks: Vec<Vec<RecordedInput>>,
+}
+
+/// One line of a recording file. The file is JSON Lines, appended to as the
+/// match runs: a `Player` line when someone is first seen, then a `Tick`
+/// line per fixed tick.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+enum RecordingLine {
+    Player(RecordedPlayer),
+    Tick(Vec<RecordedInput>),
+}
+
+impl InputRecording {
+    /// Read a recording file. A last line that doesn't parse is dropped: it
+    /// is the tick being written when the server died.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&data).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    fn parse(data: &str) -> Result<Self, String> {
+        let mut recording = Self::default();
+        let lines: Vec<&str> = data.lines().filter(|line| !line.trim().is_empty()).collect();
+        for (index, line) in lines.iter().enumerate() {
+            match serde_json::from_str::<RecordingLine>(line) {
+                Ok(RecordingLine::Player(player)) => recording.players.push(player),
+                Ok(RecordingLine::Tick(inputs)) => recording.ticks.push(inputs),
+                Err(_) if index + 1 == lines.len() => break,
+                Err(e) => return Err(format!("line {}: {}", index + 1, e)),
+            }
+        }
+        Ok(recording)
+    }
+}
+
+/// Writes a recording as it happens: each tick is appended to the file, so
+/// nothing is re-serialized and an interrupted session loses at most what
+/// is still buffered. Call `flush` on shutdown.
+pub struct RecordingWriter {
+    file: BufWriter<fs::File>,
+    /// Players already written, so each gets one `Player` line.
+    players: Vec<u64>,
+    /// Ticks written so far.
+    ticks: u32,
+}
+
+impl RecordingWriter {
+    /// Start a new recording at `path`, replacing any file already there.
+    pub fn create(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let file = fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        Ok(Self { file: BufWriter::new(file), players: Vec::new(), ticks: 0 })
+    }
+
+    /// Record `player` as first seen on the tick being recorded (the next
+    /// `push_tick`), unless already known.
+    pub fn see_player(&mut self, player: u64, position: Vec3, yaw: f32, pitch: f32) -> Result<(), String> {
+        if self.players.contains(&player) {
+            return Ok(());
+        }
+        self.players.push(player);
+        let first_tick = self.ticks;
+        self.write_line(&RecordingLine::Player(RecordedPlayer { player, first_tick, position, yaw, pitch }))
+    }
+
+    /// Append one tick of inputs.
+    pub fn push_tick(&mut self, inputs: Vec<RecordedInput>) -> Result<(), String> {
+        self.ticks += 1;
+        self.write_line(&RecordingLine::Tick(inputs))
+    }
+
+    /// Push everything buffered so far out to the file.
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.file.flush().map_err(|e| format!("Failed to write recording: {}", e))
+    }
+
+    fn write_line(&mut self, line: &RecordingLine) -> Result<(), String> {
+        serde_json::to_writer(&mut self.file, line).map_err(|e| format!("Failed to write recording: {}", e))?;
+        self.file.write_all(b"\n").map_err(|e| format!("Failed to write recording: {}", e))
+    }
+}
+
+/// --record-inputs <path> or --replay-inputs <path>.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplayMode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// Parse --record-inputs <path> / --replay-inputs <path> from CLI args.
+/// Replaying wins if both are given. None when neither is.
+pub fn parse_replay_mode() -> Option<ReplayMode> {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+            .map(PathBuf::from)
+    };
+    value("--replay-inputs")
+        .map(ReplayMode::Replay)
+        .or_else(|| value("--record-inputs").map(ReplayMode::Record))
+}
+
+/// Byte-exact physics state of every player: id, position, velocity, yaw
+/// and pitch as raw little-endian bits, in id order. Two replays are
+/// deterministic when these bytes are identical.
+pub fn state_bytes(players: impl Iterator<Item = (u64, Vec3, Vec3, f32, f32)>) -> Vec<u8> {
+    let mut players: Vec<_> = players.collect();
+    players.sort_by_key(|(id, ..)| *id);
+    let mut bytes = Vec::new();
+    for (id, position, velocity, yaw, pitch) in players {
+        bytes.extend_from_slice(&id.to_le_bytes());
+        for v in position.to_array().into_iter().chain(velocity.to_array()).chain([yaw, pitch]) {
+            bytes.extend_from_slice(&v.to_bits().to_le_bytes());
+        }
+    }
+    bytes
+}
+
+/// Where a replay keeps the final state of its first run, next to the recording.
+pub fn state_path(recording: &Path) -> PathBuf {
+    recording.with_extension("state")
+}
+
+/// Outcome of comparing a replay's final state with the first run's.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeterminismCheck {
+    /// No earlier run: this state is now the reference.
+    Recorded,
+    /// Byte-identical to the reference.
+    Identical,
+    /// Differs from the reference, first at this byte offset.
+    Diverged { at: usize },
+}
+
+/// Compare `state` with the reference bytes from an earlier replay.
+pub fn compare_states(reference: &[u8], state: &[u8]) -> DeterminismCheck {
+    match reference.iter().zip(state).position(|(a, b)| a != b) {
+        Some(at) => DeterminismCheck::Diverged { at },
+        None if reference.len() != state.len() => DeterminismCheck::Diverged { at: reference.len().min(state.len()) },
+        None => DeterminismCheck::Identical,
+    }
+}
+
+/// Check `state` against the reference stored next to `recording`, or store
+/// it as the reference if there is none yet.
+pub fn check_determinism(recording: &Path, state: &[u8]) -> Result<DeterminismCheck, String> {
+    let path = state_path(recording);
+    match fs::read(&path) {
+        Ok(reference) => Ok(compare_states(&reference, state)),
+        Err(_) => {
+            fs::write(&path, state).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            Ok(DeterminismCheck::Recorded)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_bytes_ignore_query_order() {
+        let a = (1, Vec3::new(1.0, 2.0, 3.0), Vec3::ZERO, 0.5, -0.1);
+        let b = (2, Vec3::new(-4.0, 0.0, 8.0), Vec3::X, 1.5, 0.2);
+        assert_eq!(state_bytes([a, b].into_iter()), state_bytes([b, a].into_iter()));
+        assert_eq!(state_bytes([a].into_iter()).len(), 8 + 8 * 4);
+    }
+
+    #[test]
+    fn test_compare_states() {
+        let reference = state_bytes([(7, Vec3::ONE, Vec3::ZERO, 0.0, 0.0)].into_iter());
+        assert_eq!(compare_states(&reference, &reference), DeterminismCheck::Identical);
+
+        // The tiniest float difference is a divergence
+        let nudged = state_bytes([(7, Vec3::new(1.0 + f32::EPSILON, 1.0, 1.0), Vec3::ZERO, 0.0, 0.0)].into_iter());
+        assert_eq!(compare_states(&reference, &nudged), DeterminismCheck::Diverged { at: 8 });
+        assert_eq!(compare_states(&reference, &reference[..8]), DeterminismCheck::Diverged { at: 8 });
+    }
+
+    #[test]
+    fn test_recording_round_trip() {
+        let path = std::env::temp_dir().join(format!("fps-recording-{}.jsonl", std::process::id()));
+        let input = RecordedInput {
+            player: 42,
+            move_axis: Vec2::new(0.0, -1.0),
+            look: Vec2::ZERO,
+            pressed: vec![PlayerActions::Jump, PlayerActions::Primary],
+        };
+        let mut writer = RecordingWriter::create(&path).unwrap();
+        writer.see_player(42, Vec3::new(1.0, 0.0, -2.0), 0.3, 0.0).unwrap();
+        writer.push_tick(vec![input.clone()]).unwrap();
+        // Seen again later: keeps the first sighting
+        writer.see_player(42, Vec3::ZERO, 0.0, 0.0).unwrap();
+        writer.push_tick(vec![input.clone()]).unwrap();
+        writer.flush().unwrap();
+
+        let recording = InputRecording::load(&path).unwrap();
+        assert_eq!(recording.players.len(), 1);
+        assert_eq!(recording.players[0].first_tick, 0);
+        assert_eq!(recording.ticks, vec![vec![input.clone()], vec![input]]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_recording_ignores_truncated_last_line() {
+        let data = "{\"Tick\":[]}\n{\"Tick\":[]}\n{\"Tick\":[{\"pla";
+        assert_eq!(InputRecording::parse(data).unwrap().ticks.len(), 2);
+        assert!(InputRecording::parse("{\"Tick\":[{\"pla\n{\"Tick\":[]}").is_err());
+    }
+}
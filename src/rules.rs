@@ -0,0 +1,500 @@
+//! Match rules — server-side gameplay variants selected with CLI flags.
+//!
+//! Clients learn about the active rules through replicated state (e.g.
+//! `MatchState`, `Eliminated`), never by parsing the flags themselves.
+
+use bevy::prelude::*;
+
+use crate::protocol::{LoadoutItems, PlayerEquipped, PlayerInventory, Team};
+
+/// Seconds a dead player waits before respawning, unless `--respawn-delay` says otherwise.
+pub const DEFAULT_RESPAWN_DELAY: f32 = 20.0;
+
+/// Seconds between a round being decided and the next round starting.
+pub const ROUND_INTERMISSION: f32 = 5.0;
+
+/// Team swaps are refused if they would leave the teams further apart in
+/// size than this, unless `--team-swap-imbalance` says otherwise.
+pub const DEFAULT_TEAM_SWAP_IMBALANCE: u32 = 1;
+
+/// Capture the flag: how close a player must get to a flag to take/return it,
+/// and to their own base to score (meters).
+pub const FLAG_TOUCH_RADIUS: f32 = 1.5;
+pub const FLAG_CAPTURE_RADIUS: f32 = 2.5;
+/// Seconds a dropped flag lies on the ground before returning to its base.
+pub const FLAG_RETURN_SECS: f32 = 30.0;
+
+/// Seconds of holding Interact next to a downed player to revive them.
+pub const REVIVE_TIME: f32 = 3.0;
+/// How close a reviver must stand to the downed player (meters).
+pub const REVIVE_DISTANCE: f32 = 2.0;
+/// Health a revived player gets back.
+pub const REVIVE_HEALTH: i32 = 30;
+
+/// Active ruleset for this server.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Ruleset {
+    /// Last man standing: players who die are out (spectating) until the round
+    /// ends. The round ends when at most one player is left alive; that player
+    /// gets a win and everyone respawns for the next round.
+    pub elimination: bool,
+    /// Players at zero health are downed instead of killed, and bleed out
+    /// after this many seconds unless someone revives them. None disables.
+    pub downed_bleedout: Option<f32>,
+    /// Any hit kills, and clients hide the health bar and respawn countdown.
+    /// Announced to clients through a replicated `Hardcore` entity.
+    pub hardcore: bool,
+    /// Capture the flag: players are split into teams, each with a flag at
+    /// its base. Carry the enemy flag to your own base (while your flag is
+    /// home) to score.
+    pub ctf: bool,
+    /// Aim training: only headshots deal damage; bots are stationary or
+    /// strafing targets. Announced through a replicated `HeadshotOnly` entity.
+    pub headshot_only: bool,
+    /// Wave respawns: instead of individual timers, dead players respawn
+    /// together every this many seconds. None uses the per-player delay.
+    /// Announced through a replicated `RespawnWave` entity.
+    pub respawn_wave: Option<f32>,
+    /// Per-player respawn delay in seconds. None uses `DEFAULT_RESPAWN_DELAY`.
+    /// Announced through a replicated `RespawnDelay` entity.
+    pub respawn_delay: Option<f32>,
+    /// Largest team size difference a team swap may leave behind. None uses
+    /// `DEFAULT_TEAM_SWAP_IMBALANCE`.
+    pub team_swap_imbalance: Option<u32>,
+    /// Players who disconnect drop their items where they stood, as if they
+    /// had died, instead of taking them with them.
+    pub drop_on_disconnect: bool,
+    /// Where the ore from a mined-out vein goes.
+    pub ore_drop: OreDrop,
+    /// Rounds stopped by a wall bounce off it, as often as the weapon's
+    /// `WeaponSpec::ricochets` allows. Off by default.
+    /// Announced through a replicated `Ricochets` entity.
+    pub ricochets: bool,
+}
+
+/// What mining out an ore vein produces (`--ore-drop`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OreDrop {
+    /// Straight into the miner's `Resources`.
+    #[default]
+    Inventory,
+    /// A loose ore chunk at the vein; whoever picks it up gets the ore.
+    Chunk,
+    /// Both: the miner is credited and a chunk drops as well.
+    Both,
+}
+
+impl OreDrop {
+    /// Parse an `--ore-drop` value (`inventory`, `chunk`, `both`).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "inventory" => Some(Self::Inventory),
+            "chunk" => Some(Self::Chunk),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    /// The miner's `Resources` are credited.
+    pub fn credits_miner(self) -> bool {
+        matches!(self, Self::Inventory | Self::Both)
+    }
+
+    /// A loose chunk drops at the vein.
+    pub fn drops_chunk(self) -> bool {
+        matches!(self, Self::Chunk | Self::Both)
+    }
+}
+
+impl Ruleset {
+    /// One human-readable line per active rule, for the join message.
+    /// Empty for default free-for-all rules.
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.elimination {
+            lines.push("Elimination: dead players sit out until the round ends".to_string());
+        }
+        if let Some(secs) = self.downed_bleedout {
+            lines.push(format!("Downed: zero health downs you; teammates have {:.0}s to revive", secs));
+        }
+        if self.hardcore {
+            lines.push("Hardcore: any hit kills, no health readout".to_string());
+        }
+        if self.ctf {
+            lines.push("Capture the flag: bring the enemy flag to your base (type /team in chat to switch sides)".to_string());
+        }
+        if self.headshot_only {
+            lines.push("Aim training: only headshots count".to_string());
+        }
+        if self.drop_on_disconnect {
+            lines.push("Leavers drop their gear".to_string());
+        }
+        if self.ricochets {
+            lines.push("Ricochets: rounds bounce off walls".to_string());
+        }
+        match self.ore_drop {
+            OreDrop::Inventory => {}
+            OreDrop::Chunk => lines.push("Mined ore drops as a chunk for anyone to pick up".to_string()),
+            OreDrop::Both => lines.push("Mining pays twice: your ore plus a chunk on the ground".to_string()),
+        }
+        if let Some(secs) = self.respawn_wave {
+            lines.push(format!("Wave respawns every {:.0}s", secs));
+        } else if let Some(secs) = self.respawn_delay {
+            lines.push(format!("Respawn after {:.0}s", secs));
+        }
+        lines
+    }
+
+    /// Seconds a dead player waits before respawning (outside wave respawns).
+    pub fn respawn_delay(&self) -> f32 {
+        self.respawn_delay.unwrap_or(DEFAULT_RESPAWN_DELAY)
+    }
+
+    /// Largest team size difference a team swap may leave behind.
+    pub fn team_swap_imbalance(&self) -> u32 {
+        self.team_swap_imbalance.unwrap_or(DEFAULT_TEAM_SWAP_IMBALANCE)
+    }
+}
+
+/// Server-only: message of the day sent to each joining client together with
+/// the rule summary. Set with `--motd <text>` or `--motd-file <path>`.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ServerMotd(pub Option<String>);
+
+/// Seconds before an AFK kick that the player is warned, unless
+/// `--afk-warning` says otherwise.
+pub const DEFAULT_AFK_WARNING: f32 = 15.0;
+
+/// Server-only anti-AFK: players who send no input for `timeout` seconds
+/// are kicked, after a warning `warning` seconds before. Absent when off
+/// (the default).
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct AfkConfig {
+    pub timeout: f32,
+    pub warning: f32,
+}
+
+/// Parse --afk-timeout <secs> and --afk-warning <secs> from CLI args.
+pub fn parse_afk_config() -> Option<AfkConfig> {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|v| *v > 0.0)
+    };
+    let timeout = value("--afk-timeout")?;
+    let warning = value("--afk-warning").unwrap_or(DEFAULT_AFK_WARNING).min(timeout);
+    info!("[AFK] Kicking players idle for {}s (warned {}s before)", timeout, warning);
+    Some(AfkConfig { timeout, warning })
+}
+
+/// Parse --motd <text> and --motd-file <path> from CLI args (the file wins).
+pub fn parse_motd() -> ServerMotd {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+    };
+    let from_file = value("--motd-file").and_then(|path| match std::fs::read_to_string(path) {
+        Ok(text) => Some(text.trim().to_string()),
+        Err(e) => {
+            warn!("[MOTD] Failed to read {}: {}", path, e);
+            None
+        }
+    });
+    ServerMotd(from_file.or_else(|| value("--motd").cloned()).filter(|text| !text.is_empty()))
+}
+
+/// Server time of the next respawn wave after `now`, with waves every
+/// `interval` seconds counted from server start.
+pub fn next_respawn_wave(now: f32, interval: f32) -> f32 {
+    ((now / interval).floor() + 1.0) * interval
+}
+
+/// The team with fewer members (Red on a tie), for assigning new players.
+pub fn smaller_team(teams: impl Iterator<Item = Team>) -> Team {
+    let (red, blue) = teams.fold((0, 0), |(red, blue), team| match team {
+        Team::Red => (red + 1, blue),
+        Team::Blue => (red, blue + 1),
+    });
+    if blue < red { Team::Blue } else { Team::Red }
+}
+
+/// Whether moving one player off `from` leaves the teams at most
+/// `max_imbalance` apart in size.
+pub fn swap_keeps_balance(teams: impl Iterator<Item = Team>, from: Team, max_imbalance: u32) -> bool {
+    let (red, blue) = teams.fold((0i64, 0i64), |(red, blue), team| match team {
+        Team::Red => (red + 1, blue),
+        Team::Blue => (red, blue + 1),
+    });
+    let (red, blue) = match from {
+        Team::Red => (red - 1, blue + 1),
+        Team::Blue => (red + 1, blue - 1),
+    };
+    (red - blue).unsigned_abs() <= max_imbalance as u64
+}
+
+/// Items a player spawns with, by slot. Names must match a map `Equippable`
+/// (its model is used for the view model). Empty slots grant nothing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Loadout {
+    pub primary: Option<String>,
+    pub secondary: Option<String>,
+    pub melee: Option<String>,
+}
+
+impl Loadout {
+    /// Parse `primary,secondary,melee`; slots may be left empty (`AK47,,Pickaxe`).
+    pub fn parse(spec: &str) -> Self {
+        let mut slots = spec
+            .split(',')
+            .map(str::trim)
+            .map(|s| (!s.is_empty()).then(|| s.to_string()));
+        Self {
+            primary: slots.next().flatten(),
+            secondary: slots.next().flatten(),
+            melee: slots.next().flatten(),
+        }
+    }
+
+    /// Item names in slot order.
+    pub fn items(&self) -> Vec<String> {
+        [&self.primary, &self.secondary, &self.melee]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Components that hand this loadout to a player: the first item in
+    /// hand, the rest in the inventory.
+    pub fn grant(&self) -> (PlayerEquipped, PlayerInventory, LoadoutItems) {
+        let items = self.items();
+        let mut rest = items.clone();
+        let equipped = (!rest.is_empty()).then(|| rest.remove(0));
+        (PlayerEquipped(equipped), PlayerInventory { items: rest }, LoadoutItems(items))
+    }
+}
+
+/// Spawn loadouts. Team loadouts (CTF) override the default for that team.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct LoadoutConfig {
+    pub default: Loadout,
+    pub red: Option<Loadout>,
+    pub blue: Option<Loadout>,
+}
+
+impl LoadoutConfig {
+    pub fn for_team(&self, team: Option<Team>) -> &Loadout {
+        let team_loadout = match team {
+            Some(Team::Red) => self.red.as_ref(),
+            Some(Team::Blue) => self.blue.as_ref(),
+            None => None,
+        };
+        team_loadout.unwrap_or(&self.default)
+    }
+}
+
+/// Parse --loadout, --loadout-red and --loadout-blue <primary,secondary,melee>
+/// from CLI args.
+pub fn parse_loadouts() -> LoadoutConfig {
+    let args: Vec<String> = std::env::args().collect();
+    let loadout = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+            .map(|spec| Loadout::parse(spec))
+    };
+    let config = LoadoutConfig {
+        default: loadout("--loadout").unwrap_or_default(),
+        red: loadout("--loadout-red"),
+        blue: loadout("--loadout-blue"),
+    };
+    info!("[LOADOUT] {:?}", config);
+    config
+}
+
+/// What a kill-streak milestone grants.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StreakReward {
+    /// Heal by this much, up to full health.
+    Health(i32),
+    /// Move `multiplier` times faster for `secs` seconds.
+    Speed { multiplier: f32, secs: f32 },
+}
+
+impl StreakReward {
+    /// Parse `health:<amount>` or `speed:<multiplier>:<secs>`.
+    fn parse(fields: &[&str]) -> Option<Self> {
+        match fields {
+            ["health", amount] => amount.parse().ok().filter(|a| *a > 0).map(Self::Health),
+            ["speed", multiplier, secs] => {
+                let multiplier: f32 = multiplier.parse().ok().filter(|m| *m > 0.0)?;
+                let secs: f32 = secs.parse().ok().filter(|s| *s > 0.0)?;
+                Some(Self::Speed { multiplier, secs })
+            }
+            _ => None,
+        }
+    }
+
+    /// Text for the kill-feed announcement.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Health(amount) => format!("+{} health", amount),
+            Self::Speed { multiplier, secs } => format!("{:.0}% speed for {:.0}s", multiplier * 100.0, secs),
+        }
+    }
+}
+
+/// Rewards granted when a player's kill streak reaches a threshold. None
+/// unless the server opts in with --streak-rewards.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct KillStreakRewards(pub Vec<(u32, StreakReward)>);
+
+impl KillStreakRewards {
+    /// The stock set (`--streak-rewards default`): health at 3 kills, a
+    /// speed boost at 5.
+    pub fn standard() -> Self {
+        Self(vec![
+            (3, StreakReward::Health(50)),
+            (5, StreakReward::Speed { multiplier: 1.3, secs: 10.0 }),
+        ])
+    }
+
+    /// Rewards for reaching exactly `streak` kills.
+    pub fn at(&self, streak: u32) -> impl Iterator<Item = &StreakReward> {
+        self.0.iter().filter(move |(kills, _)| *kills == streak).map(|(_, reward)| reward)
+    }
+}
+
+/// Parse --streak-rewards from CLI args: comma-separated `<kills>:<reward>`
+/// entries, e.g. `3:health:50,5:speed:1.3:10`, or `default` for the stock
+/// set. Invalid entries are skipped; without the flag there are no rewards.
+pub fn parse_streak_rewards() -> KillStreakRewards {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(spec) = args
+        .iter()
+        .position(|a| a == "--streak-rewards")
+        .and_then(|pos| args.get(pos + 1))
+    else {
+        return KillStreakRewards::default();
+    };
+    let rewards = if spec == "default" {
+        KillStreakRewards::standard()
+    } else {
+        KillStreakRewards(
+            spec.split(',')
+                .filter_map(|entry| {
+                    let fields: Vec<&str> = entry.trim().split(':').collect();
+                    let (kills, reward) = fields.split_first()?;
+                    Some((kills.parse::<u32>().ok().filter(|k| *k > 0)?, StreakReward::parse(reward)?))
+                })
+                .collect(),
+        )
+    };
+    info!("[STREAK] Rewards: {:?}", rewards.0);
+    rewards
+}
+
+/// Bot auto-balance: keep `target` combatants in the arena by adding a bot
+/// whenever a real player leaves and removing one when a player joins.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct BotFill {
+    /// Desired humans + bots. 0 disables bots.
+    pub target: usize,
+    /// Bots only chase and shoot at players within this many meters.
+    pub aggro_range: f32,
+}
+
+/// Default `BotFill::aggro_range` (meters).
+pub const DEFAULT_BOT_AGGRO_RANGE: f32 = 30.0;
+
+/// Parse --bot-fill <count> and --bot-aggro-range <meters> from CLI args.
+pub fn parse_bot_fill() -> BotFill {
+    let args: Vec<String> = std::env::args().collect();
+    let target = args
+        .iter()
+        .position(|a| a == "--bot-fill")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let aggro_range = args
+        .iter()
+        .position(|a| a == "--bot-aggro-range")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|r| *r > 0.0)
+        .unwrap_or(DEFAULT_BOT_AGGRO_RANGE);
+    if target > 0 {
+        info!("[BOTS] Filling the arena to {} combatants (aggro range {}m)", target, aggro_range);
+    }
+    BotFill { target, aggro_range }
+}
+
+/// Parse --elimination, --downed-bleedout <seconds>, --hardcore, --ctf,
+/// --headshot-only, --respawn-wave <seconds>, --respawn-delay <seconds>,
+/// --team-swap-imbalance <count>, --drop-on-disconnect,
+/// --ore-drop <inventory|chunk|both> and --ricochets from CLI args.
+pub fn parse_ruleset() -> Ruleset {
+    let args: Vec<String> = std::env::args().collect();
+    let ruleset = Ruleset {
+        elimination: args.iter().any(|a| a == "--elimination"),
+        downed_bleedout: args
+            .iter()
+            .position(|a| a == "--downed-bleedout")
+            .and_then(|pos| args.get(pos + 1))
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|v| *v > 0.0),
+        hardcore: args.iter().any(|a| a == "--hardcore"),
+        ctf: args.iter().any(|a| a == "--ctf"),
+        headshot_only: args.iter().any(|a| a == "--headshot-only"),
+        respawn_wave: args
+            .iter()
+            .position(|a| a == "--respawn-wave")
+            .and_then(|pos| args.get(pos + 1))
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|v| *v > 0.0),
+        respawn_delay: args
+            .iter()
+            .position(|a| a == "--respawn-delay")
+            .and_then(|pos| args.get(pos + 1))
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|v| *v >= 0.0),
+        team_swap_imbalance: args
+            .iter()
+            .position(|a| a == "--team-swap-imbalance")
+            .and_then(|pos| args.get(pos + 1))
+            .and_then(|v| v.parse::<u32>().ok()),
+        drop_on_disconnect: args.iter().any(|a| a == "--drop-on-disconnect"),
+        ore_drop: args
+            .iter()
+            .position(|a| a == "--ore-drop")
+            .and_then(|pos| args.get(pos + 1))
+            .and_then(|v| OreDrop::parse(v))
+            .unwrap_or_default(),
+        ricochets: args.iter().any(|a| a == "--ricochets"),
+    };
+    info!("[RULES] {:?}", ruleset);
+    ruleset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_keeps_balance() {
+        let teams = [Team::Red, Team::Red, Team::Blue];
+        // 2v1 -> 1v2 is still one apart
+        assert!(swap_keeps_balance(teams.into_iter(), Team::Red, 1));
+        // 2v1 -> 3v0 is three apart
+        assert!(!swap_keeps_balance(teams.into_iter(), Team::Blue, 1));
+        assert!(swap_keeps_balance(teams.into_iter(), Team::Blue, 3));
+        // Alone on a team: 1v0 -> 0v1
+        assert!(swap_keeps_balance([Team::Red].into_iter(), Team::Red, 1));
+        assert!(!swap_keeps_balance([Team::Red].into_iter(), Team::Red, 0));
+    }
+}
@@ -0,0 +1,1096 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Centimeters per inch — mouse DPI is counts per inch, cm/360 is in centimeters.
+const CM_PER_INCH: f32 = 2.54;
+
+/// Storage key (`crate::storage`) for the saved player settings.
+const SETTINGS_KEY: &str = "settings.json";
+
+/// Client-only player settings.
+///
+/// Sensitivity is expressed in real-world units (cm of mouse travel per full
+/// 360° turn + mouse DPI) so players can match the feel of other games.
+/// It is converted to a per-count yaw/pitch factor in `CameraSensitivity`.
+///
+/// Saved to `~/.anima/settings.json` whenever it changes and loaded on the
+/// next start; fields missing from the file keep their defaults.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PlayerSettings {
+    /// Mouse resolution in counts per inch.
+    pub mouse_dpi: f32,
+    /// Mouse travel (cm) for a full 360° yaw turn.
+    pub cm_per_360: f32,
+    /// Vertical sensitivity as a fraction of horizontal.
+    pub vertical_ratio: f32,
+    /// Moving the mouse (or stick) up looks down.
+    pub invert_y: bool,
+    /// Picking up a better weapon switches to it; everything else goes to inventory.
+    /// Sent to the server on connect (`PlayerPreferencesMessage`).
+    pub auto_equip: bool,
+    /// Aiming down sights also crouches (manual crouch still works on its own).
+    pub auto_crouch_ads: bool,
+    /// Gentle breathing sway on the held item while standing still.
+    pub idle_breathing: bool,
+    /// Seconds of slow-motion on our screen after getting a kill (0 = off).
+    pub hit_stop_secs: f32,
+    /// Hip-fire field of view in degrees (`BaseFov`, adjusted in game with the arrow keys).
+    pub fov_degrees: f32,
+    /// Start with the cursor grabbed for mouse look.
+    pub lock_cursor: bool,
+    /// How far the held item sways with movement and turning (1.0 = default,
+    /// 0.0 holds it steady).
+    pub weapon_sway: f32,
+    /// Sound effect volume, 0.0 (muted) to 1.0.
+    pub sfx_volume: f32,
+    /// Look acceleration: sensitivity multiplier by input speed.
+    pub look_curve: LookCurve,
+    /// While firing, plot our aim drift against the pull that cancels the
+    /// weapon's spread pattern.
+    pub recoil_trainer: bool,
+}
+
+impl Default for PlayerSettings {
+    fn default() -> Self {
+        Self {
+            mouse_dpi: 800.0,
+            // ~0.003 rad/count at 800 DPI — matches the old hardcoded factor
+            cm_per_360: 6.65,
+            vertical_ratio: 2.0 / 3.0,
+            invert_y: false,
+            auto_equip: false,
+            auto_crouch_ads: false,
+            idle_breathing: true,
+            hit_stop_secs: 0.0,
+            fov_degrees: 90.0,
+            lock_cursor: true,
+            weapon_sway: 1.0,
+            sfx_volume: 0.8,
+            look_curve: LookCurve::default(),
+            recoil_trainer: false,
+        }
+    }
+}
+
+impl PlayerSettings {
+    /// Yaw radians per mouse count for the configured DPI and cm/360.
+    pub fn yaw_per_count(&self) -> f32 {
+        let counts_per_360 = self.cm_per_360 / CM_PER_INCH * self.mouse_dpi;
+        if counts_per_360 <= 0.0 {
+            return 0.0;
+        }
+        TAU / counts_per_360
+    }
+
+    /// Pitch radians per mouse count (yaw factor scaled by `vertical_ratio`),
+    /// negative with `invert_y`.
+    pub fn pitch_per_count(&self) -> f32 {
+        let pitch = self.yaw_per_count() * self.vertical_ratio;
+        if self.invert_y { -pitch } else { pitch }
+    }
+
+    /// Parse a saved settings file. None if it isn't valid settings JSON.
+    pub fn from_json(data: &str) -> Option<Self> {
+        serde_json::from_str(data).ok()
+    }
+
+    /// Load the saved settings, or the defaults if there are none or the
+    /// file can't be parsed.
+    pub fn load() -> Self {
+        let Some(data) = crate::storage::read(SETTINGS_KEY) else {
+            return Self::default();
+        };
+        Self::from_json(&data).unwrap_or_else(|| {
+            warn!(
+                "[SETTINGS] Ignoring unreadable {} — using defaults",
+                crate::storage::location(SETTINGS_KEY)
+            );
+            Self::default()
+        })
+    }
+
+    /// What to save for these (live) settings: `saved` with every field that
+    /// changed in game since `started` taken from here. Fields only set by a
+    /// CLI flag keep their saved value, so one-off flags don't stick.
+    pub fn persisted(&self, saved: &Self, started: &Self) -> Self {
+        use serde_json::{to_value, Value};
+        let (Ok(Value::Object(live)), Ok(Value::Object(started)), Ok(Value::Object(mut out))) =
+            (to_value(self), to_value(started), to_value(saved))
+        else {
+            return saved.clone();
+        };
+        for (field, value) in live {
+            if started.get(&field) != Some(&value) {
+                out.insert(field, value);
+            }
+        }
+        serde_json::from_value(Value::Object(out)).unwrap_or_else(|_| saved.clone())
+    }
+
+    /// Write the settings back to disk.
+    pub fn save(&self) {
+        let json = match serde_json::to_string_pretty(self) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("[SETTINGS] Failed to serialize settings: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = crate::storage::write(SETTINGS_KEY, &json) {
+            warn!("[SETTINGS] Failed to write {}: {}", crate::storage::location(SETTINGS_KEY), e);
+        }
+    }
+}
+
+/// Mouse speed (counts per millisecond) at the right end of the look curve.
+/// 20 counts/ms is a fast flick at 800 DPI (about 64 cm/s).
+pub const LOOK_CURVE_MOUSE_SPEED: f32 = 20.0;
+/// Largest sensitivity multiplier a look curve point can have.
+pub const MAX_LOOK_CURVE_MULTIPLIER: f32 = 4.0;
+
+/// Built-in look curves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LookCurvePreset {
+    /// Flat: sensitivity doesn't depend on speed.
+    None,
+    /// Ramps steadily up to double sensitivity at full speed.
+    Linear,
+    /// Quake-style: flat for slow aim, then accelerates and levels off.
+    Classic,
+}
+
+impl LookCurvePreset {
+    pub const ALL: [LookCurvePreset; 3] = [LookCurvePreset::None, LookCurvePreset::Linear, LookCurvePreset::Classic];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Linear => "Linear",
+            Self::Classic => "Classic",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.label().eq_ignore_ascii_case(value))
+    }
+
+    pub fn curve(self) -> LookCurve {
+        let points = match self {
+            Self::None => vec![[0.0, 1.0], [1.0, 1.0]],
+            Self::Linear => vec![[0.0, 1.0], [1.0, 2.0]],
+            Self::Classic => vec![[0.0, 1.0], [0.15, 1.0], [0.6, 1.8], [1.0, 2.0]],
+        };
+        LookCurve { points }
+    }
+}
+
+/// Look response curve: control points `[speed, multiplier]`, sorted by speed,
+/// joined by straight lines and flat past either end. Speed is normalized:
+/// 1.0 is `LOOK_CURVE_MOUSE_SPEED` for the mouse and full deflection for the
+/// right stick.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LookCurve {
+    pub points: Vec<[f32; 2]>,
+}
+
+impl Default for LookCurve {
+    fn default() -> Self {
+        LookCurvePreset::None.curve()
+    }
+}
+
+impl LookCurve {
+    /// Sensitivity multiplier at normalized input speed `speed`.
+    pub fn multiplier(&self, speed: f32) -> f32 {
+        let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+            return 1.0;
+        };
+        if speed <= first[0] {
+            return first[1];
+        }
+        if speed >= last[0] {
+            return last[1];
+        }
+        for pair in self.points.windows(2) {
+            let ([x0, y0], [x1, y1]) = (pair[0], pair[1]);
+            if speed <= x1 {
+                let span = x1 - x0;
+                let blend = if span > 0.0 { (speed - x0) / span } else { 1.0 };
+                return y0 + (y1 - y0) * blend;
+            }
+        }
+        last[1]
+    }
+
+    /// The preset this curve is, if it is one.
+    pub fn preset(&self) -> Option<LookCurvePreset> {
+        LookCurvePreset::ALL.into_iter().find(|p| p.curve() == *self)
+    }
+
+    /// Re-sort after an edit and keep every point in range.
+    pub fn normalize(&mut self) {
+        for point in &mut self.points {
+            point[0] = point[0].clamp(0.0, 1.0);
+            point[1] = point[1].clamp(0.0, MAX_LOOK_CURVE_MULTIPLIER);
+        }
+        self.points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+    }
+}
+
+/// Most weapon sway a player can ask for, in multiples of the default.
+pub const MAX_WEAPON_SWAY: f32 = 3.0;
+
+/// Client-only: the settings as last saved, and as they were right after
+/// the previous save (or at startup, CLI flags applied). Their difference
+/// tells in-game edits apart from CLI overrides — see `PlayerSettings::persisted`.
+#[derive(Resource, Clone, Debug)]
+pub struct SavedPlayerSettings {
+    pub saved: PlayerSettings,
+    pub started: PlayerSettings,
+}
+
+/// Take the saved settings, then apply --dpi <counts>, --cm360 <cm>,
+/// --fov <degrees>, --auto-equip, --auto-crouch-ads, --no-idle-breathing,
+/// --no-cursor-lock, --invert-y, --recoil-trainer, --hit-stop <secs>, --weapon-sway <mult>,
+/// --sfx-volume <0-1> and --look-curve <none|linear|classic> from CLI args on
+/// top. Invalid or non-positive values are
+/// ignored and the saved value is kept (--weapon-sway and --sfx-volume also
+/// take 0, to turn sway or sound effects off). The flags only last for this
+/// run; they are never written back to the saved settings.
+pub fn parse_player_settings(saved: &PlayerSettings) -> PlayerSettings {
+    let args: Vec<String> = std::env::args().collect();
+    let mut settings = saved.clone();
+
+    let positive_arg = |flag: &str| -> Option<f32> {
+        let pos = args.iter().position(|a| a == flag)?;
+        args.get(pos + 1)?.parse::<f32>().ok().filter(|v| *v > 0.0)
+    };
+
+    if let Some(dpi) = positive_arg("--dpi") {
+        settings.mouse_dpi = dpi;
+    }
+    if let Some(cm) = positive_arg("--cm360") {
+        settings.cm_per_360 = cm;
+    }
+    if let Some(fov) = positive_arg("--fov") {
+        settings.fov_degrees = fov.clamp(20.0, 160.0);
+    }
+    // Flags only switch things on/off when given, so saved choices apply on a plain start
+    if args.iter().any(|a| a == "--auto-equip") {
+        settings.auto_equip = true;
+    }
+    if args.iter().any(|a| a == "--auto-crouch-ads") {
+        settings.auto_crouch_ads = true;
+    }
+    if args.iter().any(|a| a == "--no-idle-breathing") {
+        settings.idle_breathing = false;
+    }
+    if args.iter().any(|a| a == "--no-cursor-lock") {
+        settings.lock_cursor = false;
+    }
+    if args.iter().any(|a| a == "--invert-y") {
+        settings.invert_y = true;
+    }
+    if args.iter().any(|a| a == "--recoil-trainer") {
+        settings.recoil_trainer = true;
+    }
+    if let Some(secs) = positive_arg("--hit-stop") {
+        settings.hit_stop_secs = secs;
+    }
+    if let Some(sway) = args
+        .iter()
+        .position(|a| a == "--weapon-sway")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|v| *v >= 0.0)
+    {
+        settings.weapon_sway = sway.min(MAX_WEAPON_SWAY);
+    }
+    if let Some(volume) = args
+        .iter()
+        .position(|a| a == "--sfx-volume")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|v| *v >= 0.0)
+    {
+        settings.sfx_volume = volume.min(1.0);
+    }
+    if let Some(preset) = args
+        .iter()
+        .position(|a| a == "--look-curve")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| LookCurvePreset::parse(v))
+    {
+        settings.look_curve = preset.curve();
+    }
+
+    settings
+}
+
+/// Keyboard actions that can be rebound (see `KeyBindings`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum BoundAction {
+    Forward,
+    Back,
+    Left,
+    Right,
+    Jump,
+    Interact,
+    Drop,
+    Crouch,
+    Sprint,
+    /// Quick-melee (left-hand jab) — works whatever is in hand.
+    Melee,
+    LeanLeft,
+    LeanRight,
+    /// Hold to open the quick-chat wheel.
+    QuickChat,
+    /// Throw the selected utility (frag, flashbang, smoke).
+    Throw,
+    CycleUtility,
+    /// Toggle the inventory panel (client UI only, not a PlayerActions).
+    Inventory,
+}
+
+impl BoundAction {
+    pub const ALL: [BoundAction; 16] = [
+        BoundAction::Forward,
+        BoundAction::Back,
+        BoundAction::Left,
+        BoundAction::Right,
+        BoundAction::Jump,
+        BoundAction::Interact,
+        BoundAction::Drop,
+        BoundAction::Crouch,
+        BoundAction::Sprint,
+        BoundAction::Melee,
+        BoundAction::LeanLeft,
+        BoundAction::LeanRight,
+        BoundAction::QuickChat,
+        BoundAction::Throw,
+        BoundAction::CycleUtility,
+        BoundAction::Inventory,
+    ];
+
+    /// Name used by `--bind` (e.g. `lean-left`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Forward => "forward",
+            Self::Back => "back",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Jump => "jump",
+            Self::Interact => "interact",
+            Self::Drop => "drop",
+            Self::Crouch => "crouch",
+            Self::Sprint => "sprint",
+            Self::Melee => "melee",
+            Self::LeanLeft => "lean-left",
+            Self::LeanRight => "lean-right",
+            Self::QuickChat => "quick-chat",
+            Self::Throw => "throw",
+            Self::CycleUtility => "cycle-utility",
+            Self::Inventory => "inventory",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.to_ascii_lowercase();
+        Self::ALL.into_iter().find(|action| action.name() == value)
+    }
+}
+
+/// Client-only keyboard bindings for the player's actions. Defaults to the
+/// WASD layout; the InputMap on our player is rebuilt whenever this changes.
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Resource)]
+pub struct KeyBindings {
+    pub forward: KeyCode,
+    pub back: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub jump: KeyCode,
+    /// Also held to revive a downed teammate.
+    pub interact: KeyCode,
+    pub drop: KeyCode,
+    pub crouch: KeyCode,
+    pub sprint: KeyCode,
+    pub melee: KeyCode,
+    /// Hold to lean. Q and E are taken by melee and interact.
+    pub lean_left: KeyCode,
+    pub lean_right: KeyCode,
+    pub quick_chat: KeyCode,
+    pub throw: KeyCode,
+    pub cycle_utility: KeyCode,
+    pub inventory: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            forward: KeyCode::KeyW,
+            back: KeyCode::KeyS,
+            left: KeyCode::KeyA,
+            right: KeyCode::KeyD,
+            jump: KeyCode::Space,
+            interact: KeyCode::KeyE,
+            drop: KeyCode::KeyG,
+            crouch: KeyCode::ControlLeft,
+            sprint: KeyCode::ShiftLeft,
+            melee: KeyCode::KeyQ,
+            lean_left: KeyCode::KeyZ,
+            lean_right: KeyCode::KeyC,
+            quick_chat: KeyCode::KeyT,
+            throw: KeyCode::KeyX,
+            cycle_utility: KeyCode::KeyB,
+            inventory: KeyCode::KeyI,
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Key currently bound to `action`.
+    pub fn key(&self, action: BoundAction) -> KeyCode {
+        match action {
+            BoundAction::Forward => self.forward,
+            BoundAction::Back => self.back,
+            BoundAction::Left => self.left,
+            BoundAction::Right => self.right,
+            BoundAction::Jump => self.jump,
+            BoundAction::Interact => self.interact,
+            BoundAction::Drop => self.drop,
+            BoundAction::Crouch => self.crouch,
+            BoundAction::Sprint => self.sprint,
+            BoundAction::Melee => self.melee,
+            BoundAction::LeanLeft => self.lean_left,
+            BoundAction::LeanRight => self.lean_right,
+            BoundAction::QuickChat => self.quick_chat,
+            BoundAction::Throw => self.throw,
+            BoundAction::CycleUtility => self.cycle_utility,
+            BoundAction::Inventory => self.inventory,
+        }
+    }
+
+    /// Bind `action` to `key`. Doesn't unbind other actions on the same key —
+    /// check `conflicts()` afterwards.
+    pub fn rebind(&mut self, action: BoundAction, key: KeyCode) {
+        let slot = match action {
+            BoundAction::Forward => &mut self.forward,
+            BoundAction::Back => &mut self.back,
+            BoundAction::Left => &mut self.left,
+            BoundAction::Right => &mut self.right,
+            BoundAction::Jump => &mut self.jump,
+            BoundAction::Interact => &mut self.interact,
+            BoundAction::Drop => &mut self.drop,
+            BoundAction::Crouch => &mut self.crouch,
+            BoundAction::Sprint => &mut self.sprint,
+            BoundAction::Melee => &mut self.melee,
+            BoundAction::LeanLeft => &mut self.lean_left,
+            BoundAction::LeanRight => &mut self.lean_right,
+            BoundAction::QuickChat => &mut self.quick_chat,
+            BoundAction::Throw => &mut self.throw,
+            BoundAction::CycleUtility => &mut self.cycle_utility,
+            BoundAction::Inventory => &mut self.inventory,
+        };
+        *slot = key;
+    }
+
+    /// Pairs of actions bound to the same key.
+    pub fn conflicts(&self) -> Vec<(BoundAction, BoundAction)> {
+        let mut conflicts = Vec::new();
+        for (i, a) in BoundAction::ALL.iter().enumerate() {
+            for b in &BoundAction::ALL[i + 1..] {
+                if self.key(*a) == self.key(*b) {
+                    conflicts.push((*a, *b));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// Parse --bind <action>=<key> (repeatable, e.g. `--bind forward=Z`) plus the
+/// older --lean-keys <LR> and --melee-key <key> shorthands from CLI args.
+/// Unknown actions or keys are ignored; conflicts are logged.
+pub fn parse_key_bindings() -> KeyBindings {
+    let args: Vec<String> = std::env::args().collect();
+    let mut bindings = KeyBindings::default();
+
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+    };
+    if let Some([left, right]) = value("--lean-keys").and_then(|v| parse_lean_keys(v)) {
+        bindings.lean_left = left;
+        bindings.lean_right = right;
+    }
+    if let Some(key) = value("--melee-key").and_then(|v| parse_key(v)) {
+        bindings.melee = key;
+    }
+    for pair in args.windows(2).filter(|w| w[0] == "--bind").map(|w| &w[1]) {
+        let parsed = pair
+            .split_once('=')
+            .and_then(|(action, key)| Some((BoundAction::parse(action)?, parse_key(key)?)));
+        match parsed {
+            Some((action, key)) => bindings.rebind(action, key),
+            None => warn!("[SETTINGS] Ignoring --bind {} (expected <action>=<key>)", pair),
+        }
+    }
+    for (a, b) in bindings.conflicts() {
+        warn!("[SETTINGS] {} and {} are both bound to {:?}", a.name(), b.name(), bindings.key(a));
+    }
+
+    bindings
+}
+
+/// Client-only: phrases on the quick-chat wheel, clockwise from the top.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct QuickChatSettings {
+    pub phrases: Vec<String>,
+}
+
+/// Most phrases the wheel can hold before segments get too narrow to hit.
+pub const MAX_QUICK_CHAT_PHRASES: usize = 8;
+
+impl Default for QuickChatSettings {
+    fn default() -> Self {
+        Self {
+            phrases: ["Need backup", "Enemy spotted", "Nice shot", "On my way", "Fall back", "Thanks"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+/// Parse --quick-chat <phrase;phrase;...> from CLI args. Empty entries are
+/// skipped and at most MAX_QUICK_CHAT_PHRASES are kept.
+pub fn parse_quick_chat_settings() -> QuickChatSettings {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(list) = args
+        .iter()
+        .position(|a| a == "--quick-chat")
+        .and_then(|pos| args.get(pos + 1))
+    else {
+        return QuickChatSettings::default();
+    };
+    let phrases: Vec<String> = list
+        .split(';')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .take(MAX_QUICK_CHAT_PHRASES)
+        .map(String::from)
+        .collect();
+    if phrases.is_empty() {
+        return QuickChatSettings::default();
+    }
+    QuickChatSettings { phrases }
+}
+
+const LETTER_KEYS: [KeyCode; 26] = [
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF,
+    KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL,
+    KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR,
+    KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX,
+    KeyCode::KeyY, KeyCode::KeyZ,
+];
+
+fn letter_key(c: char) -> Option<KeyCode> {
+    let c = c.to_ascii_uppercase();
+    c.is_ascii_uppercase().then(|| LETTER_KEYS[(c as u8 - b'A') as usize])
+}
+
+/// Parse a single-key binding: one letter (e.g. `V`) or one of `space`,
+/// `ctrl`, `shift`, `alt`, `tab`.
+pub fn parse_key(value: &str) -> Option<KeyCode> {
+    match value.to_ascii_lowercase().as_str() {
+        "space" => return Some(KeyCode::Space),
+        "ctrl" => return Some(KeyCode::ControlLeft),
+        "shift" => return Some(KeyCode::ShiftLeft),
+        "alt" => return Some(KeyCode::AltLeft),
+        "tab" => return Some(KeyCode::Tab),
+        _ => {}
+    }
+    let mut chars = value.chars();
+    let key = letter_key(chars.next()?)?;
+    chars.next().is_none().then_some(key)
+}
+
+/// Parse a `--lean-keys` value: two letters, left then right (e.g. `ZC`).
+pub fn parse_lean_keys(value: &str) -> Option<[KeyCode; 2]> {
+    let mut chars = value.chars();
+    let keys = [letter_key(chars.next()?)?, letter_key(chars.next()?)?];
+    chars.next().is_none().then_some(keys)
+}
+
+/// Client-only gamepad tuning. The controller works alongside mouse and
+/// keyboard: sticks add to the same Move/Look actions.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct GamepadSettings {
+    /// Stick deflection (0..1) ignored around the center.
+    pub deadzone: f32,
+    /// Turn speed at full right-stick deflection (radians per second). Pitch
+    /// is scaled by the same vertical ratio as the mouse.
+    pub look_speed: f32,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        Self {
+            deadzone: 0.15,
+            look_speed: 3.0,
+        }
+    }
+}
+
+/// Parse --gamepad-deadzone <0..1> and --gamepad-look-speed <rad/s> from CLI args.
+pub fn parse_gamepad_settings() -> GamepadSettings {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+            .and_then(|v| v.parse::<f32>().ok())
+    };
+    let mut settings = GamepadSettings::default();
+    if let Some(deadzone) = value("--gamepad-deadzone").filter(|v| (0.0..1.0).contains(v)) {
+        settings.deadzone = deadzone;
+    }
+    if let Some(speed) = value("--gamepad-look-speed").filter(|v| *v > 0.0) {
+        settings.look_speed = speed;
+    }
+    settings
+}
+
+/// Short on-screen name for a key: `E` for `KeyE`, `ControlLeft` as is.
+pub fn key_label(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key").map(str::to_string).unwrap_or(name)
+}
+
+/// Post-process anti-aliasing applied on top of (or instead of) MSAA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AntiAliasing {
+    Off,
+    Fxaa,
+    /// Temporal AA. Incompatible with MSAA, which is turned off while it is active.
+    Taa,
+}
+
+impl AntiAliasing {
+    pub const ALL: [AntiAliasing; 3] = [AntiAliasing::Off, AntiAliasing::Fxaa, AntiAliasing::Taa];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Fxaa => "FXAA",
+            Self::Taa => "TAA",
+        }
+    }
+}
+
+/// Valid MSAA sample counts (1 = off).
+pub const MSAA_SAMPLES: [u32; 4] = [1, 2, 4, 8];
+/// Lowest allowed render-resolution scale.
+pub const MIN_RENDER_SCALE: f32 = 0.25;
+
+/// Client-only graphics settings for performance scaling.
+///
+/// `render_scale` below 1.0 renders the 3D world at a lower internal
+/// resolution and upscales it to the window; the HUD is still drawn at
+/// native resolution.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct GraphicsSettings {
+    /// MSAA samples per pixel, one of `MSAA_SAMPLES`.
+    pub msaa_samples: u32,
+    pub anti_aliasing: AntiAliasing,
+    /// Internal 3D resolution as a fraction of the window (MIN_RENDER_SCALE..=1.0).
+    pub render_scale: f32,
+    /// Remote players farther than this (meters) are drawn as a flat billboard
+    /// instead of their full model and held item.
+    pub player_lod_distance: f32,
+    /// Nameplates are shown over remote players up to this distance (meters).
+    pub nameplate_distance: f32,
+    /// Teammates are drawn as a team-colored silhouette on top of the world,
+    /// so they stay visible through walls.
+    pub teammate_outlines: bool,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            msaa_samples: 4,
+            anti_aliasing: AntiAliasing::Off,
+            render_scale: 1.0,
+            player_lod_distance: 60.0,
+            nameplate_distance: 100.0,
+            teammate_outlines: true,
+        }
+    }
+}
+
+impl GraphicsSettings {
+    /// MSAA samples actually used — TAA requires MSAA off.
+    pub fn effective_msaa_samples(&self) -> u32 {
+        if self.anti_aliasing == AntiAliasing::Taa {
+            return 1;
+        }
+        self.msaa_samples
+    }
+
+    /// Internal render size for a window of `width` x `height` physical pixels.
+    pub fn scaled_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let scale = self.render_scale.clamp(MIN_RENDER_SCALE, 1.0);
+        (
+            ((width as f32 * scale).round() as u32).max(1),
+            ((height as f32 * scale).round() as u32).max(1),
+        )
+    }
+}
+
+/// Parse --msaa <1|2|4|8>, --aa <off|fxaa|taa>, --render-scale <0.25..1>,
+/// --player-lod-distance <m>, --nameplate-distance <m> and
+/// --no-teammate-outlines from CLI args.
+/// Invalid values are ignored and the default is kept.
+pub fn parse_graphics_settings() -> GraphicsSettings {
+    let args: Vec<String> = std::env::args().collect();
+    let mut settings = GraphicsSettings::default();
+    let value = |flag: &str| -> Option<&String> {
+        let pos = args.iter().position(|a| a == flag)?;
+        args.get(pos + 1)
+    };
+
+    if let Some(samples) = value("--msaa").and_then(|v| v.parse::<u32>().ok()) {
+        if MSAA_SAMPLES.contains(&samples) {
+            settings.msaa_samples = samples;
+        }
+    }
+    match value("--aa").map(|v| v.to_ascii_lowercase()).as_deref() {
+        Some("off") => settings.anti_aliasing = AntiAliasing::Off,
+        Some("fxaa") => settings.anti_aliasing = AntiAliasing::Fxaa,
+        Some("taa") => settings.anti_aliasing = AntiAliasing::Taa,
+        _ => {}
+    }
+    if let Some(scale) = value("--render-scale").and_then(|v| v.parse::<f32>().ok()) {
+        if (MIN_RENDER_SCALE..=1.0).contains(&scale) {
+            settings.render_scale = scale;
+        }
+    }
+    let distance = |flag: &str| value(flag).and_then(|v| v.parse::<f32>().ok()).filter(|v| *v > 0.0);
+    if let Some(lod) = distance("--player-lod-distance") {
+        settings.player_lod_distance = lod;
+    }
+    if let Some(nameplates) = distance("--nameplate-distance") {
+        settings.nameplate_distance = nameplates;
+    }
+    settings.teammate_outlines = !args.iter().any(|a| a == "--no-teammate-outlines");
+
+    settings
+}
+
+/// Scoreboard sort order. Counts sort highest first, ping lowest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ScoreboardSort {
+    #[default]
+    Score,
+    Kills,
+    Deaths,
+    Ping,
+}
+
+/// Optional scoreboard columns (the player name is always shown).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoreboardColumn {
+    Team,
+    Kills,
+    Deaths,
+    Assists,
+    Score,
+    Ping,
+}
+
+impl ScoreboardColumn {
+    pub const ALL: [ScoreboardColumn; 6] = [
+        ScoreboardColumn::Team,
+        ScoreboardColumn::Kills,
+        ScoreboardColumn::Deaths,
+        ScoreboardColumn::Assists,
+        ScoreboardColumn::Score,
+        ScoreboardColumn::Ping,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Team => "Team",
+            Self::Kills => "K",
+            Self::Deaths => "D",
+            Self::Assists => "A",
+            Self::Score => "Score",
+            Self::Ping => "Ping",
+        }
+    }
+
+    /// Parse one `--scoreboard-columns` entry (`team`, `k`, `d`, `a`, `score`, `ping`).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "team" => Some(Self::Team),
+            "k" | "kills" => Some(Self::Kills),
+            "d" | "deaths" => Some(Self::Deaths),
+            "a" | "assists" => Some(Self::Assists),
+            "score" => Some(Self::Score),
+            "ping" => Some(Self::Ping),
+            _ => None,
+        }
+    }
+}
+
+/// Client-only: Tab scoreboard layout.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct ScoreboardSettings {
+    pub sort: ScoreboardSort,
+    /// Shown columns, in display order.
+    pub columns: Vec<ScoreboardColumn>,
+}
+
+impl Default for ScoreboardSettings {
+    fn default() -> Self {
+        Self {
+            sort: ScoreboardSort::default(),
+            columns: ScoreboardColumn::ALL.to_vec(),
+        }
+    }
+}
+
+/// Parse --scoreboard-sort <score|kills|deaths|ping> and
+/// --scoreboard-columns <list> (comma-separated, e.g. `team,k,d,score,ping`).
+pub fn parse_scoreboard_settings() -> ScoreboardSettings {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+    };
+    let mut settings = ScoreboardSettings::default();
+    match value("--scoreboard-sort").map(String::as_str) {
+        Some("kills") => settings.sort = ScoreboardSort::Kills,
+        Some("deaths") => settings.sort = ScoreboardSort::Deaths,
+        Some("ping") => settings.sort = ScoreboardSort::Ping,
+        _ => {}
+    }
+    if let Some(list) = value("--scoreboard-columns") {
+        settings.columns = list.split(',').filter_map(ScoreboardColumn::parse).collect();
+    }
+    settings
+}
+
+/// Client-only: compass strip along the top of the HUD.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct CompassSettings {
+    pub enabled: bool,
+    /// Width of the visible heading range in degrees, centered on where we look.
+    pub arc_degrees: f32,
+}
+
+impl Default for CompassSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            arc_degrees: 180.0,
+        }
+    }
+}
+
+/// Parse --no-compass and --compass-arc <degrees> (30–360) from CLI args.
+pub fn parse_compass_settings() -> CompassSettings {
+    let args: Vec<String> = std::env::args().collect();
+    let mut settings = CompassSettings::default();
+    settings.enabled = !args.iter().any(|a| a == "--no-compass");
+    if let Some(arc) = args
+        .iter()
+        .position(|a| a == "--compass-arc")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+    {
+        settings.arc_degrees = arc.clamp(30.0, 360.0);
+    }
+    settings
+}
+
+/// Default crosshair image size in logical pixels.
+pub const DEFAULT_CROSSHAIR_SIZE: f32 = 32.0;
+
+/// Client-only: crosshair look. With `image` set, that texture (a path under
+/// `assets/`) is drawn centered at `size` pixels instead of the drawn cross;
+/// if it is missing or fails to load the drawn cross is used.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct CrosshairSettings {
+    pub image: Option<String>,
+    pub size: f32,
+}
+
+impl Default for CrosshairSettings {
+    fn default() -> Self {
+        Self {
+            image: None,
+            size: DEFAULT_CROSSHAIR_SIZE,
+        }
+    }
+}
+
+/// Parse --crosshair-image <asset path> and --crosshair-size <px> (8–256)
+/// from CLI args.
+pub fn parse_crosshair_settings() -> CrosshairSettings {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+    };
+    let mut settings = CrosshairSettings::default();
+    settings.image = value("--crosshair-image").cloned();
+    if let Some(size) = value("--crosshair-size").and_then(|v| v.parse::<f32>().ok()) {
+        settings.size = size.clamp(8.0, 256.0);
+    }
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_turn_over_cm_per_360() {
+        let settings = PlayerSettings {
+            mouse_dpi: 1600.0,
+            cm_per_360: 34.0,
+            vertical_ratio: 1.0,
+            invert_y: false,
+            auto_equip: false,
+            auto_crouch_ads: false,
+            idle_breathing: true,
+            hit_stop_secs: 0.0,
+            fov_degrees: 90.0,
+            lock_cursor: true,
+            weapon_sway: 1.0,
+            sfx_volume: 0.8,
+            look_curve: LookCurve::default(),
+            recoil_trainer: false,
+        };
+        // 34cm of travel at 1600 DPI
+        let counts = 34.0 / CM_PER_INCH * 1600.0;
+        let turned = counts * settings.yaw_per_count();
+        assert!((turned - TAU).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_invert_y_flips_pitch_only() {
+        let inverted = PlayerSettings { invert_y: true, ..default() };
+        let normal = PlayerSettings::default();
+        assert_eq!(inverted.yaw_per_count(), normal.yaw_per_count());
+        assert_eq!(inverted.pitch_per_count(), -normal.pitch_per_count());
+    }
+
+    #[test]
+    fn test_default_matches_legacy_factor() {
+        let settings = PlayerSettings::default();
+        assert!((settings.yaw_per_count() - 0.003).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_settings_json_round_trip() {
+        let settings = PlayerSettings {
+            cm_per_360: 30.0,
+            fov_degrees: 105.0,
+            lock_cursor: false,
+            ..default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        assert_eq!(PlayerSettings::from_json(&json), Some(settings));
+        // Older files without the newer fields fall back per field
+        let partial = PlayerSettings::from_json(r#"{"mouse_dpi": 1600.0}"#).unwrap();
+        assert_eq!(partial.mouse_dpi, 1600.0);
+        assert_eq!(partial.fov_degrees, 90.0);
+        assert_eq!(PlayerSettings::from_json("{not json"), None);
+    }
+
+    #[test]
+    fn test_cli_overrides_are_not_persisted() {
+        let saved = PlayerSettings::default();
+        // Started with --fov 110 --recoil-trainer
+        let started = PlayerSettings { fov_degrees: 110.0, recoil_trainer: true, ..default() };
+        // ...then inverted Y in the menu
+        let live = PlayerSettings { invert_y: true, ..started.clone() };
+
+        let persisted = live.persisted(&saved, &started);
+        assert_eq!(persisted, PlayerSettings { invert_y: true, ..default() });
+
+        // Changing an overridden field in game does stick
+        let live = PlayerSettings { fov_degrees: 100.0, ..started.clone() };
+        assert_eq!(live.persisted(&saved, &started).fov_degrees, 100.0);
+    }
+
+    #[test]
+    fn test_lean_keys_parse() {
+        assert_eq!(parse_lean_keys("qe"), Some([KeyCode::KeyQ, KeyCode::KeyE]));
+        assert_eq!(parse_lean_keys("ZC"), Some([KeyCode::KeyZ, KeyCode::KeyC]));
+        assert_eq!(parse_lean_keys("Z"), None);
+        assert_eq!(parse_lean_keys("ZCX"), None);
+        assert_eq!(parse_lean_keys("Z1"), None);
+    }
+
+    #[test]
+    fn test_melee_key_parse() {
+        assert_eq!(parse_key("v"), Some(KeyCode::KeyV));
+        assert_eq!(parse_key("F"), Some(KeyCode::KeyF));
+        assert_eq!(parse_key(""), None);
+        assert_eq!(parse_key("VF"), None);
+        assert_eq!(parse_key("Shift"), Some(KeyCode::ShiftLeft));
+    }
+
+    #[test]
+    fn test_key_binding_conflicts() {
+        let mut bindings = KeyBindings::default();
+        assert!(bindings.conflicts().is_empty());
+        bindings.rebind(BoundAction::Forward, KeyCode::KeyZ);
+        assert_eq!(bindings.forward, KeyCode::KeyZ);
+        assert_eq!(bindings.conflicts(), vec![(BoundAction::Forward, BoundAction::LeanLeft)]);
+        assert_eq!(BoundAction::parse("Lean-Left"), Some(BoundAction::LeanLeft));
+    }
+
+    #[test]
+    fn test_scoreboard_columns_parse() {
+        let columns: Vec<_> = "team,k,d,score,PING,bogus".split(',').filter_map(ScoreboardColumn::parse).collect();
+        assert_eq!(
+            columns,
+            [ScoreboardColumn::Team, ScoreboardColumn::Kills, ScoreboardColumn::Deaths, ScoreboardColumn::Score, ScoreboardColumn::Ping]
+        );
+    }
+
+    #[test]
+    fn test_render_scale_clamped() {
+        let settings = GraphicsSettings { render_scale: 0.05, ..default() };
+        assert_eq!(settings.scaled_size(1920, 1080), (480, 270));
+        let settings = GraphicsSettings { render_scale: 0.5, ..default() };
+        assert_eq!(settings.scaled_size(1920, 1080), (960, 540));
+    }
+
+    #[test]
+    fn test_look_curve_interpolates_and_clamps() {
+        let curve = LookCurvePreset::Classic.curve();
+        assert_eq!(curve.multiplier(0.1), 1.0);
+        assert!((curve.multiplier(0.375) - 1.4).abs() < 1e-5);
+        assert_eq!(curve.multiplier(5.0), 2.0);
+        assert_eq!(LookCurve::default().multiplier(0.7), 1.0);
+        assert_eq!(LookCurve { points: vec![] }.multiplier(0.5), 1.0);
+        assert_eq!(curve.preset(), Some(LookCurvePreset::Classic));
+    }
+
+    #[test]
+    fn test_taa_disables_msaa() {
+        let settings = GraphicsSettings { anti_aliasing: AntiAliasing::Taa, ..default() };
+        assert_eq!(settings.effective_msaa_samples(), 1);
+    }
+}
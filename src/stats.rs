@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::protocol::PlayerStats;
+
+/// Server-side lifetime stats file: JSON map of client id → PlayerStats.
+const STATS_FILE: &str = "player_stats.json";
+const APP_DIR: &str = "anima";
+
+/// Returns the path to ~/.anima/player_stats.json, or an error when there is
+/// no home directory to keep it in.
+fn stats_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join(format!(".{}", APP_DIR)).join(STATS_FILE))
+}
+
+/// Server-only: lifetime stats for every client id that has ever connected.
+/// Client ids are derived from each player's persistent Ed25519 keypair
+/// (see `auth::pubkey_to_client_id`), so they are stable across reconnects.
+/// Stats are only handed out and recorded once the client has proven it owns
+/// that keypair (WalletAuthMessage), so nobody can claim another id's stats.
+#[derive(Resource, Default)]
+pub struct StatsStore {
+    pub players: HashMap<u64, PlayerStats>,
+    /// Set when `players` has changes not yet written to disk.
+    pub dirty: bool,
+}
+
+impl StatsStore {
+    /// Load the stats file, or start empty if it is missing or unreadable.
+    pub fn load() -> Self {
+        let path = match stats_path() {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("[STATS] {} — lifetime stats won't be kept", e);
+                return Self::default();
+            }
+        };
+        let players = match fs::read_to_string(&path) {
+            Ok(data) => match serde_json::from_str(&data) {
+                Ok(players) => players,
+                Err(e) => {
+                    warn!("[STATS] Failed to parse {}: {} — starting empty", path.display(), e);
+                    HashMap::new()
+                }
+            },
+            Err(_) => HashMap::new(),
+        };
+        info!("[STATS] Loaded lifetime stats for {} player(s)", players.len());
+        Self { players, dirty: false }
+    }
+
+    /// Stats for a client id (default if never seen).
+    pub fn get(&self, client_id: u64) -> PlayerStats {
+        self.players.get(&client_id).cloned().unwrap_or_default()
+    }
+
+    /// Record the latest stats for a client id.
+    pub fn record(&mut self, client_id: u64, stats: &PlayerStats) {
+        self.players.insert(client_id, stats.clone());
+        self.dirty = true;
+    }
+
+    /// Write to disk if anything changed since the last save.
+    pub fn save_if_dirty(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let path = match stats_path() {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("[STATS] Not saving lifetime stats: {}", e);
+                return;
+            }
+        };
+        if let Some(parent) = path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                warn!("[STATS] Failed to create {}: {}", parent.display(), e);
+                return;
+            }
+        }
+        match serde_json::to_string_pretty(&self.players) {
+            Ok(json) => match fs::write(&path, json) {
+                Ok(()) => self.dirty = false,
+                Err(e) => warn!("[STATS] Failed to write {}: {}", path.display(), e),
+            },
+            Err(e) => warn!("[STATS] Failed to serialize stats: {}", e),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Small key → string store for client-side persistence.
+//!
+//! Native builds keep each key as a file in `~/.anima/`; browser (WASM)
+//! builds have no filesystem and use `window.localStorage` instead, with keys
+//! prefixed by `anima.`. Callers only see `read`/`write`.
+
+/// Directory under $HOME (native) / key prefix (web).
+const APP_DIR: &str = "anima";
+
+/// Human-readable location of `key`, for log messages.
+pub fn location(key: &str) -> String {
+    imp::location(key)
+}
+
+/// Read the value stored under `key`, if present.
+pub fn read(key: &str) -> Option<String> {
+    imp::read(key)
+}
+
+/// Store `value` under `key`, replacing any previous value.
+pub fn write(key: &str, value: &str) -> Result<(), String> {
+    imp::write(key, value)
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod imp {
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn path(key: &str) -> PathBuf {
+        let home = dirs::home_dir().expect("Could not find home directory");
+        home.join(format!(".{}", super::APP_DIR)).join(key)
+    }
+
+    pub fn location(key: &str) -> String {
+        path(key).display().to_string()
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        fs::read_to_string(path(key)).ok()
+    }
+
+    pub fn write(key: &str, value: &str) -> Result<(), String> {
+        let path = path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, value).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod imp {
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    fn storage_key(key: &str) -> String {
+        format!("{}.{}", super::APP_DIR, key)
+    }
+
+    pub fn location(key: &str) -> String {
+        format!("localStorage[{}]", storage_key(key))
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        storage()?.get_item(&storage_key(key)).ok()?
+    }
+
+    pub fn write(key: &str, value: &str) -> Result<(), String> {
+        let storage = storage().ok_or("localStorage unavailable")?;
+        storage
+            .set_item(&storage_key(key), value)
+            .map_err(|e| format!("{:?}", e))
+    }
+}
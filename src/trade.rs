@@ -0,0 +1,28 @@
+//! Trading station catalog — what a station sells and what it costs in
+//! gathered materials (`Resources`).
+//!
+//! The server validates every purchase against this table; clients only use
+//! it to draw the buy menu.
+
+use crate::protocol::Resources;
+
+/// One item a trading station sells.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TradeOffer {
+    /// Must match an `Equippable::name`; the item goes to the buyer's inventory.
+    pub item: &'static str,
+    pub ore_cost: u32,
+}
+
+impl TradeOffer {
+    /// Whether `resources` cover the price.
+    pub fn affordable(&self, resources: &Resources) -> bool {
+        resources.ore >= self.ore_cost
+    }
+}
+
+/// Everything a station sells, in menu order (the menu's number keys index this).
+pub const TRADE_OFFERS: &[TradeOffer] = &[
+    TradeOffer { item: "AK47", ore_cost: 1 },
+    TradeOffer { item: "Pickaxe", ore_cost: 1 },
+];
@@ -0,0 +1,141 @@
+//! Network transports — the IO layer lightyear's netcode runs on.
+//!
+//! Game code only deals with lightyear link entities (`Client`, `ClientOf`,
+//! `MessageSender`, ...). Which socket actually carries packets is decided
+//! once at startup by the `Transport` picked here, so adding e.g. WebTransport
+//! for browser clients means implementing the trait and registering it in
+//! `available_transports` — no gameplay or protocol changes.
+
+use std::net::SocketAddr;
+
+use bevy::prelude::*;
+#[cfg(not(target_family = "wasm"))]
+use lightyear::prelude::server::ServerUdpIo;
+use lightyear::prelude::*;
+
+/// An IO layer for netcode connections.
+pub trait Transport: Send + Sync + 'static {
+    /// Name used to select this transport with `--transport <name>`.
+    fn name(&self) -> &'static str;
+
+    /// Insert the IO components on the server entity (which already has
+    /// `NetcodeServer`), listening on `local_addr`. Errors if this transport
+    /// can't host a server here.
+    fn insert_server_io(&self, server: &mut EntityCommands, local_addr: SocketAddr) -> Result<(), String>;
+
+    /// Insert the IO components on the client entity (which already has
+    /// `NetcodeClient`), bound to `local_addr` and talking to `server_addr`.
+    fn insert_client_io(&self, client: &mut EntityCommands, local_addr: SocketAddr, server_addr: SocketAddr);
+}
+
+/// Plain UDP sockets — the native client/server default.
+#[cfg(not(target_family = "wasm"))]
+pub struct UdpTransport;
+
+#[cfg(not(target_family = "wasm"))]
+impl Transport for UdpTransport {
+    fn name(&self) -> &'static str {
+        "udp"
+    }
+
+    fn insert_server_io(&self, server: &mut EntityCommands, local_addr: SocketAddr) -> Result<(), String> {
+        server.insert((LocalAddr(local_addr), ServerUdpIo::default()));
+        Ok(())
+    }
+
+    fn insert_client_io(&self, client: &mut EntityCommands, local_addr: SocketAddr, server_addr: SocketAddr) {
+        client.insert((UdpIo::default(), LocalAddr(local_addr), PeerAddr(server_addr)));
+    }
+}
+
+/// WebTransport (HTTP/3 over QUIC) — the only option for browser clients.
+///
+/// The server generates a self-signed certificate at startup and logs its
+/// digest; clients pin it via `ANIMA_CERT_DIGEST` (runtime env var natively,
+/// build-time env var for WASM builds).
+#[cfg(feature = "webtransport")]
+pub struct WebTransportTransport;
+
+#[cfg(feature = "webtransport")]
+impl Transport for WebTransportTransport {
+    fn name(&self) -> &'static str {
+        "webtransport"
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn insert_server_io(&self, server: &mut EntityCommands, local_addr: SocketAddr) -> Result<(), String> {
+        use lightyear::prelude::server::{Identity, WebTransportServerIo};
+
+        let certificate = Identity::self_signed(["localhost", "127.0.0.1", "::1"])
+            .map_err(|e| format!("Failed to generate self-signed certificate: {}", e))?;
+        // Plain hex, as ANIMA_CERT_DIGEST takes it
+        let hash = certificate.certificate_chain().as_slice()[0].hash();
+        let digest: &[u8; 32] = hash.as_ref();
+        let digest: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        info!("[TRANSPORT] WebTransport certificate digest: {}", digest);
+        server.insert((LocalAddr(local_addr), WebTransportServerIo { certificate }));
+        Ok(())
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn insert_server_io(&self, _server: &mut EntityCommands, _local_addr: SocketAddr) -> Result<(), String> {
+        Err("The server cannot run in the browser".to_string())
+    }
+
+    fn insert_client_io(&self, client: &mut EntityCommands, local_addr: SocketAddr, server_addr: SocketAddr) {
+        use lightyear::prelude::client::WebTransportClientIo;
+
+        let certificate_digest = std::env::var("ANIMA_CERT_DIGEST")
+            .ok()
+            .or(option_env!("ANIMA_CERT_DIGEST").map(String::from))
+            .unwrap_or_default();
+        client.insert((
+            WebTransportClientIo { certificate_digest },
+            LocalAddr(local_addr),
+            PeerAddr(server_addr),
+        ));
+    }
+}
+
+/// Every transport this build can use. The first entry is the default:
+/// UDP natively, WebTransport in the browser.
+pub fn available_transports() -> Vec<Box<dyn Transport>> {
+    #[allow(unused_mut)]
+    let mut transports: Vec<Box<dyn Transport>> = Vec::new();
+    #[cfg(not(target_family = "wasm"))]
+    transports.push(Box::new(UdpTransport));
+    #[cfg(feature = "webtransport")]
+    transports.push(Box::new(WebTransportTransport));
+    transports
+}
+
+/// The transport selected for this process.
+#[derive(Resource)]
+pub struct ActiveTransport(pub Box<dyn Transport>);
+
+/// Parse --transport <name> from CLI args. Unknown names fall back to the
+/// default transport with a warning.
+pub fn transport_from_args() -> ActiveTransport {
+    let args: Vec<String> = std::env::args().collect();
+    let requested = args
+        .iter()
+        .position(|a| a == "--transport")
+        .and_then(|pos| args.get(pos + 1))
+        .cloned();
+
+    let mut transports = available_transports();
+    assert!(
+        !transports.is_empty(),
+        "No network transport in this build — WASM clients need --features webtransport"
+    );
+    let index = match requested {
+        Some(ref name) => transports.iter().position(|t| t.name() == name).unwrap_or_else(|| {
+            warn!("[TRANSPORT] Unknown transport '{}', using '{}'", name, transports[0].name());
+            0
+        }),
+        None => 0,
+    };
+    let transport = transports.swap_remove(index);
+    info!("[TRANSPORT] Using {}", transport.name());
+    ActiveTransport(transport)
+}
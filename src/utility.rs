@@ -0,0 +1,440 @@
+//! Throwable utility — frag grenades, flashbangs and smoke.
+//!
+//! Each player carries a `UtilityPouch` with a limited count per kind and one
+//! kind selected. The server spawns the thrown `ThrownUtility`, detonates it
+//! when its fuse runs out and applies the effect (damage, `Flashed`, or a
+//! `SmokeCloud`); clients only draw what is replicated. With `--homing`,
+//! chosen kinds also steer toward enemies in flight.
+
+use avian3d::prelude::*;
+use bevy::camera::visibility::RenderLayers;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::world::DEFAULT_RENDER_LAYER;
+
+/// Launch speed of a thrown utility (m/s), plus a little upward lob.
+pub const THROW_SPEED: f32 = 14.0;
+pub const THROW_LOB: f32 = 2.5;
+/// Radius of the thrown projectile's collider and mesh.
+pub const UTILITY_RADIUS: f32 = 0.08;
+
+/// Frag damage at the center of the blast; falls off linearly to 0 at the edge.
+pub const FRAG_DAMAGE: f32 = 120.0;
+/// A flashbang blinds players who face it (view direction within ~60° of it).
+pub const FLASH_FACING_DOT: f32 = 0.5;
+/// Longest blind, for a player looking straight at the flash.
+pub const FLASH_SECS: f32 = 3.0;
+/// Seconds a smoke cloud lingers, unless `--smoke-secs` says otherwise.
+pub const DEFAULT_SMOKE_SECS: f32 = 15.0;
+/// Most a shot fired through smoke is thrown off, per axis (radians).
+pub const SMOKE_SPREAD: f32 = 0.08;
+/// Fraction of its lifetime a smoke cloud spends fading out at the end.
+pub const SMOKE_FADE_FRACTION: f32 = 0.3;
+
+/// The kinds of utility, in pouch order.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum UtilityKind {
+    #[default]
+    Frag,
+    Flashbang,
+    Smoke,
+}
+
+impl UtilityKind {
+    pub const ALL: [UtilityKind; 3] = [UtilityKind::Frag, UtilityKind::Flashbang, UtilityKind::Smoke];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Frag => "Frag",
+            Self::Flashbang => "Flashbang",
+            Self::Smoke => "Smoke",
+        }
+    }
+
+    /// Parse a `--utility` entry name (`frag`, `flash`/`flashbang`, `smoke`).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "frag" | "grenade" => Some(Self::Frag),
+            "flash" | "flashbang" => Some(Self::Flashbang),
+            "smoke" => Some(Self::Smoke),
+            _ => None,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Frag => 0,
+            Self::Flashbang => 1,
+            Self::Smoke => 2,
+        }
+    }
+
+    /// Seconds between two throws of this kind by the same player.
+    pub fn cooldown_secs(self) -> f32 {
+        match self {
+            Self::Frag => 3.0,
+            Self::Flashbang => 3.0,
+            Self::Smoke => 5.0,
+        }
+    }
+
+    /// Seconds from the throw to detonation.
+    pub fn fuse_secs(self) -> f32 {
+        match self {
+            Self::Frag => 2.0,
+            Self::Flashbang => 1.5,
+            Self::Smoke => 1.5,
+        }
+    }
+
+    /// Reach of the effect (meters): blast radius, flash range, cloud radius.
+    pub fn radius(self) -> f32 {
+        match self {
+            Self::Frag => 6.0,
+            Self::Flashbang => 25.0,
+            Self::Smoke => 4.0,
+        }
+    }
+
+    /// Projectile color on the client.
+    pub fn color(self) -> Color {
+        match self {
+            Self::Frag => Color::srgb(0.25, 0.35, 0.2),
+            Self::Flashbang => Color::srgb(0.75, 0.75, 0.8),
+            Self::Smoke => Color::srgb(0.4, 0.4, 0.45),
+        }
+    }
+}
+
+/// A player's throwables: count per kind (indexed in `UtilityKind::ALL`
+/// order) and the kind the throw key uses. Server-authoritative, replicated.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct UtilityPouch {
+    pub counts: [u32; 3],
+    pub selected: UtilityKind,
+}
+
+impl UtilityPouch {
+    pub fn count(&self, kind: UtilityKind) -> u32 {
+        self.counts[kind.index()]
+    }
+
+    /// Take one of `kind` out of the pouch. False when there are none left.
+    pub fn take(&mut self, kind: UtilityKind) -> bool {
+        let count = &mut self.counts[kind.index()];
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+
+    /// Select the next kind the player still has (stays put if none).
+    pub fn cycle(&mut self) {
+        let start = self.selected.index();
+        for step in 1..=UtilityKind::ALL.len() {
+            let kind = UtilityKind::ALL[(start + step) % UtilityKind::ALL.len()];
+            if self.count(kind) > 0 {
+                self.selected = kind;
+                return;
+            }
+        }
+    }
+}
+
+/// Server-only: what each player spawns with. Set with
+/// `--utility frag=2,flash=2,smoke=1`.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct UtilityLoadout(pub [u32; 3]);
+
+impl Default for UtilityLoadout {
+    fn default() -> Self {
+        Self([1, 1, 1])
+    }
+}
+
+impl UtilityLoadout {
+    /// A full pouch, with the first stocked kind selected.
+    pub fn pouch(&self) -> UtilityPouch {
+        let selected = UtilityKind::ALL
+            .into_iter()
+            .find(|kind| self.0[kind.index()] > 0)
+            .unwrap_or_default();
+        UtilityPouch { counts: self.0, selected }
+    }
+}
+
+/// Parse --utility <kind=count,...> from CLI args. Kinds not listed keep the
+/// default count; unknown entries are ignored.
+pub fn parse_utility_loadout() -> UtilityLoadout {
+    let args: Vec<String> = std::env::args().collect();
+    let mut loadout = UtilityLoadout::default();
+    let Some(list) = args
+        .iter()
+        .position(|a| a == "--utility")
+        .and_then(|pos| args.get(pos + 1))
+    else {
+        return loadout;
+    };
+    for entry in list.split(',') {
+        let parsed = entry
+            .split_once('=')
+            .and_then(|(kind, count)| Some((UtilityKind::parse(kind)?, count.trim().parse::<u32>().ok()?)));
+        match parsed {
+            Some((kind, count)) => loadout.0[kind.index()] = count,
+            None => warn!("[UTILITY] Ignoring --utility entry {:?} (expected <kind>=<count>)", entry),
+        }
+    }
+    info!("[UTILITY] Spawn pouch: {:?}", loadout.0);
+    loadout
+}
+
+/// A utility in flight. Server-spawned, replicated with its Position.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ThrownUtility {
+    pub kind: UtilityKind,
+    /// PlayerId of the thrower (frag kills are credited to them).
+    pub thrower: u64,
+}
+
+/// The player is blinded by a flashbang for `secs` from when this was
+/// (re)inserted. Server-authoritative, replicated; removed when it wears off.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Flashed {
+    pub secs: f32,
+}
+
+/// A lingering smoke cloud. Server-spawned, replicated with its Position.
+/// It blocks sight (flashes, nameplates, the player behind it) and shots
+/// through it, and lasts `secs`, thinning out towards the end.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SmokeCloud {
+    pub radius: f32,
+    pub secs: f32,
+    /// Whole seconds until the server despawns it, rounded up. The server
+    /// only writes it when the value changes, so it replicates once per
+    /// second and a client that joins late still fades in step.
+    pub remaining_secs: u32,
+}
+
+impl SmokeCloud {
+    /// Opacity multiplier with `remaining` seconds of the cloud left.
+    pub fn density(&self, remaining: f32) -> f32 {
+        let fade_secs = self.secs * SMOKE_FADE_FRACTION;
+        (remaining / fade_secs.max(f32::EPSILON)).clamp(0.0, 1.0)
+    }
+}
+
+/// What happens to a hitscan shot whose path crosses smoke.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SmokeShots {
+    /// The shot is thrown off by up to SMOKE_SPREAD.
+    #[default]
+    Degrade,
+    /// The shot does nothing.
+    Block,
+}
+
+/// Server-only smoke tuning (`--smoke-secs <s>`, `--smoke-blocks-shots`).
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct SmokeSettings {
+    pub secs: f32,
+    pub shots: SmokeShots,
+}
+
+impl Default for SmokeSettings {
+    fn default() -> Self {
+        Self { secs: DEFAULT_SMOKE_SECS, shots: SmokeShots::Degrade }
+    }
+}
+
+/// Parse --smoke-secs <secs> and --smoke-blocks-shots from CLI args.
+pub fn parse_smoke_settings() -> SmokeSettings {
+    let args: Vec<String> = std::env::args().collect();
+    let mut settings = SmokeSettings::default();
+    if let Some(secs) = args
+        .iter()
+        .position(|a| a == "--smoke-secs")
+        .and_then(|pos| args.get(pos + 1))
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|s| *s > 0.0)
+    {
+        settings.secs = secs;
+    }
+    if args.iter().any(|a| a == "--smoke-blocks-shots") {
+        settings.shots = SmokeShots::Block;
+    }
+    settings
+}
+
+/// Homing utilities only lock on to players within this many meters.
+pub const HOMING_RANGE: f32 = 25.0;
+
+/// Server-only: thrown utilities of `kinds` steer toward the nearest enemy
+/// within `cone` radians of their flight direction, turning at most
+/// `turn_rate` radians per second. Absent when homing is off (the default).
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct HomingSettings {
+    pub kinds: Vec<UtilityKind>,
+    pub cone: f32,
+    pub turn_rate: f32,
+}
+
+impl HomingSettings {
+    /// `velocity` turned toward `to_target` by at most `max_turn` radians,
+    /// keeping its speed. Unchanged when the target is outside the cone.
+    pub fn steer(&self, velocity: Vec3, to_target: Vec3, max_turn: f32) -> Vec3 {
+        let (Some(heading), Some(wanted)) = (velocity.try_normalize(), to_target.try_normalize()) else {
+            return velocity;
+        };
+        let angle = heading.angle_between(wanted);
+        if angle > self.cone || angle <= f32::EPSILON {
+            return velocity;
+        }
+        let turned = heading.slerp(wanted, (max_turn / angle).min(1.0));
+        turned * velocity.length()
+    }
+}
+
+/// Parse --homing <kind,...>, --homing-cone <degrees> (default 30) and
+/// --homing-turn-rate <degrees/s> (default 90) from CLI args.
+pub fn parse_homing_settings() -> Option<HomingSettings> {
+    let args: Vec<String> = std::env::args().collect();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|pos| args.get(pos + 1))
+    };
+    let kinds: Vec<UtilityKind> = value("--homing")?.split(',').filter_map(UtilityKind::parse).collect();
+    if kinds.is_empty() {
+        warn!("[UTILITY] --homing lists no known utility kinds, homing is off");
+        return None;
+    }
+    let degrees = |flag: &str, default: f32| {
+        value(flag)
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|v| *v > 0.0)
+            .unwrap_or(default)
+            .to_radians()
+    };
+    let settings = HomingSettings {
+        kinds,
+        cone: degrees("--homing-cone", 30.0).min(std::f32::consts::PI),
+        turn_rate: degrees("--homing-turn-rate", 90.0),
+    };
+    info!("[UTILITY] Homing: {:?}", settings);
+    Some(settings)
+}
+
+/// Blind duration for a player at `eye` looking along `view` when a flash
+/// goes off at `flash`. Zero when out of range or facing away; line of sight
+/// is checked by the caller.
+pub fn flash_duration(eye: Vec3, view: Vec3, flash: Vec3) -> f32 {
+    let to_flash = flash - eye;
+    let distance = to_flash.length();
+    if distance > UtilityKind::Flashbang.radius() || distance < f32::EPSILON {
+        return if distance < f32::EPSILON { FLASH_SECS } else { 0.0 };
+    }
+    let facing = view.normalize_or_zero().dot(to_flash / distance);
+    if facing < FLASH_FACING_DOT {
+        return 0.0;
+    }
+    // Looking straight at it blinds longest; nearer flashes blind longer
+    let along = (facing - FLASH_FACING_DOT) / (1.0 - FLASH_FACING_DOT);
+    let near = 1.0 - distance / UtilityKind::Flashbang.radius();
+    FLASH_SECS * (0.5 + 0.5 * along) * (0.5 + 0.5 * near)
+}
+
+/// Whether the segment `from`→`to` passes through any smoke cloud
+/// (`(center, radius)` pairs). Smoke blocks flashes and nameplates.
+pub fn smoke_between(from: Vec3, to: Vec3, clouds: impl IntoIterator<Item = (Vec3, f32)>) -> bool {
+    let segment = to - from;
+    let length_sq = segment.length_squared();
+    clouds.into_iter().any(|(center, radius)| {
+        let t = if length_sq > 0.0 {
+            ((center - from).dot(segment) / length_sq).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (from + segment * t).distance_squared(center) <= radius * radius
+    })
+}
+
+/// Client-only: when a smoke cloud runs out, on our clock. Set from the
+/// replicated `SmokeCloud::remaining_secs` whenever it changes.
+#[derive(Component)]
+pub struct SmokeExpiry {
+    pub expires_at: f32,
+}
+
+/// Base opacity of a smoke cloud before it starts fading.
+const SMOKE_ALPHA: f32 = 0.92;
+
+/// Client-only: meshes for thrown utilities and smoke clouds as they replicate in.
+pub fn init_replicated_utilities(
+    thrown: Query<(Entity, &ThrownUtility, &Position), Added<ThrownUtility>>,
+    smoke: Query<(Entity, &SmokeCloud, &Position), Added<SmokeCloud>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    for (entity, utility, pos) in thrown.iter() {
+        commands.entity(entity).insert((
+            Mesh3d(meshes.add(Sphere::new(UTILITY_RADIUS))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: utility.kind.color(),
+                ..default()
+            })),
+            Transform::from_translation(pos.0),
+            Visibility::default(),
+            RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+        ));
+    }
+    for (entity, cloud, pos) in smoke.iter() {
+        commands.entity(entity).insert((
+            Mesh3d(meshes.add(Sphere::new(cloud.radius))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgba(0.7, 0.7, 0.72, SMOKE_ALPHA),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                cull_mode: None,
+                ..default()
+            })),
+            Transform::from_translation(pos.0),
+            Visibility::default(),
+            RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+            SmokeExpiry { expires_at: time.elapsed_secs() + cloud.remaining_secs as f32 },
+        ));
+    }
+}
+
+/// Client-only: thins smoke clouds out over the end of their lifetime,
+/// re-syncing the expiry with the server each time `remaining_secs` ticks down.
+pub fn fade_smoke_clouds(
+    mut query: Query<(Ref<SmokeCloud>, &mut SmokeExpiry, &MeshMaterial3d<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (cloud, mut expiry, material) in query.iter_mut() {
+        if cloud.is_changed() {
+            expiry.expires_at = now + cloud.remaining_secs as f32;
+        }
+        let density = cloud.density(expiry.expires_at - now);
+        if density >= 1.0 {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color.set_alpha(SMOKE_ALPHA * density);
+        }
+    }
+}
+
+/// Client-only: moves thrown utility meshes with their replicated Position.
+pub fn sync_thrown_utilities(mut query: Query<(&Position, &mut Transform), With<ThrownUtility>>) {
+    for (pos, mut transform) in query.iter_mut() {
+        transform.translation = pos.0;
+    }
+}
@@ -0,0 +1,230 @@
+//! Weapon catalog — per-weapon properties keyed by the `Equippable` name.
+//!
+//! Gameplay constants shared by every gun (damage, range, cooldown) still live
+//! in `world`; this table holds what differs between individual weapons.
+
+/// Laser sight attached to a weapon: a thin beam from the muzzle along the aim
+/// direction, ending in a dot on the first surface hit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaserSightSpec {
+    /// Beam/dot color (linear RGB, values > 1.0 glow).
+    pub color: [f32; 3],
+    /// Maximum beam length in meters.
+    pub range: f32,
+    /// Whether other players see this laser too, or only the shooter.
+    pub visible_to_others: bool,
+}
+
+/// Shape of the camera's return to the original aim after recoil. Only the
+/// one shape so far; a weapon that needs another adds a variant here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecoveryCurve {
+    /// Fast at first, settling gently onto the original aim.
+    EaseOut,
+}
+
+impl RecoveryCurve {
+    /// Fraction of the kick still applied at recovery progress `t` (0..=1).
+    pub fn remaining(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::EaseOut => (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// Visual camera kick per shot and how it recovers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecoilSpec {
+    /// Upward camera kick per shot (radians).
+    pub kick: f32,
+    /// Cap on accumulated kick during a burst (radians).
+    pub max_kick: f32,
+    /// Seconds to return fully to the original aim after the last shot.
+    pub recovery_secs: f32,
+    pub curve: RecoveryCurve,
+}
+
+/// Fixed aim offsets for consecutive shots, applied to the hitscan ray on
+/// the server (and the predicted tracer), so a burst always walks the same
+/// way and can be learned and pulled against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpreadPattern {
+    /// [yaw, pitch] offsets in radians for the 1st, 2nd, ... shot of a burst.
+    /// Positive pitch is up, positive yaw is left. The last entry repeats.
+    pub offsets: &'static [[f32; 2]],
+    /// Seconds without firing after which the next shot starts a new burst.
+    pub reset_secs: f32,
+}
+
+/// One-shot "inspect" animation on the view model: the item is raised,
+/// turned and tilted to show one side, rolled over to the other, then lowered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InspectSpec {
+    /// Total length of the animation in seconds.
+    pub duration: f32,
+    /// Yaw toward the camera at the peak of the first pose (radians).
+    pub turn: f32,
+    /// Roll at the peak of the first pose (radians).
+    pub tilt: f32,
+    /// Offset (meters, camera space) the item is raised/pulled in by.
+    pub lift: [f32; 3],
+}
+
+/// Inspect animation for items without their own (tools, unknown items).
+pub const DEFAULT_INSPECT: InspectSpec = InspectSpec {
+    duration: 1.6,
+    turn: 0.6,
+    tilt: 0.3,
+    lift: [-0.05, 0.03, 0.0],
+};
+
+/// Damage multiplier applied for each surface a round passes through.
+pub const PENETRATION_DAMAGE_FALLOFF: f32 = 0.5;
+/// Damage multiplier applied for each ricochet.
+pub const RICOCHET_DAMAGE_FALLOFF: f32 = 0.6;
+
+/// Static description of one weapon.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WeaponSpec {
+    /// Must match `Equippable::name`.
+    pub name: &'static str,
+    /// Higher is better. Auto-equip switches to a picked-up weapon that outranks
+    /// the one in hand.
+    pub rank: u32,
+    /// Optional laser sight drawn while the weapon is equipped.
+    pub laser_sight: Option<LaserSightSpec>,
+    /// Total thickness of non-player colliders (meters) a round can pass
+    /// through. 0.0 stops at the first surface.
+    pub penetration: f32,
+    /// Times a round stopped by a surface bounces off it before it's spent,
+    /// when the server runs with --ricochets. 0 never ricochets.
+    pub ricochets: u32,
+    /// Camera recoil while firing. None for no kick.
+    pub recoil: Option<RecoilSpec>,
+    /// Inspect animation. None uses DEFAULT_INSPECT.
+    pub inspect: Option<InspectSpec>,
+    /// Draw a tracer on every Nth round only, like belted tracer ammo.
+    /// 1 (or 0) traces every round.
+    pub tracer_every: u32,
+    /// Shot-by-shot aim offsets during a burst. None fires dead on.
+    pub spread: Option<SpreadPattern>,
+}
+
+/// All weapons known to the game.
+pub const WEAPONS: &[WeaponSpec] = &[WeaponSpec {
+    name: "AK47",
+    rank: 10,
+    laser_sight: Some(LaserSightSpec {
+        color: [4.0, 0.1, 0.1],
+        range: 100.0,
+        visible_to_others: false,
+    }),
+    penetration: 0.3,
+    ricochets: 1,
+    recoil: Some(RecoilSpec {
+        kick: 0.025,
+        max_kick: 0.12,
+        recovery_secs: 0.4,
+        curve: RecoveryCurve::EaseOut,
+    }),
+    inspect: Some(InspectSpec {
+        duration: 2.2,
+        turn: 0.9,
+        tilt: 0.5,
+        lift: [-0.08, 0.05, 0.05],
+    }),
+    // Belted like a machine gun: every 5th round is a tracer
+    tracer_every: 5,
+    // Climbs, then drifts right and back left
+    spread: Some(SpreadPattern {
+        offsets: &[
+            [0.0, 0.0],
+            [0.0, 0.010],
+            [-0.002, 0.019],
+            [-0.005, 0.026],
+            [-0.008, 0.031],
+            [-0.006, 0.034],
+            [0.0, 0.036],
+            [0.006, 0.037],
+        ],
+        reset_secs: 0.5,
+    }),
+}];
+
+/// Look up a weapon by its equippable name.
+pub fn weapon_spec(name: &str) -> Option<&'static WeaponSpec> {
+    WEAPONS.iter().find(|w| w.name == name)
+}
+
+/// Inspect animation for the named item.
+pub fn inspect_spec(name: &str) -> InspectSpec {
+    weapon_spec(name).and_then(|w| w.inspect).unwrap_or(DEFAULT_INSPECT)
+}
+
+/// Penetration budget for the named weapon (0.0 for unknown items).
+pub fn penetration(name: &str) -> f32 {
+    weapon_spec(name).map(|w| w.penetration).unwrap_or(0.0)
+}
+
+/// Ricochets allowed for the named weapon (0 for unknown items).
+pub fn ricochets(name: &str) -> u32 {
+    weapon_spec(name).map(|w| w.ricochets).unwrap_or(0)
+}
+
+/// Whether round number `round` (1-based, per shooter) of the named weapon
+/// draws a tracer. Unknown items trace every round.
+pub fn shows_tracer(name: &str, round: u32) -> bool {
+    let every = weapon_spec(name).map(|w| w.tracer_every).unwrap_or(1).max(1);
+    round % every == 0
+}
+
+/// Index into the named weapon's spread pattern for a shot fired at `now`,
+/// given the time and index of the shooter's previous shot (if any).
+pub fn burst_index(name: &str, previous: Option<(f32, u32)>, now: f32) -> u32 {
+    let Some(pattern) = weapon_spec(name).and_then(|w| w.spread) else { return 0 };
+    match previous {
+        Some((last, index)) if now - last <= pattern.reset_secs => index + 1,
+        _ => 0,
+    }
+}
+
+/// [yaw, pitch] aim offset (radians) for shot `index` of a burst.
+pub fn spread_offset(name: &str, index: u32) -> [f32; 2] {
+    let Some(pattern) = weapon_spec(name).and_then(|w| w.spread) else { return [0.0, 0.0] };
+    let Some(last) = pattern.offsets.len().checked_sub(1) else { return [0.0, 0.0] };
+    pattern.offsets[(index as usize).min(last)]
+}
+
+/// Damage left after passing through `surfaces` penetrable surfaces.
+pub fn penetrated_damage(base: i32, surfaces: u32) -> i32 {
+    (base as f32 * PENETRATION_DAMAGE_FALLOFF.powi(surfaces as i32)).round() as i32
+}
+
+/// Damage left after `bounces` ricochets.
+pub fn ricochet_damage(base: i32, bounces: u32) -> i32 {
+    (base as f32 * RICOCHET_DAMAGE_FALLOFF.powi(bounces as i32)).round() as i32
+}
+
+/// Whether `candidate` should replace `current` in hand under auto-equip.
+/// Any weapon beats a non-weapon tool; between weapons the higher rank wins.
+pub fn outranks(candidate: &str, current: &str) -> bool {
+    match (weapon_spec(candidate), weapon_spec(current)) {
+        (Some(new), Some(held)) => new.rank > held.rank,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracer_every_fifth_round() {
+        let tracers: Vec<u32> = (1..=12).filter(|round| shows_tracer("AK47", *round)).collect();
+        assert_eq!(tracers, vec![5, 10]);
+        // Unknown items trace every round
+        assert!((1..=3).all(|round| shows_tracer("Pickaxe", round)));
+    }
+}
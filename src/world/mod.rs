@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use avian3d::prelude::*;
 use bevy::camera::visibility::RenderLayers;
 use bevy::gltf::GltfAssetLabel;
@@ -7,17 +9,60 @@ use lightyear::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::player::VIEW_MODEL_RENDER_LAYER;
-use crate::protocol::{PlayerActions, PlayerEquipped, PlayerHealth, PlayerId, PlayerPitch, PlayerYaw};
+use crate::protocol::{PlayerActions, PlayerEquipped, PlayerHealth, PlayerId, PlayerInventory, PlayerPitch, PlayerYaw};
 
 #[derive(Debug, Component)]
 pub struct WorldModelCamera;
 
 pub const DEFAULT_RENDER_LAYER: usize = 0;
 
+/// Tools that `Interactable`s can require.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToolType {
+    Pickaxe,
+}
+
+/// What an item does when held and Primary is pressed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemKind {
+    /// Works `Interactable`s that require this tool (or none).
+    Tool { tool_type: ToolType },
+    /// Hitscan gun. Per-weapon properties live in `weapons::WEAPONS`.
+    Weapon,
+    /// Carried only (e.g. ore chunks).
+    Misc,
+}
+
+impl ItemKind {
+    /// Kind of the item with this name. Held items are tracked by name
+    /// (`PlayerEquipped`), so systems without the `Equippable` look it up here.
+    pub fn of(name: &str) -> Self {
+        if crate::weapons::weapon_spec(name).is_some() {
+            return Self::Weapon;
+        }
+        match name {
+            "Pickaxe" => Self::Tool { tool_type: ToolType::Pickaxe },
+            _ => Self::Misc,
+        }
+    }
+
+    pub fn is_weapon(self) -> bool {
+        matches!(self, Self::Weapon)
+    }
+
+    pub fn tool_type(self) -> Option<ToolType> {
+        match self {
+            Self::Tool { tool_type } => Some(tool_type),
+            _ => None,
+        }
+    }
+}
+
 /// Component for items that can be equipped by the player.
 /// Replicated from server to all clients.
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Equippable {
+    /// Identifies the item; its `ItemKind` follows from it (`ItemKind::of`).
     pub name: String,
     pub model_path: String,
     pub interaction_distance: f32,
@@ -43,62 +88,107 @@ pub struct BulletTracer {
     pub lifetime: f32,
 }
 
+/// Client-only: a short-lived muzzle or impact flash. Its point light (and
+/// the impact's glow sphere) fades out over `lifetime`; the sphere also
+/// grows to `expand` times its size.
+#[derive(Component)]
+pub struct ShotFlash {
+    pub spawn_time: f32,
+    pub lifetime: f32,
+    pub intensity: f32,
+    pub expand: f32,
+}
+
+const MUZZLE_FLASH_SECS: f32 = 0.05;
+const MUZZLE_FLASH_INTENSITY: f32 = 60_000.0;
+const IMPACT_FLASH_SECS: f32 = 0.12;
+const IMPACT_FLASH_INTENSITY: f32 = 15_000.0;
+const IMPACT_FLASH_RADIUS: f32 = 0.06;
+const IMPACT_FLASH_EXPAND: f32 = 3.0;
+
 /// Event fired when a shot happens — client uses this to spawn visual tracer.
 #[derive(Event)]
 pub struct ShotFired {
     pub muzzle: Vec3,
     pub hit_point: Vec3,
+    /// Points where the round ricocheted, in order, between muzzle and hit_point.
+    pub ricochets: Vec<Vec3>,
+    /// Whether this round is a tracer round (see `WeaponSpec::tracer_every`).
+    pub tracer: bool,
 }
 
-/// Client-only observer: spawns a red tracer mesh when a shot is fired.
+/// Client-only observer: muzzle and impact flashes for every round, and a red
+/// tracer mesh when a tracer round is fired.
 pub fn spawn_tracer(
     trigger: On<ShotFired>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    time: Res<Time>,
+    time: Res<Time<crate::player::Presentation>>,
 ) {
     let shot = trigger.event();
-    let diff = shot.hit_point - shot.muzzle;
-    let length = diff.length();
-    let dir = diff / length;
-    let midpoint = shot.muzzle + dir * (length / 2.0);
-
-    // Cylinder extends along local Y — rotate so Y aligns with shot direction
-    let rotation = Quat::from_rotation_arc(Vec3::Y, dir);
-
-    commands.spawn((
-        Mesh3d(meshes.add(Cylinder::new(0.01, length))),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::srgb(1.0, 0.1, 0.1),
-            emissive: bevy::color::LinearRgba::new(5.0, 0.2, 0.2, 1.0),
-            unlit: true,
-            ..default()
-        })),
-        Transform::from_translation(midpoint).with_rotation(rotation),
-        BulletTracer {
-            spawn_time: time.elapsed_secs(),
-            lifetime: 0.08,
-        },
-    ));
+    spawn_shot_flashes(&mut commands, &mut meshes, &mut materials, time.elapsed_secs(), shot.muzzle, shot.hit_point);
+    if !shot.tracer {
+        return;
+    }
+    spawn_tracer_path(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        time.elapsed_secs(),
+        shot.muzzle,
+        &shot.ricochets,
+        shot.hit_point,
+    );
 }
 
-/// Client-only: spawns tracers for remote players when their LastShot changes.
+/// Client-only: spawns tracers and flashes for remote players when their
+/// LastShot changes.
 pub fn remote_shot_tracers(
     query: Query<&crate::protocol::LastShot, (Changed<crate::protocol::LastShot>, With<Interpolated>)>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    time: Res<Time>,
+    time: Res<Time<crate::player::Presentation>>,
 ) {
     for shot in query.iter() {
         if shot.tick == 0 { continue; } // default, no shot yet
-        // Spawn tracer
-        let diff = shot.hit_point - shot.muzzle;
+        spawn_shot_flashes(&mut commands, &mut meshes, &mut materials, time.elapsed_secs(), shot.muzzle, shot.hit_point);
+        spawn_tracer_path(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            time.elapsed_secs(),
+            shot.muzzle,
+            &shot.ricochets,
+            shot.hit_point,
+        );
+    }
+}
+
+/// Spawns one tracer segment per leg of a round's path:
+/// muzzle → each ricochet point → hit point.
+fn spawn_tracer_path(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    now: f32,
+    muzzle: Vec3,
+    ricochets: &[Vec3],
+    hit_point: Vec3,
+) {
+    let points: Vec<Vec3> = std::iter::once(muzzle)
+        .chain(ricochets.iter().copied())
+        .chain(std::iter::once(hit_point))
+        .collect();
+    for leg in points.windows(2) {
+        let diff = leg[1] - leg[0];
         let length = diff.length();
         if length < 0.01 { continue; }
         let dir = diff / length;
-        let midpoint = shot.muzzle + dir * (length / 2.0);
+        let midpoint = leg[0] + dir * (length / 2.0);
+
+        // Cylinder extends along local Y — rotate so Y aligns with shot direction
         let rotation = Quat::from_rotation_arc(Vec3::Y, dir);
 
         commands.spawn((
@@ -111,17 +201,105 @@ pub fn remote_shot_tracers(
             })),
             Transform::from_translation(midpoint).with_rotation(rotation),
             BulletTracer {
-                spawn_time: time.elapsed_secs(),
+                spawn_time: now,
                 lifetime: 0.08,
             },
         ));
     }
 }
 
+/// A point light at the muzzle, and a light plus an expanding glow sphere
+/// where the round landed.
+fn spawn_shot_flashes(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    now: f32,
+    muzzle: Vec3,
+    hit_point: Vec3,
+) {
+    commands.spawn((
+        PointLight {
+            color: Color::srgb(1.0, 0.75, 0.35),
+            intensity: MUZZLE_FLASH_INTENSITY,
+            range: 6.0,
+            shadows_enabled: false,
+            ..default()
+        },
+        Transform::from_translation(muzzle),
+        ShotFlash {
+            spawn_time: now,
+            lifetime: MUZZLE_FLASH_SECS,
+            intensity: MUZZLE_FLASH_INTENSITY,
+            expand: 1.0,
+        },
+    ));
+    commands
+        .spawn((
+            Mesh3d(meshes.add(Sphere::new(IMPACT_FLASH_RADIUS))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgba(1.0, 0.8, 0.5, 1.0),
+                emissive: bevy::color::LinearRgba::new(4.0, 2.5, 1.0, 1.0),
+                unlit: true,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            })),
+            Transform::from_translation(hit_point),
+            RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+            bevy::light::NotShadowCaster,
+            ShotFlash {
+                spawn_time: now,
+                lifetime: IMPACT_FLASH_SECS,
+                intensity: IMPACT_FLASH_INTENSITY,
+                expand: IMPACT_FLASH_EXPAND,
+            },
+        ))
+        .with_child(PointLight {
+            color: Color::srgb(1.0, 0.7, 0.4),
+            intensity: IMPACT_FLASH_INTENSITY,
+            range: 3.0,
+            shadows_enabled: false,
+            ..default()
+        });
+}
+
+/// Client-only: fades shot flashes out and despawns them when done.
+pub fn animate_shot_flashes(
+    mut flashes: Query<(Entity, &ShotFlash, &mut Transform, Option<&mut PointLight>, Option<&MeshMaterial3d<StandardMaterial>>, Option<&Children>)>,
+    mut child_lights: Query<&mut PointLight, Without<ShotFlash>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time<crate::player::Presentation>>,
+    mut commands: Commands,
+) {
+    let now = time.elapsed_secs();
+    for (entity, flash, mut transform, light, material, children) in flashes.iter_mut() {
+        let t = (now - flash.spawn_time) / flash.lifetime;
+        if t >= 1.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let fade = 1.0 - t;
+        if let Some(mut light) = light {
+            light.intensity = flash.intensity * fade;
+        }
+        if let Some(children) = children {
+            for child in children.iter() {
+                if let Ok(mut light) = child_lights.get_mut(child) {
+                    light.intensity = flash.intensity * fade;
+                }
+            }
+        }
+        transform.scale = Vec3::splat(1.0 + (flash.expand - 1.0) * t);
+        if let Some(material) = material.and_then(|m| materials.get_mut(&m.0)) {
+            material.base_color.set_alpha(fade);
+        }
+    }
+}
+
 /// Client-only: despawns tracers after their lifetime expires.
 pub fn cleanup_tracers(
     query: Query<(Entity, &BulletTracer)>,
-    time: Res<Time>,
+    time: Res<Time<crate::player::Presentation>>,
     mut commands: Commands,
 ) {
     let now = time.elapsed_secs();
@@ -132,12 +310,292 @@ pub fn cleanup_tracers(
     }
 }
 
+/// Flag pole height (meters) and banner offset on a carrier's back.
+const FLAG_POLE_HEIGHT: f32 = 2.5;
+const FLAG_CARRY_OFFSET: Vec3 = Vec3::new(0.0, -0.2, 0.35);
+
+/// Client-only: adds a pole and team-colored banner to replicated flags.
+pub fn init_replicated_flags(
+    query: Query<(Entity, &Flag, &Position), Added<Flag>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, flag, pos) in query.iter() {
+        info!("init_replicated_flags: {:?} flag at {:?}", flag.team, pos.0);
+        let color = match flag.team {
+            crate::protocol::Team::Red => Color::srgb(0.9, 0.15, 0.1),
+            crate::protocol::Team::Blue => Color::srgb(0.1, 0.3, 0.95),
+        };
+        commands
+            .entity(entity)
+            .insert((
+                Transform::from_translation(pos.0),
+                Visibility::default(),
+                RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Mesh3d(meshes.add(Cylinder::new(0.03, FLAG_POLE_HEIGHT))),
+                    MeshMaterial3d(materials.add(Color::srgb(0.6, 0.6, 0.6))),
+                    Transform::from_xyz(0.0, FLAG_POLE_HEIGHT * 0.5, 0.0),
+                    RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+                ));
+                parent.spawn((
+                    Mesh3d(meshes.add(Cuboid::new(0.8, 0.5, 0.02))),
+                    MeshMaterial3d(materials.add(StandardMaterial {
+                        base_color: color,
+                        emissive: color.to_linear() * 0.5,
+                        ..default()
+                    })),
+                    Transform::from_xyz(0.4, FLAG_POLE_HEIGHT - 0.3, 0.0),
+                    RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+                ));
+            });
+    }
+}
+
+/// Client-only: places flags. A carried flag rides on the carrier's back
+/// (hidden for our own carrier, whose body isn't drawn); otherwise it stands
+/// at its replicated Position.
+pub fn sync_flag_transforms(
+    mut flags: Query<(&Flag, &Position, &mut Transform, &mut Visibility), Without<PlayerId>>,
+    players: Query<(&PlayerId, &Transform, Has<lightyear::prelude::Controlled>)>,
+) {
+    for (flag, pos, mut transform, mut visibility) in flags.iter_mut() {
+        let carrier = flag.carrier.and_then(|id| players.iter().find(|(pid, ..)| pid.0 == id));
+        match carrier {
+            Some((_, carrier_transform, is_local)) => {
+                transform.translation = carrier_transform.translation + carrier_transform.rotation * FLAG_CARRY_OFFSET;
+                transform.rotation = carrier_transform.rotation;
+                *visibility = if is_local { Visibility::Hidden } else { Visibility::Inherited };
+            }
+            None => {
+                transform.translation = pos.0;
+                transform.rotation = Quat::IDENTITY;
+                *visibility = Visibility::Inherited;
+            }
+        }
+    }
+}
+
+/// Client-only: rotating light beam over an available map weapon spawn.
+#[derive(Component)]
+pub struct WeaponGlow {
+    /// Equippable entity with the WeaponSpawn this glow belongs to.
+    pub owner: Entity,
+}
+
+/// Beam height (meters) and rotation speed (radians per second).
+const WEAPON_GLOW_HEIGHT: f32 = 3.0;
+const WEAPON_GLOW_SPIN: f32 = 1.5;
+
+/// Client-only: a slowly rotating beam of light over each map weapon, shown
+/// while the server reports the weapon available at its spawn.
+pub fn update_weapon_spawn_glow(
+    spawns: Query<(Entity, &WeaponSpawn)>,
+    mut glows: Query<(Entity, &WeaponGlow, &mut Transform, &mut Visibility)>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time<crate::player::Presentation>>,
+) {
+    let spin = Quat::from_rotation_y(time.elapsed_secs() * WEAPON_GLOW_SPIN);
+
+    for (glow_entity, glow, mut transform, mut visibility) in glows.iter_mut() {
+        let Ok((_, spawn)) = spawns.get(glow.owner) else {
+            commands.entity(glow_entity).despawn();
+            continue;
+        };
+        transform.translation = spawn.home + Vec3::Y * (WEAPON_GLOW_HEIGHT * 0.5);
+        transform.rotation = spin;
+        *visibility = if spawn.available { Visibility::Inherited } else { Visibility::Hidden };
+    }
+
+    for (owner, spawn) in spawns.iter() {
+        if glows.iter().any(|(_, glow, ..)| glow.owner == owner) {
+            continue;
+        }
+        commands
+            .spawn((
+                Mesh3d(meshes.add(Cuboid::new(0.4, WEAPON_GLOW_HEIGHT, 0.01))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Color::srgba(1.0, 0.85, 0.4, 0.15),
+                    emissive: bevy::color::LinearRgba::new(2.0, 1.6, 0.6, 1.0),
+                    alpha_mode: AlphaMode::Add,
+                    unlit: true,
+                    cull_mode: None,
+                    ..default()
+                })),
+                Transform::from_translation(spawn.home + Vec3::Y * (WEAPON_GLOW_HEIGHT * 0.5)),
+                Visibility::Hidden,
+                RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+                WeaponGlow { owner },
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    PointLight {
+                        color: Color::srgb(1.0, 0.85, 0.4),
+                        intensity: 20_000.0,
+                        range: 3.0,
+                        shadows_enabled: false,
+                        ..default()
+                    },
+                    Transform::from_xyz(0.0, -WEAPON_GLOW_HEIGHT * 0.5 + 0.3, 0.0),
+                ));
+            });
+    }
+}
+
+/// Client-only: one half (beam or dot) of a player's laser sight.
+#[derive(Component)]
+pub struct LaserSightPart {
+    /// Player entity whose weapon owns this laser.
+    pub owner: Entity,
+    /// True for the impact dot, false for the beam.
+    pub dot: bool,
+    /// The laser this part was built for; a weapon with a different laser
+    /// gets fresh parts.
+    pub spec: crate::weapons::LaserSightSpec,
+}
+
+/// Beams shorter than this (the muzzle is inside a wall) are hidden.
+const LASER_MIN_LENGTH: f32 = 1e-4;
+
+/// Client-only: draws laser sights for players holding a laser-sighted weapon.
+/// Ray casts from the muzzle along the aim direction every frame and stretches a
+/// thin beam to the first surface, with a small dot at the hit point. The local
+/// player always sees their own laser; remote lasers only when the weapon's
+/// `visible_to_others` is set.
+pub fn update_laser_sights(
+    players: Query<
+        (Entity, &Transform, &PlayerYaw, &PlayerPitch, &PlayerEquipped, Has<Controlled>, Has<crate::protocol::PlayerDead>),
+        With<PlayerId>,
+    >,
+    mut parts: Query<(Entity, &LaserSightPart, &mut Transform, &mut Visibility), Without<PlayerId>>,
+    stance_query: Query<&crate::protocol::PlayerStance>,
+    lean_config: Res<crate::player::LeanConfig>,
+    equippable_query: Query<&Equippable>,
+    spatial_query: SpatialQuery,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // owner -> (beam start, beam end, laser spec)
+    let mut lasers: Vec<(Entity, Vec3, Vec3, crate::weapons::LaserSightSpec)> = Vec::new();
+
+    for (owner, transform, yaw, pitch, equipped, is_controlled, is_dead) in players.iter() {
+        if is_dead { continue; }
+        let Some(ref name) = equipped.0 else { continue; };
+        let Some(laser) = crate::weapons::weapon_spec(name).and_then(|w| w.laser_sight) else { continue; };
+        if !is_controlled && !laser.visible_to_others { continue; }
+
+        let muzzle_local = equippable_query
+            .iter()
+            .find(|e| e.name == *name)
+            .and_then(|e| e.muzzle_offset)
+            .map(Vec3::from_array)
+            .unwrap_or(Vec3::new(0.2, -0.1, -0.9));
+
+        let eye_pos = transform.translation
+            + stance_query
+                .get(owner)
+                .map(|s| s.eye_offset(yaw.0, &lean_config))
+                .unwrap_or(Vec3::Y * crate::player::EYE_HEIGHT);
+        let look = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0);
+        let aim = Dir3::new(look * Vec3::NEG_Z).unwrap_or(Dir3::NEG_Z);
+        let start = crate::player::safe_muzzle_position(
+            transform.translation,
+            eye_pos + look * muzzle_local,
+            aim,
+            crate::player::CAPSULE_RADIUS,
+            crate::player::CAPSULE_HEIGHT * 0.5,
+        );
+
+        let filter = SpatialQueryFilter::from_excluded_entities([owner]);
+        let dist = spatial_query
+            .cast_ray(start, aim, laser.range, true, &filter)
+            .map(|hit| hit.distance)
+            .unwrap_or(laser.range);
+
+        lasers.push((owner, start, start + aim * dist, laser));
+    }
+
+    // Update or despawn existing parts (also when the owner switched to a
+    // weapon with a different laser, so they respawn with its color)
+    let mut drawn: Vec<Entity> = Vec::new();
+    for (entity, part, mut transform, mut visibility) in parts.iter_mut() {
+        let Some((_, start, end, _)) = lasers.iter().find(|(o, .., spec)| *o == part.owner && *spec == part.spec) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        *transform = laser_part_transform(*start, *end, part.dot);
+        visibility.set_if_neq(laser_part_visibility(*start, *end, part.dot));
+        drawn.push(part.owner);
+    }
+
+    // Spawn parts for lasers that just appeared
+    for (owner, start, end, laser) in lasers {
+        if drawn.contains(&owner) { continue; }
+        let [r, g, b] = laser.color;
+        let material = materials.add(StandardMaterial {
+            base_color: Color::linear_rgb(r, g, b),
+            emissive: bevy::color::LinearRgba::new(r, g, b, 1.0),
+            unlit: true,
+            ..default()
+        });
+        commands.spawn((
+            Mesh3d(meshes.add(Cylinder::new(0.004, 1.0))),
+            MeshMaterial3d(material.clone()),
+            laser_part_transform(start, end, false),
+            laser_part_visibility(start, end, false),
+            LaserSightPart { owner, dot: false, spec: laser },
+        ));
+        commands.spawn((
+            Mesh3d(meshes.add(Sphere::new(0.025))),
+            MeshMaterial3d(material),
+            laser_part_transform(start, end, true),
+            LaserSightPart { owner, dot: true, spec: laser },
+        ));
+    }
+}
+
+/// The beam is hidden when it has no length; the dot always shows.
+fn laser_part_visibility(start: Vec3, end: Vec3, dot: bool) -> Visibility {
+    if dot || start.distance(end) >= LASER_MIN_LENGTH {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
+
+/// Transform for a laser beam (unit cylinder stretched from `start` to `end`)
+/// or for the dot at `end`. A beam with no length has no direction, so it
+/// keeps the identity rotation (and is hidden by the caller).
+fn laser_part_transform(start: Vec3, end: Vec3, dot: bool) -> Transform {
+    if dot {
+        return Transform::from_translation(end);
+    }
+    let diff = end - start;
+    let length = diff.length();
+    let rotation = if length < LASER_MIN_LENGTH {
+        Quat::IDENTITY
+    } else {
+        Quat::from_rotation_arc(Vec3::Y, diff / length)
+    };
+    Transform::from_translation(start + diff * 0.5)
+        .with_rotation(rotation)
+        .with_scale(Vec3::new(1.0, length.max(0.001), 1.0))
+}
+
 // ========================================
 // Jab (melee) system
 // ========================================
 
 const JAB_DAMAGE: i32 = 15;
 const JAB_RANGE: f32 = 2.5;
+/// Radius of the swept sphere, so a jab doesn't need pixel-perfect aim.
+const JAB_RADIUS: f32 = 0.3;
 const JAB_COOLDOWN: f32 = 0.4;
 const JAB_DURATION: f32 = 0.3;
 
@@ -152,11 +610,16 @@ pub struct JabAnimation {
 }
 
 /// Shared FixedUpdate system: jab melee attack — short range punch, server applies damage.
-/// Queries each player's ActionState and fires on `just_pressed(Jab)`. Leafwing's
+/// This is the quick-melee: it never touches `Equipped`, so the held weapon stays
+/// in hand. Queries each player's ActionState and fires on `just_pressed(Jab)`. Leafwing's
 /// ActionState is restored cleanly during rollback, so this is safe to replay.
 pub fn shared_jab_system(
-    player_query: Query<(Entity, &ActionState<PlayerActions>, &Position, &PlayerYaw, &PlayerPitch, &PlayerId, Has<Predicted>, Has<Interpolated>)>,
+    player_query: Query<(Entity, &ActionState<PlayerActions>, &Position, &PlayerYaw, &PlayerPitch, &PlayerId, Has<Predicted>, Has<Interpolated>), Without<crate::protocol::Downed>>,
     mut health_query: Query<(Entity, &mut PlayerHealth, &Position, Option<&mut crate::protocol::LastDamagedBy>)>,
+    stance_query: Query<&crate::protocol::PlayerStance>,
+    lean_config: Res<crate::player::LeanConfig>,
+    hardcore_query: Query<(), With<crate::protocol::Hardcore>>,
+    headshot_only_query: Query<(), With<crate::protocol::HeadshotOnly>>,
     spatial_query: SpatialQuery,
     mut commands: Commands,
     mut last_jab: Local<f32>,
@@ -172,7 +635,11 @@ pub fn shared_jab_system(
         }
         *last_jab = current;
 
-        let eye_pos = player_pos.0 + Vec3::Y * 0.8;
+        let eye_pos = player_pos.0
+            + stance_query
+                .get(shooter)
+                .map(|s| s.eye_offset(yaw.0, &lean_config))
+                .unwrap_or(Vec3::Y * crate::player::EYE_HEIGHT);
         let ray_dir = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0) * Vec3::NEG_Z;
         let filter = SpatialQueryFilter::from_excluded_entities([shooter]);
 
@@ -181,21 +648,27 @@ pub fn shared_jab_system(
             eye_pos, ray_dir, is_predicted
         );
 
-        if let Some(hit) = spatial_query.cast_ray(
+        let config = ShapeCastConfig::from_max_distance(JAB_RANGE);
+        if let Some(hit) = spatial_query.cast_shape(
+            &Collider::sphere(JAB_RADIUS),
             eye_pos,
+            Quat::IDENTITY,
             Dir3::new(ray_dir).unwrap_or(Dir3::NEG_Z),
-            JAB_RANGE,
-            true,
+            &config,
             &filter,
         ) {
             info!("[JAB] Hit entity {:?} at distance {:.1}", hit.entity, hit.distance);
-            if !is_predicted {
+            if !is_predicted && !headshot_only_query.is_empty() {
+                info!("[JAB] Ignored — headshot-only training");
+            } else if !is_predicted {
                 if let Ok((_entity, mut health, _pos, last_damaged)) = health_query.get_mut(hit.entity) {
-                    health.0 -= JAB_DAMAGE;
+                    // Hardcore: any hit kills
+                    let damage = if hardcore_query.is_empty() { JAB_DAMAGE } else { health.0.max(1) };
+                    health.0 -= damage;
                     if let Some(mut last) = last_damaged {
                         last.0 = attacker_id.0;
                     }
-                    info!("[JAB] {} damage applied, health now: {}", JAB_DAMAGE, health.0);
+                    info!("[JAB] {} damage applied, health now: {}", damage, health.0);
                 } else {
                     info!("[JAB] Hit entity {:?} but it has no PlayerHealth", hit.entity);
                 }
@@ -213,12 +686,51 @@ pub fn shared_jab_system(
 #[derive(Event)]
 pub struct JabFired;
 
+/// Server-only event: a player finished mining the ore vein at `at`. The
+/// server credits their `Resources` and/or drops an ore chunk there,
+/// depending on `Ruleset::ore_drop`.
+#[derive(Event)]
+pub struct OreMined {
+    pub miner: Entity,
+    pub at: Vec3,
+}
+
+/// Name of the loose ore Equippable. Ore chunks stack: picking one up adds
+/// to the player's `Resources` instead of taking a hand or inventory slot.
+pub const ORE_CHUNK: &str = "Ore Chunk";
+
+/// How much ore a loose chunk is worth when picked up. Server-only: dropping
+/// a pile of ore spawns one chunk carrying the whole stack.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct OreStack(pub u32);
+
+/// A loose chunk of `count` ore at `at` that falls and can be picked up.
+pub fn ore_chunk_bundle(at: Vec3, count: u32) -> impl Bundle {
+    (
+        Position(at),
+        OreStack(count),
+        Rotation::default(),
+        RigidBody::Dynamic,
+        Collider::cuboid(0.2, 0.2, 0.2),
+        Equippable {
+            name: ORE_CHUNK.to_string(),
+            model_path: "ore_chunk.glb".to_string(),
+            interaction_distance: 2.0,
+            scale: 0.5,
+            model_rotation: [0.0, 0.0, 0.0],
+            muzzle_offset: None,
+        },
+        Name::new(ORE_CHUNK),
+        Replicate::to_clients(NetworkTarget::All),
+    )
+}
+
 /// Client-only observer: starts the jab animation on the left hand.
 pub fn start_jab_animation(
     _trigger: On<JabFired>,
     hand_query: Query<Entity, With<LeftHand>>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<Time<crate::player::Presentation>>,
 ) {
     let Ok(hand) = hand_query.single() else { return; };
     // Insert/overwrite animation component to restart
@@ -231,7 +743,7 @@ pub fn start_jab_animation(
 /// Slides in from off-screen left, punches forward, retracts.
 pub fn animate_jab(
     mut hand_query: Query<(&mut Transform, &JabAnimation, Entity), With<LeftHand>>,
-    time: Res<Time>,
+    time: Res<Time<crate::player::Presentation>>,
     mut commands: Commands,
 ) {
     let Ok((mut transform, anim, entity)) = hand_query.single_mut() else { return; };
@@ -288,7 +800,7 @@ fn lerp3(a: (f32, f32, f32), b: (f32, f32, f32), t: f32) -> (f32, f32, f32) {
 /// and rollback-safe — replaying the same ticks gives the same result.
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Interactable {
-    pub required_tool: Option<String>,
+    pub required_tool: Option<ToolType>,
     pub interaction_distance: f32,
     pub interaction_time: f32,
     pub model_path: String,
@@ -323,6 +835,95 @@ impl Interactable {
     }
 }
 
+/// Environmental hazard: players whose capsule overlaps this axis-aligned box
+/// (centered on the entity's Position) lose `dps` health per second.
+/// Server-authoritative, replicated so clients know where hazards are.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DamageVolume {
+    pub name: String,
+    pub dps: f32,
+    pub half_extents: Vec3,
+}
+
+impl DamageVolume {
+    /// Whether a player capsule centered at `player_pos` overlaps the volume at `center`.
+    pub fn contains(&self, center: Vec3, player_pos: Vec3) -> bool {
+        let reach = self.half_extents
+            + Vec3::new(
+                crate::player::CAPSULE_RADIUS,
+                crate::player::CAPSULE_HEIGHT * 0.5 + crate::player::CAPSULE_RADIUS,
+                crate::player::CAPSULE_RADIUS,
+            );
+        let d = (player_pos - center).abs();
+        d.x <= reach.x && d.y <= reach.y && d.z <= reach.z
+    }
+}
+
+/// Seconds after a map weapon is taken before it can return to its spawn.
+pub const WEAPON_RESPAWN_SECS: f32 = 30.0;
+
+/// A weapon placed on the map. Server-tracked, replicated so clients can show
+/// the pickup glow while the weapon waits at its spawn.
+///
+/// Taking the weapon ends `available`; once `respawn_secs` have passed and
+/// nobody holds it, the server moves it back to `home` from wherever it lies.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WeaponSpawn {
+    pub home: Vec3,
+    pub respawn_secs: f32,
+    /// True while the weapon sits untouched at its spawn.
+    pub available: bool,
+    /// Server time the weapon was taken. None while available.
+    pub taken_at: Option<f32>,
+}
+
+impl WeaponSpawn {
+    pub fn new(home: Vec3, respawn_secs: f32) -> Self {
+        Self { home, respawn_secs, available: true, taken_at: None }
+    }
+}
+
+/// A capture-the-flag flag. Server-authoritative, replicated.
+/// While carried, the server keeps `Position` on the carrier; clients draw
+/// the flag on the carrier's back.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Flag {
+    pub team: crate::protocol::Team,
+    /// Base position — the flag starts and returns here.
+    pub home: Vec3,
+    /// PlayerId of the enemy player carrying it.
+    pub carrier: Option<u64>,
+    /// Server time it was dropped away from base. None at base or while carried.
+    pub dropped_at: Option<f32>,
+}
+
+impl Flag {
+    /// Flag base for each team: the equipment shed and the mine entrance.
+    pub fn base(team: crate::protocol::Team) -> Vec3 {
+        match team {
+            crate::protocol::Team::Red => Vec3::new(-14.0, 0.2, 2.0),
+            crate::protocol::Team::Blue => Vec3::new(19.0, 0.5, -2.0),
+        }
+    }
+}
+
+/// Hazard volumes in the level: (name, center, half extents, damage per second).
+pub const HAZARD_VOLUMES: &[(&str, Vec3, Vec3, f32)] = &[
+    // Campfire embers inside the stone ring south of the cabin
+    ("Campfire", Vec3::new(3.0, 0.2, 10.0), Vec3::new(0.6, 0.2, 0.6), 15.0),
+];
+
+/// Trading station: a player within `interaction_distance` can spend their
+/// `Resources` on `trade::TRADE_OFFERS`. Server-authoritative, replicated.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TradeStation {
+    pub interaction_distance: f32,
+}
+
+/// Trading station outside the cabin, and its counter size.
+const TRADE_STATION_POS: Vec3 = Vec3::new(-6.0, 0.6, 6.0);
+const TRADE_STATION_SIZE: Vec3 = Vec3::new(1.2, 1.0, 0.6);
+
 /// Networked door state — replicated from server to all clients.
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct DoorState {
@@ -1529,6 +2130,7 @@ pub fn spawn_server_interactive_objects(mut commands: Commands) {
             model_rotation: [std::f32::consts::FRAC_PI_2, std::f32::consts::FRAC_PI_2, 0.0],
             muzzle_offset: Some([0.2, -0.1, -0.9]),
         },
+        WeaponSpawn::new(Vec3::new(0.0, 0.9, -1.0), WEAPON_RESPAWN_SECS),
         Name::new("AK47"),
         Replicate::to_clients(NetworkTarget::All),
     ));
@@ -1540,7 +2142,7 @@ pub fn spawn_server_interactive_objects(mut commands: Commands) {
         RigidBody::Static,
         Collider::cuboid(0.5, 0.5, 0.5),
         Interactable {
-            required_tool: Some("Pickaxe".to_string()),
+            required_tool: Some(ToolType::Pickaxe),
             interaction_distance: 2.0,
             interaction_time: 3.0,
             model_path: "ore_chunk.glb".to_string(),
@@ -1552,7 +2154,32 @@ pub fn spawn_server_interactive_objects(mut commands: Commands) {
         Replicate::to_clients(NetworkTarget::All),
     ));
 
-    info!("Server spawned interactive objects (cabin door, pickaxe in shed, AK47 on table, ore in mine)");
+    // Trading station by the cabin — spend mined ore on items
+    commands.spawn((
+        Position(TRADE_STATION_POS),
+        Rotation::default(),
+        RigidBody::Static,
+        Collider::cuboid(TRADE_STATION_SIZE.x, TRADE_STATION_SIZE.y, TRADE_STATION_SIZE.z),
+        TradeStation { interaction_distance: 2.5 },
+        Name::new("Trading Station"),
+        Replicate::to_clients(NetworkTarget::All),
+    ));
+
+    // Hazard volumes (campfire, ...) — damage is applied by the server each tick
+    for (name, center, half_extents, dps) in HAZARD_VOLUMES {
+        commands.spawn((
+            Position(*center),
+            DamageVolume {
+                name: name.to_string(),
+                dps: *dps,
+                half_extents: *half_extents,
+            },
+            Name::new(*name),
+            Replicate::to_clients(NetworkTarget::All),
+        ));
+    }
+
+    info!("Server spawned interactive objects (cabin door, pickaxe in shed, AK47 on table, ore in mine, trading station, hazards)");
 }
 
 /// Lighting for the Colorado wilderness — late afternoon golden hour,
@@ -1628,6 +2255,31 @@ pub fn init_replicated_doors(
     }
 }
 
+/// Client-only system: adds rendering (and the counter's collider) to
+/// replicated trading stations.
+pub fn init_replicated_trade_stations(
+    query: Query<(Entity, &Position), Added<TradeStation>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, pos) in query.iter() {
+        info!("init_replicated_trade_stations: {:?} at {:?}", entity, pos.0);
+        commands.entity(entity).insert((
+            Mesh3d(meshes.add(Cuboid::from_size(TRADE_STATION_SIZE))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgb(0.45, 0.3, 0.15),
+                emissive: LinearRgba::rgb(0.3, 0.2, 0.05),
+                ..default()
+            })),
+            Transform::from_translation(pos.0),
+            Visibility::default(),
+            Collider::cuboid(TRADE_STATION_SIZE.x, TRADE_STATION_SIZE.y, TRADE_STATION_SIZE.z),
+            RenderLayers::from_layers(&[DEFAULT_RENDER_LAYER]),
+        ));
+    }
+}
+
 /// Client-only system: adds rendering to replicated equippable entities.
 pub fn init_replicated_equippables(
     query: Query<(Entity, &Equippable, &Position, &Rotation), Added<Equippable>>,
@@ -1651,6 +2303,41 @@ pub fn init_replicated_equippables(
     }
 }
 
+/// Server-only: map weapon respawn. Marks a spawn unavailable when its weapon
+/// is picked up; after the respawn time, returns the weapon home as soon as
+/// nobody is holding or carrying it. Issued copies (LoadoutItems) of the same
+/// name don't count, as in shared_equip_interact_system.
+pub fn respawn_map_weapons(
+    mut weapons: Query<(&Equippable, &mut Position, &mut WeaponSpawn)>,
+    holders: Query<(&PlayerEquipped, &PlayerInventory, Option<&crate::protocol::LoadoutItems>)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (equippable, mut pos, mut spawn) in weapons.iter_mut() {
+        let held = holders.iter().any(|(pe, inv, loadout)| {
+            let carries = pe.0.as_deref() == Some(equippable.name.as_str()) || inv.items.contains(&equippable.name);
+            carries && loadout.is_none_or(|l| !l.contains(&equippable.name))
+        });
+
+        if spawn.available {
+            if held {
+                info!("[WEAPON SPAWN] {} taken — back in {}s", equippable.name, spawn.respawn_secs);
+                spawn.available = false;
+                spawn.taken_at = Some(now);
+            }
+            continue;
+        }
+
+        let ready = spawn.taken_at.is_none_or(|taken| now - taken >= spawn.respawn_secs);
+        if ready && !held {
+            info!("[WEAPON SPAWN] {} respawned at {:?}", equippable.name, spawn.home);
+            pos.0 = spawn.home;
+            spawn.available = true;
+            spawn.taken_at = None;
+        }
+    }
+}
+
 /// Client-only system: adds rendering to replicated interactable entities.
 pub fn init_replicated_interactables(
     query: Query<(Entity, &Interactable, &Position, &Rotation), Added<Interactable>>,
@@ -1679,21 +2366,24 @@ pub fn init_replicated_interactables(
 // ========================================
 
 /// Client-only: syncs equippable visibility when any player's equipped state changes.
-/// Hides world entities for items that any player is currently holding.
+/// Hides world entities for items that any player is currently holding or carrying.
 /// Gated by `not(is_in_rollback)` in client.rs to avoid flicker during prediction rollback.
 pub fn sync_equippable_visibility(
-    equipped_query: Query<&PlayerEquipped, Changed<PlayerEquipped>>,
-    all_equipped: Query<&PlayerEquipped>,
+    equipped_query: Query<(), Or<(Changed<PlayerEquipped>, Changed<PlayerInventory>)>>,
+    all_equipped: Query<(&PlayerEquipped, &PlayerInventory)>,
     mut equippable_query: Query<(&Equippable, &mut Visibility)>,
+    mut departed: RemovedComponents<PlayerEquipped>,
 ) {
-    // Only recalculate when someone's equipped state actually changed
-    if equipped_query.is_empty() {
+    // Only recalculate when someone's equipped/inventory state actually changed,
+    // or a player left (what they carried may have been dropped)
+    let someone_left = departed.read().count() > 0;
+    if equipped_query.is_empty() && !someone_left {
         return;
     }
     for (equippable, mut visibility) in equippable_query.iter_mut() {
-        let held = all_equipped
-            .iter()
-            .any(|pe| pe.0.as_deref() == Some(equippable.name.as_str()));
+        let held = all_equipped.iter().any(|(pe, inv)| {
+            pe.0.as_deref() == Some(equippable.name.as_str()) || inv.items.contains(&equippable.name)
+        });
         *visibility = if held {
             Visibility::Hidden
         } else {
@@ -1817,16 +2507,39 @@ pub fn shared_door_interact_system(
 }
 
 /// Shared FixedUpdate system: equip items when player presses E within range.
+///
+/// Clients only send input; the server runs this with its own positions, so
+/// the `interaction_distance` check is authoritative. Items already carried
+/// by someone are skipped — a held item's entity stays where it was picked
+/// up, so without this anyone standing there could take it from its holder.
 pub fn shared_equip_interact_system(
-    mut player_query: Query<(&ActionState<PlayerActions>, &Position, &mut PlayerEquipped, Has<Interpolated>), With<PlayerId>>,
+    mut player_query: Query<
+        (&ActionState<PlayerActions>, &Position, &mut PlayerEquipped, &mut PlayerInventory, Option<&crate::protocol::LoadoutItems>, Has<crate::protocol::AutoEquip>, Has<Interpolated>),
+        With<PlayerId>,
+    >,
     equippable_query: Query<(Entity, &Position, &Equippable), Without<PlayerEquipped>>,
 ) {
-    for (action, player_pos, mut equipped, is_interpolated) in player_query.iter_mut() {
+    // Loadout items are issued copies; they don't claim the map entity
+    let mut carried: Vec<String> = player_query
+        .iter()
+        .flat_map(|(_, _, equipped, inventory, loadout, ..)| {
+            equipped.0.iter()
+                .chain(inventory.items.iter())
+                .filter(move |item| loadout.is_none_or(|l| !l.contains(item)))
+                .cloned()
+        })
+        .collect();
+
+    for (action, player_pos, mut equipped, mut inventory, loadout, auto_equip, is_interpolated) in player_query.iter_mut() {
         if is_interpolated { continue; }
         if !action.just_pressed(&PlayerActions::Interact) { continue; }
 
         let mut closest: Option<(Entity, f32, String)> = None;
         for (entity, eq_pos, equippable) in equippable_query.iter() {
+            // Ore chunks are stacked into Resources by the server instead
+            if equippable.name == ORE_CHUNK { continue; }
+            if carried.contains(&equippable.name) { continue; }
+            if equipped.0.as_ref() == Some(&equippable.name) || inventory.items.contains(&equippable.name) { continue; }
             let dist = player_pos.0.distance(eq_pos.0);
             if dist <= equippable.interaction_distance {
                 if closest.as_ref().is_none_or(|(_, d, _)| dist < *d) {
@@ -1835,24 +2548,53 @@ pub fn shared_equip_interact_system(
             }
         }
 
-        if let Some((_, _, name)) = closest {
-            if equipped.0.as_ref() == Some(&name) {
-                continue;
-            }
+        let Some((_, _, name)) = closest else { continue; };
+        carried.push(name.clone());
+
+        if !auto_equip {
             info!("Equipped {}", name);
-            equipped.0 = Some(name);
+            // A loadout item in hand goes to the inventory rather than away
+            if let Some(current) = equipped.0.replace(name) {
+                if loadout.is_some_and(|l| l.contains(&current)) {
+                    inventory.items.push(current);
+                }
+            }
+            continue;
+        }
+
+        // Auto-equip: switch only to something better than what's in hand,
+        // otherwise stash the pickup in inventory.
+        match equipped.0.take() {
+            Some(current) if !crate::weapons::outranks(&name, &current) => {
+                info!("Picked up {} (kept {})", name, current);
+                equipped.0 = Some(current);
+                inventory.items.push(name);
+            }
+            Some(current) => {
+                info!("Auto-equipped {} (stashed {})", name, current);
+                inventory.items.push(current);
+                equipped.0 = Some(name);
+            }
+            None => {
+                info!("Equipped {}", name);
+                equipped.0 = Some(name);
+            }
         }
     }
 }
 
 /// Shared FixedUpdate system: drop equipped item when player presses G.
+/// Loadout items can't be dropped — they aren't map items.
 pub fn shared_drop_system(
-    mut player_query: Query<(&ActionState<PlayerActions>, &Position, &mut PlayerEquipped, Has<Interpolated>), With<PlayerId>>,
+    mut player_query: Query<(&ActionState<PlayerActions>, &Position, &mut PlayerEquipped, Option<&crate::protocol::LoadoutItems>, Has<Interpolated>), With<PlayerId>>,
     mut equippable_query: Query<(Entity, &mut Position, &Equippable), Without<PlayerEquipped>>,
 ) {
-    for (action, player_pos, mut equipped, is_interpolated) in player_query.iter_mut() {
+    for (action, player_pos, mut equipped, loadout, is_interpolated) in player_query.iter_mut() {
         if is_interpolated { continue; }
         if !action.just_pressed(&PlayerActions::Drop) { continue; }
+        if let (Some(name), Some(loadout)) = (equipped.0.as_ref(), loadout) {
+            if loadout.contains(name) { continue; }
+        }
 
         let Some(dropped_name) = equipped.0.take() else {
             continue;
@@ -1878,24 +2620,30 @@ pub fn shared_drop_system(
 /// For guns we fire on `just_pressed` so a single click fires once per press.
 /// For mining we check `pressed` so the tool works as long as the button is held.
 pub fn shared_primary_action_system(
-    player_query: Query<(Entity, &ActionState<PlayerActions>, &Position, &PlayerYaw, &PlayerPitch, &PlayerEquipped, &PlayerId, Has<Predicted>, Has<Interpolated>)>,
+    mut player_query: Query<(Entity, &ActionState<PlayerActions>, &Position, &PlayerYaw, &PlayerPitch, &PlayerEquipped, &mut crate::protocol::RoundsFired, Has<Predicted>, Has<Interpolated>), Without<crate::protocol::Downed>>,
     mut interactables_query: Query<(Entity, &Position, &mut Interactable)>,
     health_query: Query<(Entity, &PlayerHealth, &Position)>,
+    stance_query: Query<&crate::protocol::PlayerStance>,
+    lean_config: Res<crate::player::LeanConfig>,
     equippable_query: Query<&Equippable>,
+    ricochets_query: Query<(), With<crate::protocol::Ricochets>>,
     spatial_query: SpatialQuery,
     mut commands: Commands,
     mut last_shot: Local<f32>,
     mut shot_counter: Local<u32>,
+    mut bursts: Local<HashMap<Entity, (f32, u32)>>,
     time: Res<Time>,
 ) {
-    for (shooter, action, player_pos, yaw, pitch, equipped, _attacker_id, is_predicted, is_interpolated) in player_query.iter() {
+    bursts.retain(|shooter, _| player_query.contains(*shooter));
+    for (shooter, action, player_pos, yaw, pitch, equipped, mut rounds_fired, is_predicted, is_interpolated) in player_query.iter_mut() {
         if is_interpolated { continue; }
 
         let tool_name = equipped.0.as_deref();
 
         // Gate the rest of the handler on whether Primary is active this tick.
         // Guns use just_pressed (one shot per click); mining uses pressed (held).
-        let is_gun = matches!(tool_name, Some(n) if n.contains("AK") || n.contains("ak") || n.contains("gun"));
+        let kind = tool_name.map(ItemKind::of);
+        let is_gun = kind.is_some_and(ItemKind::is_weapon);
         let fire = if is_gun {
             action.just_pressed(&PlayerActions::Primary)
         } else {
@@ -1905,16 +2653,24 @@ pub fn shared_primary_action_system(
 
     match tool_name {
         // Gun equipped → hitscan shoot
-        Some(name) if name.contains("AK") || name.contains("ak") || name.contains("gun") => {
+        Some(name) if is_gun => {
             let current = time.elapsed_secs();
             if current - *last_shot < SHOOT_COOLDOWN {
                 continue;
             }
             *last_shot = current;
-
-            let eye_pos = player_pos.0 + Vec3::Y * 0.8;
-            let ray_dir = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0) * Vec3::NEG_Z;
-            let filter = SpatialQueryFilter::from_excluded_entities([shooter]);
+            // Same spread pattern step as the server, so the tracer matches the hit
+            let burst = crate::weapons::burst_index(name, bursts.get(&shooter).copied(), current);
+            bursts.insert(shooter, (current, burst));
+            let [spread_yaw, spread_pitch] = crate::weapons::spread_offset(name, burst);
+
+            let eye_pos = player_pos.0
+                + stance_query
+                    .get(shooter)
+                    .map(|s| s.eye_offset(yaw.0, &lean_config))
+                    .unwrap_or(Vec3::Y * crate::player::EYE_HEIGHT);
+            let ray_dir = Quat::from_euler(EulerRot::YXZ, yaw.0 + spread_yaw, pitch.0 + spread_pitch, 0.0) * Vec3::NEG_Z;
+            let mut filter = SpatialQueryFilter::from_excluded_entities([shooter]);
 
             info!(
                 "[SHOOT] Fire! pos={:?} yaw={:.2} pitch={:.2} dir={:?} predicted={}",
@@ -1931,25 +2687,25 @@ pub fn shared_primary_action_system(
                 }
             }
 
-            let tracer_dist;
-
-            // Client-side prediction: ray-cast against local view for tracer visuals.
-            // Server damage is handled by server_compute_shot_hit with lag compensation.
-            if let Some(hit) = spatial_query.cast_ray(
+            // Client-side prediction: ray-cast against local view for tracer visuals
+            // (through thin walls, per the weapon's penetration). Server damage is
+            // handled by server_shoot_with_lag_comp with lag compensation.
+            let trace = trace_shot(
                 eye_pos,
                 Dir3::new(ray_dir).unwrap_or(Dir3::NEG_Z),
-                SHOOT_RANGE,
-                true,
-                &filter,
-            ) {
-                tracer_dist = hit.distance;
-                info!(
-                    "[SHOOT] Ray hit entity {:?} at distance {:.1}",
-                    hit.entity, hit.distance
-                );
-            } else {
-                tracer_dist = SHOOT_RANGE;
-                info!("[SHOOT] Miss — no ray hit within {} range", SHOOT_RANGE);
+                crate::weapons::penetration(name),
+                if ricochets_query.is_empty() { 0 } else { crate::weapons::ricochets(name) },
+                &mut filter,
+                &spatial_query,
+                |e| health_query.contains(e),
+                |origin, dir, max, filter| spatial_query.cast_ray(origin, dir, max, true, filter),
+            );
+            match trace.hit {
+                Some(entity) => info!(
+                    "[SHOOT] Ray hit entity {:?} at distance {:.1} ({} surface(s) penetrated, {} ricochet(s))",
+                    entity, trace.distance, trace.penetrated, trace.ricochets.len()
+                ),
+                None => info!("[SHOOT] Miss — no ray hit within {} range", SHOOT_RANGE),
             }
 
             // Look up muzzle offset from the Equippable component
@@ -1961,19 +2717,37 @@ pub fn shared_primary_action_system(
                 .unwrap_or(Vec3::new(0.2, -0.1, -0.9));
 
             let cam_rot = Quat::from_euler(EulerRot::YXZ, yaw.0, pitch.0, 0.0);
-            let muzzle_world = eye_pos + cam_rot * muzzle_local;
-            let hit_point = eye_pos + ray_dir * tracer_dist;
+            // Keep the tracer origin outside the shooter's own capsule (e.g. aiming
+            // straight down would otherwise put the muzzle inside the body).
+            let muzzle_world = crate::player::safe_muzzle_position(
+                player_pos.0,
+                eye_pos + cam_rot * muzzle_local,
+                Dir3::new(ray_dir).unwrap_or(Dir3::NEG_Z),
+                crate::player::CAPSULE_RADIUS,
+                crate::player::CAPSULE_HEIGHT * 0.5,
+            );
+            let hit_point = trace.end;
 
+            rounds_fired.0 += 1;
+            let tracer = crate::weapons::shows_tracer(name, rounds_fired.0);
             commands.trigger(ShotFired {
                 muzzle: muzzle_world,
                 hit_point,
+                ricochets: trace.ricochets.clone(),
+                tracer,
             });
 
             // Set LastShot on the player entity so remote clients can see the tracer
+            // (ricochet legs included — the server's trace is the one that replicates).
+            // Rounds without a tracer aren't replicated at all.
+            if !tracer {
+                continue;
+            }
             *shot_counter += 1;
             commands.entity(shooter).insert(crate::protocol::LastShot {
                 muzzle: muzzle_world,
                 hit_point,
+                ricochets: trace.ricochets,
                 tick: *shot_counter,
             });
         }
@@ -1989,7 +2763,7 @@ pub fn shared_primary_action_system(
                 let dist = player_pos.0.distance(pos.0);
                 if dist <= interactable.interaction_distance && dist < closest_dist {
                     let tool_matches = interactable.required_tool.is_none()
-                        || interactable.required_tool.as_deref() == tool_name;
+                        || interactable.required_tool == kind.and_then(ItemKind::tool_type);
                     if tool_matches {
                         closest_dist = dist;
                         closest = Some(entity);
@@ -2016,24 +2790,8 @@ pub fn shared_primary_action_system(
             if progress >= interactable.interaction_time {
                 info!("Mining complete!");
                 if !is_predicted {
-                    let spawn_pos = pos.0;
                     commands.entity(target).despawn();
-                    commands.spawn((
-                        Position(spawn_pos + Vec3::new(0.0, 0.3, 0.0)),
-                        Rotation::default(),
-                        RigidBody::Dynamic,
-                        Collider::cuboid(0.2, 0.2, 0.2),
-                        Equippable {
-                            name: "Ore Chunk".to_string(),
-                            model_path: "ore_chunk.glb".to_string(),
-                            interaction_distance: 2.0,
-                            scale: 0.5,
-                            model_rotation: [0.0, 0.0, 0.0],
-                            muzzle_offset: None,
-                        },
-                        Name::new("Ore Chunk"),
-                        Replicate::to_clients(NetworkTarget::All),
-                    ));
+                    commands.trigger(OreMined { miner: shooter, at: pos.0 });
                 }
             }
         }
@@ -2048,6 +2806,149 @@ pub const SHOOT_DAMAGE: i32 = 25;
 pub const SHOOT_RANGE: f32 = 500.0;
 pub const SHOOT_COOLDOWN: f32 = 0.15;
 
+/// Most surfaces a single round can pass through, whatever its budget.
+const MAX_PENETRATIONS: u32 = 4;
+/// Step past a surface so the next cast doesn't re-hit it at distance 0.
+const PENETRATION_EPSILON: f32 = 0.01;
+
+/// Where a hitscan round ended up after penetrating thin geometry and
+/// ricocheting off walls.
+pub struct ShotTrace {
+    /// Entity that stopped the round (player or too-thick surface), if any.
+    pub hit: Option<Entity>,
+    /// Path length from the origin to where the round stopped (SHOOT_RANGE on a miss).
+    pub distance: f32,
+    /// Where the round stopped (end of its range on a miss).
+    pub end: Vec3,
+    /// Surfaces passed through before `hit`.
+    pub penetrated: u32,
+    /// Points where the round ricocheted, in order.
+    pub ricochets: Vec<Vec3>,
+}
+
+/// How far below eye level the head hit zone reaches (meters).
+pub const HEAD_ZONE_BELOW_EYE: f32 = 0.2;
+
+/// Whether a round hitting at `hit_y` strikes the head of a player whose
+/// capsule center is at `victim_y` with the given eye height.
+pub fn is_headshot(hit_y: f32, victim_y: f32, eye_height: f32) -> bool {
+    hit_y >= victim_y + eye_height - HEAD_ZONE_BELOW_EYE
+}
+
+/// Traces a hitscan round through thin non-player colliders.
+///
+/// `cast` performs the ray casts — plain spatial queries on the client,
+/// lag-compensated ones on the server. Surface thickness is measured by casting
+/// from just inside the entry point to the exit face of the same collider; the
+/// round passes through while the total thickness stays within `penetration`.
+/// A round stopped by a surface bounces off it (direction reflected about the
+/// surface normal) up to `ricochets` times, continuing with the range it has
+/// left. Players always stop the round.
+pub fn trace_shot(
+    origin: Vec3,
+    dir: Dir3,
+    penetration: f32,
+    ricochets: u32,
+    filter: &mut SpatialQueryFilter,
+    spatial_query: &SpatialQuery,
+    is_player: impl Fn(Entity) -> bool,
+    mut cast: impl FnMut(Vec3, Dir3, f32, &mut SpatialQueryFilter) -> Option<RayHitData>,
+) -> ShotTrace {
+    let mut origin = origin;
+    let mut dir = dir;
+    // Path length before the current leg, and distance along the current leg
+    let mut travelled = 0.0;
+    let mut start = 0.0;
+    let mut budget = penetration;
+    let mut penetrated = 0;
+    let mut bounces = Vec::new();
+    loop {
+        let range = SHOOT_RANGE - travelled;
+        let Some(hit) = cast(origin + *dir * start, dir, range - start, filter) else {
+            return ShotTrace {
+                hit: None,
+                distance: SHOOT_RANGE,
+                end: origin + *dir * range,
+                penetrated,
+                ricochets: bounces,
+            };
+        };
+        let entry = start + hit.distance;
+        let point = origin + *dir * entry;
+        if is_player(hit.entity) {
+            return ShotTrace {
+                hit: Some(hit.entity),
+                distance: travelled + entry,
+                end: point,
+                penetrated,
+                ricochets: bounces,
+            };
+        }
+
+        let surface = hit.entity;
+        let exit = if budget > 0.0 && penetrated < MAX_PENETRATIONS {
+            spatial_query.cast_ray_predicate(
+                point + *dir * PENETRATION_EPSILON,
+                dir,
+                budget,
+                false,
+                &SpatialQueryFilter::default(),
+                &|e| e == surface,
+            )
+        } else {
+            None
+        };
+
+        if let Some(exit) = exit {
+            let thickness = exit.distance + PENETRATION_EPSILON;
+            budget -= thickness;
+            penetrated += 1;
+            start = entry + thickness + PENETRATION_EPSILON;
+            if start >= range {
+                return ShotTrace {
+                    hit: None,
+                    distance: SHOOT_RANGE,
+                    end: origin + *dir * range,
+                    penetrated,
+                    ricochets: bounces,
+                };
+            }
+            continue;
+        }
+
+        // Too thick to pass through — bounce off it if the round has ricochets left
+        let normal = hit.normal.normalize_or_zero();
+        let reflected = Dir3::new(*dir - 2.0 * dir.dot(normal) * normal);
+        match reflected {
+            Ok(reflected) if (bounces.len() as u32) < ricochets && normal != Vec3::ZERO => {
+                bounces.push(point);
+                travelled += entry;
+                origin = point + normal * PENETRATION_EPSILON;
+                dir = reflected;
+                start = 0.0;
+                if travelled >= SHOOT_RANGE {
+                    return ShotTrace {
+                        hit: None,
+                        distance: SHOOT_RANGE,
+                        end: point,
+                        penetrated,
+                        ricochets: bounces,
+                    };
+                }
+            }
+            _ => {
+                return ShotTrace {
+                    hit: Some(surface),
+                    distance: travelled + entry,
+                    end: point,
+                    penetrated,
+                    ricochets: bounces,
+                };
+            }
+        }
+    }
+}
+
 /// Shared system: resets mining state on interactables that haven't been mined recently.
 /// Runs every FixedUpdate. If `last_mine_secs` is stale (>0.05s ago), clears mining state.
 pub fn reset_stale_mining(
@@ -2069,6 +2970,174 @@ pub fn reset_stale_mining(
 // Client-only systems
 // ========================================
 
+/// Rest position of the held item relative to the world camera.
+pub const VIEW_MODEL_OFFSET: Vec3 = Vec3::new(0.2, -0.15, -0.4);
+/// Idle breathing sway amplitude (meters) and rate (breaths per second).
+const BREATHING_AMPLITUDE: Vec2 = Vec2::new(0.003, 0.006);
+const BREATHING_RATE: f32 = 0.25;
+/// Fraction of the breathing sway left while aiming down sights.
+const BREATHING_ADS_FACTOR: f32 = 0.2;
+/// Horizontal speed below which the player counts as standing still (m/s).
+const BREATHING_IDLE_SPEED: f32 = 0.1;
+
+/// Movement sway: the held item trails the player's horizontal velocity by
+/// this much per m/s (before PlayerSettings::weapon_sway).
+const MOVE_SWAY_PER_SPEED: f32 = 0.0025;
+/// Look sway: the held item lags a turn by this much per rad/s of yaw/pitch.
+const LOOK_SWAY_PER_RATE: f32 = 0.004;
+/// Cap on the look sway offset, so flicks don't throw the item off screen.
+const LOOK_SWAY_MAX: f32 = 0.02;
+
+/// Client-only: idle breathing — a slow figure-eight sway of the held item
+/// while the player stands still. Fades in/out with movement and calms down
+/// to BREATHING_ADS_FACTOR while aiming.
+///
+/// On top of that the item sways with movement (trailing the direction we
+/// walk) and turning (lagging behind the view), scaled by
+/// PlayerSettings::weapon_sway — 0.0 holds it steady.
+pub fn animate_view_model_breathing(
+    player_query: Query<
+        (
+            &crate::protocol::CharacterVelocity,
+            &crate::protocol::PlayerStance,
+            &PlayerYaw,
+            &PlayerPitch,
+        ),
+        With<lightyear::prelude::Controlled>,
+    >,
+    mut view_model_query: Query<&mut Transform, With<EquippedItem>>,
+    settings: Res<crate::settings::PlayerSettings>,
+    time: Res<Time<crate::player::Presentation>>,
+    mut weight: Local<f32>,
+    mut motion_sway: Local<Vec3>,
+    mut last_look: Local<Option<(f32, f32)>>,
+) {
+    let Ok((velocity, stance, yaw, pitch)) = player_query.single() else { return; };
+    let dt = time.delta_secs();
+
+    let idle = Vec2::new(velocity.0.x, velocity.0.z).length() < BREATHING_IDLE_SPEED;
+    let mut target = if idle && settings.idle_breathing { 1.0 } else { 0.0 };
+    if stance.aiming {
+        target *= BREATHING_ADS_FACTOR;
+    }
+    // Ease the sway weight so starting/stopping/ADS never snaps the weapon
+    *weight += (target - *weight) * (1.0 - (-4.0 * dt).exp());
+
+    let phase = time.elapsed_secs() * BREATHING_RATE * std::f32::consts::TAU;
+    let sway = Vec3::new(
+        (phase * 0.5).sin() * BREATHING_AMPLITUDE.x,
+        phase.sin() * BREATHING_AMPLITUDE.y,
+        0.0,
+    ) * *weight;
+
+    // Velocity in view space (yaw 0 looks down -Z), trailed by the item
+    let (sin, cos) = yaw.0.sin_cos();
+    let right = velocity.0.x * cos - velocity.0.z * sin;
+    let forward = -velocity.0.x * sin - velocity.0.z * cos;
+    let move_offset = Vec3::new(-right, -velocity.0.y.clamp(-5.0, 5.0) * 0.5, forward) * MOVE_SWAY_PER_SPEED;
+
+    // Turning: yaw/pitch rate since last frame, lagged behind
+    let (yaw_rate, pitch_rate) = match *last_look {
+        Some((last_yaw, last_pitch)) if dt > 0.0 => {
+            let yaw_delta = (yaw.0 - last_yaw + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+            (yaw_delta / dt, (pitch.0 - last_pitch) / dt)
+        }
+        _ => (0.0, 0.0),
+    };
+    *last_look = Some((yaw.0, pitch.0));
+    let look_offset = Vec3::new(yaw_rate, -pitch_rate, 0.0) * LOOK_SWAY_PER_RATE;
+    let look_offset = look_offset.clamp_length_max(LOOK_SWAY_MAX);
+
+    let mut intensity = settings.weapon_sway;
+    if stance.aiming {
+        intensity *= BREATHING_ADS_FACTOR;
+    }
+    let motion_target = (move_offset + look_offset) * intensity;
+    // Ease toward the offset so the item settles instead of jittering with input
+    *motion_sway += (motion_target - *motion_sway) * (1.0 - (-10.0 * dt).exp());
+
+    for mut transform in view_model_query.iter_mut() {
+        transform.translation = VIEW_MODEL_OFFSET + sway + *motion_sway;
+    }
+}
+
+/// Key that starts the weapon inspect animation.
+pub const INSPECT_KEY: KeyCode = KeyCode::KeyF;
+
+/// Client-only: an inspect animation playing on the view model.
+/// `base_rotation` is the item's resting rotation, restored when it ends.
+#[derive(Component)]
+pub struct InspectAnimation {
+    pub start_time: f32,
+    pub base_rotation: Quat,
+    pub spec: crate::weapons::InspectSpec,
+}
+
+/// Client-only: starts the inspect animation on the held item when
+/// INSPECT_KEY is pressed. Ignored while aiming, downed or typing in chat.
+pub fn start_weapon_inspect(
+    keys: Res<ButtonInput<KeyCode>>,
+    chat_focus: Res<crate::player::ChatFocus>,
+    player_query: Query<&crate::protocol::PlayerStance, (With<lightyear::prelude::Controlled>, Without<crate::protocol::Downed>)>,
+    view_model_query: Query<(Entity, &EquippedItem, &Transform), Without<InspectAnimation>>,
+    mut commands: Commands,
+    time: Res<Time<crate::player::Presentation>>,
+) {
+    if !keys.just_pressed(INSPECT_KEY) || chat_focus.typing { return; }
+    let Ok(stance) = player_query.single() else { return; };
+    if stance.aiming { return; }
+    for (entity, item, transform) in view_model_query.iter() {
+        commands.entity(entity).insert(InspectAnimation {
+            start_time: time.elapsed_secs(),
+            base_rotation: transform.rotation,
+            spec: crate::weapons::inspect_spec(&item.name),
+        });
+    }
+}
+
+/// Client-only: plays the inspect animation on top of the breathing sway.
+/// Firing, aiming, jabbing or moving cancels it and snaps the item back.
+pub fn animate_weapon_inspect(
+    player_query: Query<(&ActionState<PlayerActions>, &crate::protocol::CharacterVelocity, &crate::protocol::PlayerStance), With<lightyear::prelude::Controlled>>,
+    mut view_model_query: Query<(Entity, &mut Transform, &InspectAnimation), With<EquippedItem>>,
+    mut commands: Commands,
+    time: Res<Time<crate::player::Presentation>>,
+) {
+    let Ok((action, velocity, stance)) = player_query.single() else { return; };
+    let interrupted = action.pressed(&PlayerActions::Primary)
+        || action.pressed(&PlayerActions::Jab)
+        || stance.aiming
+        || Vec2::new(velocity.0.x, velocity.0.z).length() >= BREATHING_IDLE_SPEED;
+
+    for (entity, mut transform, anim) in view_model_query.iter_mut() {
+        let t = (time.elapsed_secs() - anim.start_time) / anim.spec.duration;
+        if interrupted || t >= 1.0 {
+            transform.rotation = anim.base_rotation;
+            commands.entity(entity).remove::<InspectAnimation>();
+            continue;
+        }
+
+        // Three phases: raise into the first pose, roll over to the
+        // other side, lower back to rest.
+        let first = (anim.spec.turn, anim.spec.tilt);
+        let second = (-anim.spec.turn * 0.4, -anim.spec.tilt * 0.6);
+        let ((turn, tilt), lift) = if t < 0.25 {
+            let p = smoothstep(t / 0.25);
+            ((first.0 * p, first.1 * p), p)
+        } else if t < 0.7 {
+            let p = smoothstep((t - 0.25) / 0.45);
+            let (turn, tilt, _) = lerp3((first.0, first.1, 0.0), (second.0, second.1, 0.0), p);
+            ((turn, tilt), 1.0)
+        } else {
+            let p = 1.0 - smoothstep((t - 0.7) / 0.3);
+            ((second.0 * p, second.1 * p), p)
+        };
+
+        transform.rotation = Quat::from_euler(EulerRot::YXZ, turn, 0.0, tilt) * anim.base_rotation;
+        transform.translation += Vec3::from_array(anim.spec.lift) * lift;
+    }
+}
+
 /// Client-only: spawns/despawns the FPS view model when PlayerEquipped changes.
 pub fn update_view_model(
     player_query: Query<(&PlayerEquipped, &Children), With<lightyear::prelude::Controlled>>,
@@ -2116,7 +3185,7 @@ pub fn update_view_model(
     let view_model = commands
         .spawn((
             SceneRoot(model_handle),
-            Transform::from_xyz(0.2, -0.15, -0.4)
+            Transform::from_translation(VIEW_MODEL_OFFSET)
                 .with_scale(Vec3::splat(1.0))
                 .with_rotation(model_rot),
             RenderLayers::layer(VIEW_MODEL_RENDER_LAYER),